@@ -2,6 +2,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Generate build metadata
     vergen::EmitBuilder::builder().all_build().all_git().emit()?;
     
+    // Expose the target triple and bundled SWC version for `arc info`
+    println!("cargo:rustc-env=ARC_TARGET_TRIPLE={}", std::env::var("TARGET")?);
+    println!("cargo:rustc-env=ARC_SWC_VERSION={}", locked_version("swc_core").unwrap_or_else(|| "unknown".to_string()));
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    
     // Path to the proto file in the quilt crate
     let proto_file = "../quilt/proto/quilt.proto";
     let proto_dir = "../quilt/proto";
@@ -16,4 +21,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed={}", proto_file);
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Look up the resolved version of a dependency in Cargo.lock
+fn locked_version(package: &str) -> Option<String> {
+    let lock = std::fs::read_to_string("Cargo.lock").ok()?;
+    let name_line = format!("name = \"{}\"", package);
+    let lines: Vec<&str> = lock.lines().map(str::trim).collect();
+    
+    lines.windows(2)
+        .find(|pair| pair[0] == name_line)
+        .and_then(|pair| pair[1].strip_prefix("version = "))
+        .map(|version| version.trim_matches('"').to_string())
+}
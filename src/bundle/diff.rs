@@ -0,0 +1,138 @@
+//! Comparison of two Aria bundles.
+//!
+//! Items are matched by kind and name. An item counts as changed when its
+//! manifest entry differs or the transpiled source behind it has a different
+//! content hash. Manifests can also be compared on their own, by entry only.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::bundle::AriaBundle;
+use crate::compiler::schema::AriaManifest;
+
+/// How an entry differs between the old and new bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+/// A tool, agent, team or pipeline that differs between bundles
+#[derive(Debug, Clone)]
+pub struct ItemChange {
+    pub kind: &'static str,
+    pub name: String,
+    pub change: ChangeKind,
+}
+
+/// A transpiled source file that differs between bundles
+#[derive(Debug, Clone)]
+pub struct SourceChange {
+    pub path: PathBuf,
+    pub change: ChangeKind,
+}
+
+/// Differences between two bundles
+#[derive(Debug, Default)]
+pub struct BundleDiff {
+    pub items: Vec<ItemChange>,
+    pub sources: Vec<SourceChange>,
+}
+
+impl BundleDiff {
+    /// Compare `old` against `new`
+    pub fn between(old: &AriaBundle, new: &AriaBundle) -> Self {
+        let old_hashes = old.source_hashes();
+        let new_hashes = new.source_hashes();
+        let source_hash = |bundle: &AriaBundle, hashes: &BTreeMap<PathBuf, String>, kind: &str, name: &String| {
+            bundle.get_implementation_of(kind, name)
+                .and_then(|i| hashes.get(&i.source_file_path))
+                .cloned()
+        };
+        let code_changed = |kind: &str, name: &String| {
+            source_hash(old, &old_hashes, kind, name) != source_hash(new, &new_hashes, kind, name)
+        };
+
+        let items = diff_manifests(&old.manifest, &new.manifest, code_changed);
+
+        let mut sources = Vec::new();
+        let paths: BTreeSet<&PathBuf> = old_hashes.keys().chain(new_hashes.keys()).collect();
+        for path in paths {
+            let change = match (old_hashes.get(path), new_hashes.get(path)) {
+                (None, Some(_)) => ChangeKind::Added,
+                (Some(_), None) => ChangeKind::Removed,
+                (Some(a), Some(b)) if a != b => ChangeKind::Changed,
+                _ => continue,
+            };
+            sources.push(SourceChange { path: path.clone(), change });
+        }
+
+        Self { items, sources }
+    }
+
+    /// Compare the entries of two manifests, without the code behind them
+    pub fn between_manifests(old: &AriaManifest, new: &AriaManifest) -> Self {
+        Self { items: diff_manifests(old, new, |_, _| false), sources: Vec::new() }
+    }
+
+    /// Whether the two bundles are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty() && self.sources.is_empty()
+    }
+}
+
+/// Compare every kind of manifest entry. An entry present in both also
+/// counts as changed when `code_changed` says so for its kind and name.
+fn diff_manifests(old: &AriaManifest, new: &AriaManifest, code_changed: impl Fn(&str, &String) -> bool) -> Vec<ItemChange> {
+    let mut items = Vec::new();
+    diff_items("tool", &old.tools, &new.tools, |t| &t.name, &code_changed, &mut items);
+    diff_items("agent", &old.agents, &new.agents, |a| &a.name, &code_changed, &mut items);
+    diff_items("team", &old.teams, &new.teams, |t| &t.name, &code_changed, &mut items);
+    diff_items("pipeline", &old.pipelines, &new.pipelines, |p| &p.name, &code_changed, &mut items);
+    diff_items("workflow", &old.workflows, &new.workflows, |w| &w.name, &code_changed, &mut items);
+    items
+}
+
+/// Compare one kind of manifest entry, appending differences to `changes`
+fn diff_items<T: Serialize>(
+    kind: &'static str,
+    old_entries: &[T],
+    new_entries: &[T],
+    name_of: impl Fn(&T) -> &String,
+    code_changed: &impl Fn(&str, &String) -> bool,
+    changes: &mut Vec<ItemChange>,
+) {
+    let old_map: BTreeMap<&String, &T> = old_entries.iter().map(|e| (name_of(e), e)).collect();
+    let new_map: BTreeMap<&String, &T> = new_entries.iter().map(|e| (name_of(e), e)).collect();
+    let names: BTreeSet<&String> = old_map.keys().chain(new_map.keys()).copied().collect();
+
+    for name in names {
+        let change = match (old_map.get(name), new_map.get(name)) {
+            (None, Some(_)) => ChangeKind::Added,
+            (Some(_), None) => ChangeKind::Removed,
+            (Some(a), Some(b)) => {
+                let manifest_changed = serde_json::to_value(a).ok() != serde_json::to_value(b).ok();
+                if manifest_changed || code_changed(kind, name) {
+                    ChangeKind::Changed
+                } else {
+                    continue;
+                }
+            }
+            (None, None) => continue,
+        };
+        changes.push(ItemChange { kind, name: name.clone(), change });
+    }
+}
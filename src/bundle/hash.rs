@@ -0,0 +1,68 @@
+//! Digest algorithms used for bundle integrity hashes.
+
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Inputs at least this large are hashed with blake3 across all cores
+const PARALLEL_HASH_THRESHOLD: usize = 1024 * 1024;
+
+/// Algorithm used for a bundle's `build_hash`, also sent with uploads as `<name>_hash`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    #[default]
+    #[serde(rename = "blake3")]
+    Blake3,
+    #[serde(rename = "sha256")]
+    Sha256,
+}
+
+impl HashAlgorithm {
+    /// Hex-encoded digest of `data`
+    pub fn digest(&self, data: &[u8]) -> String {
+        match self {
+            HashAlgorithm::Blake3 if data.len() >= PARALLEL_HASH_THRESHOLD => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update_rayon(data);
+                hasher.finalize().to_hex().to_string()
+            }
+            HashAlgorithm::Blake3 => blake3::hash(data).to_hex().to_string(),
+            HashAlgorithm::Sha256 => Sha256::digest(data)
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        }
+    }
+
+    /// Name used in config files, bundle metadata and upload requests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Blake3 => "blake3",
+            HashAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+impl fmt::Display for HashAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests_match_known_values() {
+        assert_eq!(HashAlgorithm::Blake3.digest(b"abc"), "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85");
+        assert_eq!(HashAlgorithm::Sha256.digest(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn large_blake3_inputs_hash_the_same_in_parallel() {
+        let data = vec![7u8; PARALLEL_HASH_THRESHOLD + 1];
+
+        assert_eq!(HashAlgorithm::Blake3.digest(&data), blake3::hash(&data).to_hex().to_string());
+    }
+}
@@ -1,17 +1,27 @@
-use anyhow::Result;
+pub mod diff;
+pub mod hash;
+pub mod reader;
+pub mod signature;
+
+use anyhow::{Context, Result};
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 use zip::{ZipWriter, ZipArchive};
-use std::io::{Cursor, Write};
+use std::io::{Cursor, Read, Write};
 use std::fs::File;
 use zip::write::{FileOptions};
 use zip::CompressionMethod;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use blake3;
 
-use crate::compiler::{Implementation, ImplementationDetails};
-use crate::compiler::schema::{AriaManifest, AgentManifest};
+use self::hash::HashAlgorithm;
+
+use crate::compiler::{AriaCompiler, Implementation, ImplementationDetails, SourceLanguage};
+use crate::compiler::schema::{AriaManifest, AgentManifest, ImplementationRecord, SourceEncoding, is_valid_name};
 
 /// Aria bundle containing manifest and implementations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +31,21 @@ pub struct AriaBundle {
     #[serde(skip)]
     pub compiled_code: HashMap<PathBuf, String>,
     pub metadata: BundleMetadata,
+    /// Write archive entries without compression: faster to save, larger on disk
+    #[serde(skip)]
+    pub store_uncompressed: bool,
+    #[serde(skip)]
+    pub manifest_format: ManifestFormat,
+    /// Unix permission bits of file entries; directories are always `0o755`
+    #[serde(skip, default = "default_file_permissions")]
+    pub file_permissions: u32,
+    /// An earlier build of this bundle whose `_sources` entries are copied
+    /// as they are, instead of compressed again, when they hold the same code
+    #[serde(skip)]
+    pub reuse_sources_from: Option<PathBuf>,
+    /// Whether the last save copied every source from `reuse_sources_from`
+    #[serde(skip)]
+    pub sources_reused: bool,
 }
 
 impl AriaBundle {
@@ -30,9 +55,31 @@ impl AriaBundle {
         implementations: Vec<Implementation>,
         compiled_code: HashMap<PathBuf, String>,
     ) -> Result<Self> {
-        let mut impl_map = HashMap::new();
+        // Two implementations writing the same stub would overwrite each other
+        let mut stub_owners: HashMap<String, &Implementation> = HashMap::new();
+        for implementation in &implementations {
+            let stub = stub_path(&implementation.name, &implementation.details);
+            if let Some(existing) = stub_owners.insert(stub.clone(), implementation) {
+                anyhow::bail!(
+                    "Implementations '{}' ({}) and '{}' ({}) both map to bundle stub path '{}'",
+                    existing.name, existing.source_file_path.display(),
+                    implementation.name, implementation.source_file_path.display(),
+                    stub
+                );
+            }
+        }
         
+        // Implementations are looked up by name, so one name cannot be two kinds
+        let mut impl_map: HashMap<String, Implementation> = HashMap::new();
         for implementation in implementations {
+            if let Some(existing) = impl_map.get(&implementation.name) {
+                anyhow::bail!(
+                    "'{}' is defined twice: {} in {}, {} in {}",
+                    implementation.name,
+                    existing.details.kind(), existing.source_file_path.display(),
+                    implementation.details.kind(), implementation.source_file_path.display()
+                );
+            }
             impl_map.insert(implementation.name.clone(), implementation);
         }
         
@@ -41,45 +88,251 @@ impl AriaBundle {
             implementations: impl_map,
             compiled_code,
             metadata: BundleMetadata::new(),
+            store_uncompressed: false,
+            manifest_format: ManifestFormat::default(),
+            file_permissions: DEFAULT_FILE_PERMISSIONS,
+            reuse_sources_from: None,
+            sources_reused: false,
         })
     }
     
+    /// Create a bundle from a `manifest.json` and a directory of pre-transpiled JS.
+    ///
+    /// Each manifest entry is matched to `<impl_dir>/<name>.js`, falling back to
+    /// `<impl_dir>/<kind>/<name>.js` (e.g. `tools/greet.js`).
+    pub async fn from_manifest_and_dir(manifest_path: &Path, impl_dir: &Path) -> Result<Self> {
+        let manifest_content = fs::read_to_string(manifest_path).await
+            .with_context(|| format!("Failed to read manifest: {}", manifest_path.display()))?;
+        let manifest_json: serde_json::Value = serde_json::from_str(&manifest_content)
+            .with_context(|| format!("Failed to parse manifest: {}", manifest_path.display()))?;
+        AriaManifest::validate_json(&manifest_json)
+            .with_context(|| format!("Invalid manifest: {}", manifest_path.display()))?;
+        let manifest: AriaManifest = serde_json::from_value(manifest_json)
+            .with_context(|| format!("Failed to parse manifest: {}", manifest_path.display()))?;
+        manifest.check_version()?;
+        if !is_valid_name(&manifest.name) {
+            anyhow::bail!("Bundle name {:?} in {} is not a safe file name", manifest.name, manifest_path.display());
+        }
+        
+        if !impl_dir.is_dir() {
+            anyhow::bail!("Implementation directory not found: {}", impl_dir.display());
+        }
+        
+        let mut entries: Vec<(&str, String, ImplementationDetails)> = Vec::new();
+        entries.extend(manifest.tools.iter().map(|t| ("tools", t.name.clone(), ImplementationDetails::Tool(t.clone()))));
+        entries.extend(manifest.agents.iter().map(|a| ("agents", a.name.clone(), ImplementationDetails::Agent(a.clone()))));
+        entries.extend(manifest.teams.iter().map(|t| ("teams", t.name.clone(), ImplementationDetails::Team(t.clone()))));
+        entries.extend(manifest.pipelines.iter().map(|p| ("pipelines", p.name.clone(), ImplementationDetails::Pipeline(p.clone()))));
+        entries.extend(manifest.workflows.iter().map(|w| ("workflows", w.name.clone(), ImplementationDetails::Workflow(w.clone()))));
+        
+        let mut implementations = Vec::new();
+        let mut compiled_code = HashMap::new();
+        let mut missing = Vec::new();
+        
+        for (type_dir, name, details) in entries {
+            if !is_valid_name(&name) {
+                anyhow::bail!("Manifest name {:?} in {} is not a safe file name", name, type_dir);
+            }
+            let file_name = format!("{}.js", name);
+            let candidates = [impl_dir.join(&file_name), impl_dir.join(type_dir).join(&file_name)];
+            
+            let Some(js_path) = candidates.into_iter().find(|p| p.is_file()) else {
+                missing.push(format!("{}/{}", type_dir, name));
+                continue;
+            };
+            
+            if !compiled_code.contains_key(&js_path) {
+                let code = fs::read_to_string(&js_path).await?;
+                compiled_code.insert(js_path.clone(), code);
+            }
+            
+            let (source_language, compiler_version) = recorded_provenance(&manifest, &details, &name);
+            implementations.push(Implementation {
+                name,
+                details,
+                source_file_path: js_path,
+                source_language,
+                compiler_version,
+            });
+        }
+        
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "No compiled JavaScript found in {} for:\n - {}",
+                impl_dir.display(),
+                missing.join("\n - ")
+            );
+        }
+        
+        Self::create(manifest, implementations, compiled_code)
+    }
+    
+    /// Combine separately built bundles into one deployable bundle.
+    ///
+    /// The result takes its name and version from the first bundle. Item names
+    /// must be unique across all bundles, and cross-references are resolved
+    /// again over the merged set, so a tool one bundle imported from another
+    /// is no longer external.
+    pub fn merge(bundles: Vec<AriaBundle>) -> Result<AriaBundle> {
+        let Some(first) = bundles.first() else {
+            anyhow::bail!("No bundles to merge");
+        };
+        let mut manifest = AriaManifest {
+            tools: Vec::new(),
+            agents: Vec::new(),
+            teams: Vec::new(),
+            pipelines: Vec::new(),
+            workflows: Vec::new(),
+            externals: Vec::new(),
+            implementations: Vec::new(),
+            ..first.manifest.clone()
+        };
+        let mut metadata = BundleMetadata::new();
+        metadata.hash_algorithm = first.metadata.hash_algorithm;
+        
+        let mut owners: HashMap<String, String> = HashMap::new();
+        let mut collisions = Vec::new();
+        let mut dependency_tools: HashMap<String, Vec<String>> = HashMap::new();
+        let mut implementations = Vec::new();
+        let mut compiled_code: HashMap<PathBuf, String> = HashMap::new();
+        
+        for bundle in bundles {
+            let bundle_name = bundle.manifest.name.clone();
+            let names = bundle.manifest.tools.iter().map(|t| &t.name)
+                .chain(bundle.manifest.agents.iter().map(|a| &a.name))
+                .chain(bundle.manifest.teams.iter().map(|t| &t.name))
+                .chain(bundle.manifest.pipelines.iter().map(|p| &p.name))
+                .chain(bundle.manifest.workflows.iter().map(|w| &w.name));
+            for name in names {
+                if let Some(owner) = owners.insert(name.clone(), bundle_name.clone()) {
+                    collisions.push(format!("'{}' is defined in both {} and {}", name, owner, bundle_name));
+                }
+            }
+            
+            // Externals only stay external if no merged bundle defines them
+            for external in bundle.manifest.externals {
+                let providers = dependency_tools.entry(external.name).or_default();
+                if !providers.contains(&external.bundle) {
+                    providers.push(external.bundle);
+                }
+            }
+            
+            for (path, code) in bundle.compiled_code {
+                if compiled_code.get(&path).is_some_and(|existing| *existing != code) {
+                    anyhow::bail!(
+                        "Bundle {} has different compiled code for {} than an earlier bundle",
+                        bundle_name, path.display()
+                    );
+                }
+                compiled_code.insert(path, code);
+            }
+            
+            manifest.tools.extend(bundle.manifest.tools);
+            manifest.agents.extend(bundle.manifest.agents);
+            manifest.teams.extend(bundle.manifest.teams);
+            manifest.pipelines.extend(bundle.manifest.pipelines);
+            manifest.workflows.extend(bundle.manifest.workflows);
+            implementations.extend(bundle.implementations.into_values());
+            metadata.custom.extend(bundle.metadata.custom);
+        }
+        
+        if !collisions.is_empty() {
+            anyhow::bail!("Cannot merge bundles with colliding names:\n - {}", collisions.join("\n - "));
+        }
+        
+        let (externals, errors) = AriaCompiler::resolve_cross_references(&manifest, &implementations, &dependency_tools);
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Cross-reference validation failed:\n - {}",
+                errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+            );
+        }
+        manifest.externals = externals;
+        
+        let mut merged = Self::create(manifest, implementations, compiled_code)?;
+        merged.metadata = metadata;
+        Ok(merged)
+    }
+    
+    /// `dist/<name>.aria`, where bundles go when no output path is given.
+    /// Fails if the bundle name could place the file outside `dist/`.
+    pub fn default_output_path(&self) -> Result<PathBuf> {
+        if !is_valid_name(&self.manifest.name) {
+            anyhow::bail!("Bundle name {:?} is not a safe file name; pass an output path", self.manifest.name);
+        }
+        Ok(PathBuf::from(format!("dist/{}.aria", self.manifest.name)))
+    }
+    
     /// Save bundle to a .aria file (ZIP format) with a Blake3 hash
-    pub async fn save_to_file(&mut self, path: &PathBuf) -> Result<()> {
-        // Ensure parent directory exists
+    pub async fn save_to_file(&mut self, path: &Path) -> Result<()> {
+        self.save_to_file_staged(path, None).await?;
+        Ok(())
+    }
+    
+    /// Save bundle to `path`, writing it to `staging_dir` first when given
+    /// instead of beside the output. A `{hash}` placeholder in the file name
+    /// is replaced with the build hash; the path actually written is returned.
+    #[tracing::instrument(name = "bundle", skip(self), fields(path = %path.display()))]
+    pub async fn save_to_file_staged(&mut self, path: &Path, staging_dir: Option<&Path>) -> Result<PathBuf> {
+        // Ensure parent and staging directories exist
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).await?;
         }
+        if let Some(dir) = staging_dir {
+            fs::create_dir_all(dir).await?;
+        }
 
         // --- Step 1: Create the main bundle in-memory ---
         let mut bundle_data = Vec::new();
         let cursor = Cursor::new(&mut bundle_data);
         let mut zip = ZipWriter::new(cursor);
         
-        let options: FileOptions<'_, ()> = FileOptions::default()
-            .compression_method(CompressionMethod::Deflated)
-            .unix_permissions(0o755);
+        let options = self.file_options();
         
         // Add manifest.json
+        self.manifest.implementations = self.implementation_records()?;
+        self.manifest.validate_schema()?;
         zip.start_file("manifest.json", options)?;
-        let manifest_json = serde_json::to_string_pretty(&self.manifest)?;
+        let manifest_json = self.manifest_format.to_json(&self.manifest)?;
         zip.write_all(manifest_json.as_bytes())?;
         
         // Add implementation files
-        self.write_implementations(&mut zip, options)?;
+        let (sources, reused) = self.write_implementations(&mut zip, options)?;
+        self.sources_reused = !sources.is_empty() && reused == sources.len();
+        self.metadata.sources = sources;
         
         // Add package.json for dependencies
         let package_json = self.generate_package_json();
         zip.start_file("package.json", options)?;
         zip.write_all(package_json.as_bytes())?;
         
-        // --- Step 2: Calculate Blake3 hash of the main bundle ---
+        // --- Step 2: Hash the main bundle with the configured algorithm ---
         zip.finish()?;
-        let hash = blake3::hash(&bundle_data);
-        self.metadata.build_hash = hash.to_hex().to_string();
+        self.metadata.build_hash = self.metadata.hash_algorithm.digest(&bundle_data);
+
+        // --- Step 3: Write the final file to a temp path, then move it into place ---
+        // The last step is always a rename within the output directory, which is
+        // atomic, so readers never see a half-written bundle.
+        let final_path = hashed_path(path, &self.metadata.build_hash);
+        let temp_path = temp_path_for(path, staging_dir);
+        let written = self.write_final_archive(&temp_path, &bundle_data)
+            .and_then(|_| move_into_place(&temp_path, &final_path));
+        if let Err(e) = written {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e).with_context(|| format!("Failed to write bundle {}", final_path.display()));
+        }
+        
+        tracing::debug!("Wrote bundle {} ({} {})", final_path.display(), self.metadata.hash_algorithm, self.metadata.build_hash);
+        
+        Ok(final_path)
+    }
 
-        // --- Step 3: Create the final file with metadata and the zipped bundle ---
-        let file = std::fs::File::create(path)?;
+    /// Write the outer archive holding metadata/build.json,
+    /// metadata/graph.json and bundle.zip
+    fn write_final_archive(&self, path: &Path, bundle_data: &[u8]) -> Result<()> {
+        let options = self.file_options();
+        
+        let file = File::create(path)?;
         let mut final_zip = ZipWriter::new(file);
 
         // Add metadata/build.json
@@ -87,105 +340,256 @@ impl AriaBundle {
         let metadata_json = serde_json::to_string_pretty(&self.metadata)?;
         final_zip.write_all(metadata_json.as_bytes())?;
         
+        // Add metadata/graph.json, which items reference which
+        final_zip.start_file("metadata/graph.json", options)?;
+        let graph_json = serde_json::to_string_pretty(&self.manifest.dependency_graph())?;
+        final_zip.write_all(graph_json.as_bytes())?;
+        
         // Add the main bundle as a single, raw file
         final_zip.start_file("bundle.zip", options)?;
-        final_zip.write_all(&bundle_data)?;
+        final_zip.write_all(bundle_data)?;
 
-        final_zip.finish()?;
+        final_zip.finish()?.sync_all()?;
         
         Ok(())
     }
 
-    /// Helper to write implementation files to the zip archive
-    fn write_implementations(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>, options: FileOptions<()>) -> Result<()> {
+    /// Hash and size of each implementation's compiled source, ordered by
+    /// kind and name so the manifest is deterministic. Fails if an
+    /// implementation has no compiled code, since its stub would be broken.
+    fn implementation_records(&self) -> Result<Vec<ImplementationRecord>> {
+        let mut records = self.implementations.values()
+            .map(|implementation| {
+                let code = self.compiled_code.get(&implementation.source_file_path)
+                    .with_context(|| format!(
+                        "Implementation '{}' has no compiled code for {}",
+                        implementation.name, implementation.source_file_path.display()
+                    ))?;
+                Ok(ImplementationRecord {
+                    name: implementation.name.clone(),
+                    kind: implementation.details.kind().to_string(),
+                    source_hash: self.metadata.hash_algorithm.digest(code.as_bytes()),
+                    source_size: code.len() as u64,
+                    source_language: implementation.source_language.clone(),
+                    compiler_version: implementation.compiler_version.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        records.sort_by(|a, b| (&a.kind, &a.name).cmp(&(&b.kind, &b.name)));
+        Ok(records)
+    }
+
+    /// Options for the file entries the bundle writes
+    fn file_options(&self) -> FileOptions<'static, ()> {
+        FileOptions::default()
+            .compression_method(self.compression_method())
+            .unix_permissions(self.file_permissions)
+    }
+    
+    /// Compression used for every entry the bundle writes
+    fn compression_method(&self) -> CompressionMethod {
+        if self.store_uncompressed {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        }
+    }
+
+    /// Helper to write implementation files to the zip archive.
+    /// Returns a record of where each transpiled source was stored, and how
+    /// many of them were copied from `reuse_sources_from`.
+    fn write_implementations(&self, zip: &mut ZipWriter<Cursor<&mut Vec<u8>>>, options: FileOptions<()>) -> Result<(Vec<SourceRecord>, usize)> {
         // --- Re-Export Strategy ---
-        // 1. Write all unique, transpiled source files to a `_sources` directory.
-        zip.add_directory("implementations/_sources", options)?;
-        let mut source_map: HashMap<PathBuf, String> = HashMap::new();
-        let mut i = 0;
+        // 1. Write all unique, transpiled source files to a `_sources` directory,
+        //    laid out like the source tree so relative imports between them resolve.
+        zip.add_directory("implementations/_sources", options.unix_permissions(DIRECTORY_PERMISSIONS))?;
+        //    Gzipped sources are stored as `.js.gz` without zip compression on top.
+        //    Entries of an earlier build holding the same code are copied as they are.
+        let source_map = source_bundle_paths(self.compiled_code.keys())?;
+        let mut previous = self.reuse_sources_from.as_deref().and_then(|path| previous_bundle_archive(path).ok());
+        let mut reused = 0;
+        let mut sources = Vec::new();
         for (original_path, code) in &self.compiled_code {
-            let source_bundle_path = format!("implementations/_sources/{}.js", i);
-            zip.start_file(&source_bundle_path, options)?;
-            zip.write_all(code.as_bytes())?;
-            source_map.insert(original_path.clone(), source_bundle_path);
-            i += 1;
+            let source_bundle_path = match self.manifest.source_encoding {
+                SourceEncoding::Identity => source_map[original_path].clone(),
+                SourceEncoding::Gzip => format!("{}.gz", source_map[original_path]),
+            };
+            if let Some(archive) = &mut previous {
+                if self.holds_source(archive, &source_bundle_path, code) {
+                    zip.raw_copy_file(archive.by_name(&source_bundle_path)?)?;
+                    reused += 1;
+                    sources.push(SourceRecord {
+                        bundle_path: source_bundle_path,
+                        source_path: original_path.clone(),
+                    });
+                    continue;
+                }
+            }
+            match self.manifest.source_encoding {
+                SourceEncoding::Identity => {
+                    zip.start_file(&source_bundle_path, options)?;
+                    zip.write_all(code.as_bytes())?;
+                }
+                SourceEncoding::Gzip => {
+                    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+                    encoder.write_all(code.as_bytes())?;
+                    zip.start_file(&source_bundle_path, options.compression_method(CompressionMethod::Stored))?;
+                    zip.write_all(&encoder.finish()?)?;
+                }
+            }
+            sources.push(SourceRecord {
+                bundle_path: source_bundle_path,
+                source_path: original_path.clone(),
+            });
         }
 
-        // 2. Create re-export stubs for each implementation.
+        // 2. Make sure every stub stays inside `implementations/<kind>/`.
+        for (name, implementation) in &self.implementations {
+            if !is_valid_name(name) {
+                anyhow::bail!(
+                    "Implementation name {:?} ({}) is not a safe bundle file name",
+                    name, implementation.source_file_path.display()
+                );
+            }
+        }
+
+        // 3. Create re-export stubs for each implementation.
         for (name, implementation) in &self.implementations {
             if let Some(source_bundle_path) = source_map.get(&implementation.source_file_path) {
-                let (implementation_type_dir, relative_path) = match &implementation.details {
-                    ImplementationDetails::Tool(_) => ("tools", "../../_sources/"),
-                    ImplementationDetails::Agent(_) => ("agents", "../../_sources/"),
-                    ImplementationDetails::Team(_) => ("teams", "../../_sources/"),
-                    ImplementationDetails::Pipeline(_) => ("pipelines", "../../_sources/"),
-                };
-
-                let stub_path = format!("implementations/{}/{}.js", implementation_type_dir, name);
-                let source_file_name = Path::new(source_bundle_path)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("");
-
-                let re_export_content = format!("export * from '{}{}';", relative_path, source_file_name);
+                // Stubs live in `implementations/<kind>/`, beside `_sources/`
+                let stub = stub_path(name, &implementation.details);
+                let source_file_name = source_bundle_path
+                    .strip_prefix("implementations/_sources/")
+                    .unwrap_or(source_bundle_path);
+
+                let re_export_content = format!("export * from '../_sources/{}';", source_file_name);
                 
-                zip.start_file(&stub_path, options)?;
+                zip.start_file(&stub, options)?;
                 zip.write_all(re_export_content.as_bytes())?;
             }
         }
-        Ok(())
+        Ok((sources, reused))
+    }
+
+    /// Whether `archive`, an earlier build, stores `code` at `path` exactly
+    /// as this bundle would write it
+    fn holds_source(&self, archive: &mut ZipArchive<Cursor<Vec<u8>>>, path: &str, code: &str) -> bool {
+        let Ok(mut entry) = archive.by_name(path) else {
+            return false;
+        };
+        let compression = match self.manifest.source_encoding {
+            SourceEncoding::Identity => self.compression_method(),
+            SourceEncoding::Gzip => CompressionMethod::Stored,
+        };
+        if entry.compression() != compression || entry.unix_mode().map(|mode| mode & 0o777) != Some(self.file_permissions & 0o777) {
+            return false;
+        }
+        let mut content = String::new();
+        let read = match self.manifest.source_encoding {
+            SourceEncoding::Identity => entry.read_to_string(&mut content),
+            SourceEncoding::Gzip => GzDecoder::new(entry).read_to_string(&mut content),
+        };
+        read.is_ok() && content == code
     }
     
-    /// Load bundle from a .aria file
-    pub async fn load_from_file(path: &str) -> Result<Self> {
+    /// Read only the build metadata of a bundle file
+    pub async fn read_metadata(path: &str) -> Result<BundleMetadata> {
+        let file = std::fs::File::open(path)?;
+        let mut archive = ZipArchive::new(file)?;
+        let metadata_content = read_archive_file(&mut archive, "metadata/build.json")
+            .with_context(|| format!("{} has no build metadata", path))?;
+        
+        Ok(serde_json::from_str(&metadata_content)?)
+    }
+    
+    /// Total uncompressed size of the files packed in a bundle's `bundle.zip`
+    pub async fn uncompressed_size(path: &Path) -> Result<u64> {
         let file = std::fs::File::open(path)?;
         let mut archive = ZipArchive::new(file)?;
         
-        // Read metadata first
-        let metadata = match archive.by_name("metadata/build.json") {
-            Ok(mut metadata_file) => {
-                let mut metadata_content = String::new();
-                std::io::Read::read_to_string(&mut metadata_file, &mut metadata_content)?;
-                serde_json::from_str(&metadata_content).unwrap_or_else(|_| BundleMetadata::new())
-            }
-            Err(_) => BundleMetadata::new(),
-        };
-
-        // Extract the main bundle zip from the archive
-        let mut bundle_zip_reader = archive.by_name("bundle.zip")?;
         let mut bundle_data = Vec::new();
-        std::io::Read::read_to_end(&mut bundle_zip_reader, &mut bundle_data)?;
-
-        // Verify hash if present
-        if !metadata.build_hash.is_empty() {
-            let expected_hash = blake3::hash(&bundle_data);
-            if expected_hash.to_hex().to_string() != metadata.build_hash {
-                anyhow::bail!("Bundle integrity check failed: blake3 hash mismatch");
-            }
+        std::io::Read::read_to_end(&mut archive.by_name("bundle.zip")?, &mut bundle_data)?;
+        let mut bundle_archive = ZipArchive::new(Cursor::new(bundle_data))?;
+        
+        let mut total = 0;
+        for i in 0..bundle_archive.len() {
+            total += bundle_archive.by_index(i)?.size();
         }
+        Ok(total)
+    }
+    
+    /// Load bundle from a .aria file
+    pub async fn load_from_file(path: &str) -> Result<Self> {
+        let OpenedBundle { metadata, manifest, archive: mut bundle_archive } = open_bundle(path)?;
         
-        // Now, read from the in-memory bundle data
-        let mut bundle_archive = ZipArchive::new(Cursor::new(bundle_data))?;
-
-        // Read manifest
-        let manifest = {
-            let mut manifest_file = bundle_archive.by_name("manifest.json")?;
-            let mut manifest_content = String::new();
-            std::io::Read::read_to_string(&mut manifest_file, &mut manifest_content)?;
-            serde_json::from_str::<AriaManifest>(&manifest_content)?
-        };
+        // Read transpiled sources, keyed by their original path when known
+        let source_paths: HashMap<&str, &PathBuf> = metadata.sources.iter()
+            .map(|record| (record.bundle_path.as_str(), &record.source_path))
+            .collect();
+        let file_names: Vec<String> = bundle_archive.file_names().map(|n| n.to_string()).collect();
+        
+        let mut compiled_code = HashMap::new();
+        let mut bundle_sources: HashMap<String, PathBuf> = HashMap::new();
+        for file_name in &file_names {
+            let Some(js_name) = source_js_name(file_name, manifest.source_encoding) else { continue };
+            
+            let source_path = source_paths.get(file_name.as_str())
+                .map(|p| (*p).clone())
+                .unwrap_or_else(|| PathBuf::from(js_name));
+            let content = read_source(&mut bundle_archive, file_name, manifest.source_encoding)?;
+            bundle_sources.insert(js_name.to_string(), source_path.clone());
+            compiled_code.insert(source_path, content);
+        }
         
-        // Read implementations (basic loading for now)
-        let implementations = HashMap::new();
+        // Rebuild implementations from the re-export stubs
+        let mut implementations = HashMap::new();
+        for file_name in &file_names {
+            let Some((name, details)) = stub_implementation(&manifest, file_name) else { continue };
+            
+            let content = read_archive_file(&mut bundle_archive, file_name)?;
+            let source_file_path = stub_source(file_name, &content)
+                .and_then(|source| bundle_sources.get(&source))
+                .cloned()
+                .unwrap_or_default();
+            
+            // Implementations are looked up by name, so one name cannot be two kinds
+            if let Some(existing) = implementations.get(name).map(|i: &Implementation| i.details.kind()) {
+                anyhow::bail!(
+                    "Bundle {} defines '{}' twice: {} and {}",
+                    path, name, existing, details.kind()
+                );
+            }
+            
+            let (source_language, compiler_version) = recorded_provenance(&manifest, &details, name);
+            implementations.insert(name.to_string(), Implementation {
+                name: name.to_string(),
+                details,
+                source_file_path,
+                source_language,
+                compiler_version,
+            });
+        }
         
         Ok(Self {
             manifest,
             implementations,
-            compiled_code: HashMap::new(),
+            compiled_code,
             metadata,
+            store_uncompressed: false,
+            manifest_format: ManifestFormat::default(),
+            file_permissions: DEFAULT_FILE_PERMISSIONS,
+            reuse_sources_from: None,
+            sources_reused: false,
         })
     }
     
+    /// Blake3 hashes of each transpiled source, keyed by source path
+    pub fn source_hashes(&self) -> BTreeMap<PathBuf, String> {
+        self.compiled_code.iter()
+            .map(|(path, code)| (path.clone(), blake3::hash(code.as_bytes()).to_hex().to_string()))
+            .collect()
+    }
+    
     /// Generate package.json for the bundle
     fn generate_package_json(&self) -> String {
         let package = PackageJson {
@@ -262,11 +666,22 @@ impl AriaBundle {
                 ImplementationDetails::Pipeline(_) => {
                     self.manifest.pipelines.iter().any(|p| &p.name == name)
                 }
+                ImplementationDetails::Workflow(_) => {
+                    self.manifest.workflows.iter().any(|w| &w.name == name)
+                }
             };
             
             if !found_in_manifest {
                 issues.push(format!("Implementation '{}' not found in manifest", name));
             }
+            
+            // Without compiled code the stub would import a file that is not in the bundle
+            if !self.compiled_code.contains_key(&implementation.source_file_path) {
+                issues.push(format!(
+                    "Implementation '{}' has no compiled code for {}",
+                    name, implementation.source_file_path.display()
+                ));
+            }
         }
         
         Ok(issues)
@@ -277,6 +692,27 @@ impl AriaBundle {
     }
 }
 
+/// Layout of the bundle's `manifest.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ManifestFormat {
+    #[default]
+    #[serde(rename = "pretty")]
+    Pretty,
+    /// No whitespace, for size-optimized builds
+    #[serde(rename = "compact")]
+    Compact,
+}
+
+impl ManifestFormat {
+    /// Serialize `manifest` in this format
+    pub fn to_json(&self, manifest: &AriaManifest) -> Result<String> {
+        Ok(match self {
+            ManifestFormat::Pretty => serde_json::to_string_pretty(manifest)?,
+            ManifestFormat::Compact => serde_json::to_string(manifest)?,
+        })
+    }
+}
+
 /// Bundle metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BundleMetadata {
@@ -284,6 +720,23 @@ pub struct BundleMetadata {
     pub compiler_version: String,
     pub source_language: String,
     pub build_hash: String,
+    /// Algorithm `build_hash` was computed with; bundles predating this field used blake3
+    #[serde(default)]
+    pub hash_algorithm: HashAlgorithm,
+    /// Where each transpiled source file is stored inside the bundle
+    #[serde(default)]
+    pub sources: Vec<SourceRecord>,
+    /// User key-values from `build.metadata` and `--metadata`, plus the
+    /// compiler's own git sha; forwarded to Quilt on upload
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, String>,
+}
+
+/// Location of a transpiled source file within the bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRecord {
+    pub bundle_path: String,
+    pub source_path: PathBuf,
 }
 
 impl BundleMetadata {
@@ -293,6 +746,11 @@ impl BundleMetadata {
             compiler_version: env!("CARGO_PKG_VERSION").to_string(),
             source_language: "typescript".to_string(),
             build_hash: "placeholder".to_string(), // TODO: Generate actual hash
+            hash_algorithm: HashAlgorithm::default(),
+            sources: Vec::new(),
+            custom: option_env!("VERGEN_GIT_SHA")
+                .map(|sha| BTreeMap::from([("compiler_git_sha".to_string(), sha.to_string())]))
+                .unwrap_or_default(),
         }
     }
 }
@@ -344,6 +802,11 @@ impl AriaBundle {
         self.implementations.get(name)
     }
     
+    /// The implementation of the `kind` item (e.g. "tool") named `name`
+    pub fn get_implementation_of(&self, kind: &str, name: &str) -> Option<&Implementation> {
+        self.get_implementation(name).filter(|i| i.details.kind() == kind)
+    }
+    
     /// List all tool names
     pub fn list_tools(&self) -> Vec<&str> {
         self.manifest.tools.iter().map(|t| t.name.as_str()).collect()
@@ -355,16 +818,262 @@ impl AriaBundle {
     }
 }
 
+/// Placeholder in an output file name that is replaced with the build hash
+pub const HASH_PLACEHOLDER: &str = "{hash}";
+
+/// Unix permissions of bundle file entries unless `build.file_permissions` is set
+pub const DEFAULT_FILE_PERMISSIONS: u32 = 0o644;
+
+/// Unix permissions of bundle directory entries
+const DIRECTORY_PERMISSIONS: u32 = 0o755;
+
+fn default_file_permissions() -> u32 {
+    DEFAULT_FILE_PERMISSIONS
+}
+
+/// Fill in a `{hash}` placeholder in the file name of `path`
+fn hashed_path(path: &Path, build_hash: &str) -> PathBuf {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) if name.contains(HASH_PLACEHOLDER) => {
+            path.with_file_name(name.replace(HASH_PLACEHOLDER, build_hash))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Path a bundle is written to before being renamed over `path`: inside
+/// `staging_dir` when given, otherwise beside `path`
+pub fn temp_path_for(path: &Path, staging_dir: Option<&Path>) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    match staging_dir {
+        Some(dir) => dir.join(name),
+        None => path.with_file_name(name),
+    }
+}
+
+/// Rename a written bundle over `path`. A staging directory on another
+/// filesystem cannot be renamed across, so the file is then copied beside
+/// `path` first and renamed from there.
+fn move_into_place(staged: &Path, path: &Path) -> Result<()> {
+    let sibling = temp_path_for(path, None);
+    match std::fs::rename(staged, path) {
+        Ok(()) => return Ok(()),
+        Err(e) if staged == sibling => return Err(e.into()),
+        Err(_) => {}
+    }
+    
+    let moved = std::fs::copy(staged, &sibling).and_then(|_| std::fs::rename(&sibling, path));
+    let _ = std::fs::remove_file(staged);
+    if moved.is_err() {
+        let _ = std::fs::remove_file(&sibling);
+    }
+    
+    Ok(moved?)
+}
+
+/// Where each transpiled source is stored in the bundle: its path relative to
+/// the deepest directory holding every source, with a `.js` extension, under
+/// `implementations/_sources/`. Leading `/`, `.` and `..` components are
+/// dropped first, so a merge can store the absolute paths of compiled
+/// bundles next to the relative ones of packaged or older bundles.
+fn source_bundle_paths<'a>(source_paths: impl Iterator<Item = &'a PathBuf> + Clone) -> Result<HashMap<PathBuf, String>> {
+    let storable = |path: &Path| -> PathBuf {
+        path.components().skip_while(|c| !matches!(c, std::path::Component::Normal(_))).collect()
+    };
+    
+    let mut root: Option<PathBuf> = None;
+    for path in source_paths.clone() {
+        let path = storable(path);
+        let mut candidate = root.take().unwrap_or_else(|| path.parent().unwrap_or(Path::new("")).to_path_buf());
+        while !path.starts_with(&candidate) {
+            candidate = candidate.parent().map(Path::to_path_buf).unwrap_or_default();
+        }
+        root = Some(candidate);
+    }
+    let root = root.unwrap_or_default();
+    
+    let mut bundle_paths: HashMap<PathBuf, String> = HashMap::new();
+    let mut owners: HashMap<String, &PathBuf> = HashMap::new();
+    for path in source_paths {
+        let relative = storable(path).strip_prefix(&root)?.with_extension("js");
+        let components: Option<Vec<&str>> = relative.components()
+            .map(|c| match c {
+                std::path::Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        let components = components
+            .ok_or_else(|| anyhow::anyhow!("Cannot store source {} in the bundle", path.display()))?;
+        
+        let bundle_path = format!("implementations/_sources/{}", components.join("/"));
+        if let Some(other) = owners.insert(bundle_path.clone(), path) {
+            anyhow::bail!("Sources {} and {} would both be bundled as {}", other.display(), path.display(), bundle_path);
+        }
+        bundle_paths.insert(path.clone(), bundle_path);
+    }
+    
+    Ok(bundle_paths)
+}
+
+/// Path of the re-export stub written for an implementation
+fn stub_path(name: &str, details: &ImplementationDetails) -> String {
+    let implementation_type_dir = match details {
+        ImplementationDetails::Tool(_) => "tools",
+        ImplementationDetails::Agent(_) => "agents",
+        ImplementationDetails::Team(_) => "teams",
+        ImplementationDetails::Pipeline(_) => "pipelines",
+        ImplementationDetails::Workflow(_) => "workflows",
+    };
+    format!("implementations/{}/{}.js", implementation_type_dir, name)
+}
+
+/// Source language and compiler version `manifest` records for an
+/// implementation. Bundles written before these were recorded, and
+/// hand-written manifests, fall back to TypeScript and an unknown version.
+fn recorded_provenance(manifest: &AriaManifest, details: &ImplementationDetails, name: &str) -> (SourceLanguage, String) {
+    manifest.implementations.iter()
+        .find(|record| record.kind == details.kind() && record.name == name)
+        .map(|record| (record.source_language.clone(), record.compiler_version.clone()))
+        .unwrap_or_default()
+}
+
+/// A .aria file whose build hash has been verified, with its `bundle.zip`
+/// held in memory
+struct OpenedBundle {
+    metadata: BundleMetadata,
+    manifest: AriaManifest,
+    /// Archive of the bundle's `bundle.zip`
+    archive: ZipArchive<Cursor<Vec<u8>>>,
+}
+
+/// The `bundle.zip` archive of the .aria file at `path`, unverified
+fn previous_bundle_archive(path: &Path) -> Result<ZipArchive<Cursor<Vec<u8>>>> {
+    let mut archive = ZipArchive::new(std::fs::File::open(path)?)?;
+    let mut bundle_data = Vec::new();
+    archive.by_name("bundle.zip")?.read_to_end(&mut bundle_data)?;
+    Ok(ZipArchive::new(Cursor::new(bundle_data))?)
+}
+
+/// Open a .aria file, verifying its build hash and reading its manifest
+fn open_bundle(path: &str) -> Result<OpenedBundle> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    
+    // Read metadata first
+    let metadata = match archive.by_name("metadata/build.json") {
+        Ok(mut metadata_file) => {
+            let mut metadata_content = String::new();
+            std::io::Read::read_to_string(&mut metadata_file, &mut metadata_content)?;
+            serde_json::from_str(&metadata_content).unwrap_or_else(|_| BundleMetadata::new())
+        }
+        Err(_) => BundleMetadata::new(),
+    };
+
+    // Extract the main bundle zip from the archive
+    let mut bundle_zip_reader = archive.by_name("bundle.zip")?;
+    let mut bundle_data = Vec::new();
+    std::io::Read::read_to_end(&mut bundle_zip_reader, &mut bundle_data)?;
+
+    // Verify hash if present
+    if !metadata.build_hash.is_empty() {
+        let algorithm = metadata.hash_algorithm;
+        if algorithm.digest(&bundle_data) != metadata.build_hash {
+            anyhow::bail!("Bundle integrity check failed: {} hash mismatch", algorithm);
+        }
+    }
+    
+    // Now, read from the in-memory bundle data
+    let mut bundle_archive = ZipArchive::new(Cursor::new(bundle_data))?;
+
+    // Read manifest
+    let manifest: AriaManifest = serde_json::from_str(&read_archive_file(&mut bundle_archive, "manifest.json")?)?;
+    manifest.check_version()?;
+    
+    Ok(OpenedBundle { metadata, manifest, archive: bundle_archive })
+}
+
+/// The `.js` path stubs import a `_sources/` entry by, or None if the entry
+/// is not a transpiled source. Gzipped sources are stored as `.js.gz`.
+fn source_js_name(file_name: &str, encoding: SourceEncoding) -> Option<&str> {
+    if !file_name.starts_with("implementations/_sources/") {
+        return None;
+    }
+    let js_name = match encoding {
+        SourceEncoding::Identity => Some(file_name),
+        SourceEncoding::Gzip => file_name.strip_suffix(".gz"),
+    };
+    js_name.filter(|n| n.ends_with(".js"))
+}
+
+/// Read the transpiled source stored as `file_name`, decoding it
+fn read_source<R: std::io::Read + std::io::Seek>(archive: &mut ZipArchive<R>, file_name: &str, encoding: SourceEncoding) -> Result<String> {
+    match encoding {
+        SourceEncoding::Identity => read_archive_file(archive, file_name),
+        SourceEncoding::Gzip => {
+            let mut content = String::new();
+            GzDecoder::new(archive.by_name(file_name)?).read_to_string(&mut content)
+                .with_context(|| format!("Failed to gunzip {}", file_name))?;
+            Ok(content)
+        }
+    }
+}
+
+/// Name and manifest entry of the implementation a re-export stub at
+/// `file_name` defines, or None if it is not a stub of a manifest item
+fn stub_implementation<'a>(manifest: &AriaManifest, file_name: &'a str) -> Option<(&'a str, ImplementationDetails)> {
+    let stub = file_name.strip_prefix("implementations/")?;
+    let (type_dir, stub_file) = stub.split_once('/')?;
+    let name = stub_file.strip_suffix(".js")?;
+    
+    let details = match type_dir {
+        "tools" => manifest.tools.iter().find(|t| t.name == name).cloned().map(ImplementationDetails::Tool),
+        "agents" => manifest.agents.iter().find(|a| a.name == name).cloned().map(ImplementationDetails::Agent),
+        "teams" => manifest.teams.iter().find(|t| t.name == name).cloned().map(ImplementationDetails::Team),
+        "pipelines" => manifest.pipelines.iter().find(|p| p.name == name).cloned().map(ImplementationDetails::Pipeline),
+        "workflows" => manifest.workflows.iter().find(|w| w.name == name).cloned().map(ImplementationDetails::Workflow),
+        _ => None,
+    }?;
+    Some((name, details))
+}
+
+/// The bundle entry that the re-export stub at `stub` imports from,
+/// resolving the specifier in its `content` against the stub's directory
+fn stub_source(stub: &str, content: &str) -> Option<String> {
+    let specifier = content.trim().strip_prefix("export * from '")?.strip_suffix("';")?;
+    let mut components: Vec<&str> = stub.split('/').collect();
+    components.pop();
+    for component in specifier.split('/') {
+        match component {
+            "." => {}
+            ".." => { components.pop()?; }
+            component => components.push(component),
+        }
+    }
+    Some(components.join("/"))
+}
+
+/// Read a file from a bundle archive as UTF-8 text
+fn read_archive_file<R: std::io::Read + std::io::Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<String> {
+    let mut file = archive.by_name(name)?;
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut file, &mut content)?;
+    Ok(content)
+}
+
 pub fn create_bundle(manifest: &AriaManifest, implementations: &HashMap<String, String>) -> Result<()> {
+    if let Some(name) = std::iter::once(&manifest.name).chain(implementations.keys()).find(|name| !is_valid_name(name)) {
+        anyhow::bail!("Name {:?} is not a safe bundle file name", name);
+    }
     let bundle_filename = format!("{}.aria", manifest.name);
     let path = Path::new(&bundle_filename);
-    let file = File::create(&path)?;
+    let file = File::create(path)?;
 
     let mut zip = ZipWriter::new(file);
 
     let options: FileOptions<'_, ()> = FileOptions::default()
         .compression_method(CompressionMethod::Deflated)
-        .unix_permissions(0o755);
+        .unix_permissions(DEFAULT_FILE_PERMISSIONS);
 
     zip.start_file("manifest.json", options)?;
     let manifest_json = serde_json::to_string_pretty(manifest)?;
@@ -378,4 +1087,115 @@ pub fn create_bundle(manifest: &AriaManifest, implementations: &HashMap<String,
     zip.finish()?;
 
     Ok(())
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> AriaManifest {
+        serde_json::from_value(serde_json::json!({
+            "name": "app", "version": "1.0.0",
+            "tools": [{ "name": "greet", "description": "greets", "inputs": {} }],
+            "agents": [{ "name": "greet", "description": "greets", "tools": [] }],
+            "teams": [], "pipelines": [],
+        })).unwrap()
+    }
+
+    fn implementation(details: ImplementationDetails, source: &str) -> Implementation {
+        Implementation {
+            name: "greet".to_string(),
+            details,
+            source_file_path: PathBuf::from(source),
+            source_language: SourceLanguage::TypeScript,
+            compiler_version: "0.0.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn implementations_without_compiled_code_are_an_error() {
+        let manifest = manifest();
+        let tool = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/greet.ts");
+
+        let bundle = AriaBundle::create(manifest, vec![tool], HashMap::new()).unwrap();
+        let error = bundle.implementation_records().unwrap_err();
+
+        assert!(error.to_string().contains("'greet' has no compiled code for src/greet.ts"), "{error}");
+    }
+
+    #[test]
+    fn validate_reports_implementations_without_compiled_code() {
+        let manifest = manifest();
+        let tool = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/greet.ts");
+        let compiled = HashMap::from([(PathBuf::from("src/other.ts"), "export {};".to_string())]);
+
+        let bundle = AriaBundle::create(manifest, vec![tool], compiled).unwrap();
+
+        assert_eq!(bundle.validate().unwrap(), ["Implementation 'greet' has no compiled code for src/greet.ts"]);
+    }
+
+    #[test]
+    fn implementations_sharing_a_stub_path_are_an_error() {
+        let manifest = manifest();
+        let first = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/a.ts");
+        let second = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/b.ts");
+
+        let error = AriaBundle::create(manifest, vec![first, second], HashMap::new()).unwrap_err();
+
+        assert!(error.to_string().contains("both map to bundle stub path 'implementations/tools/greet.js'"), "{error}");
+    }
+
+    #[test]
+    fn one_name_for_two_kinds_is_an_error() {
+        let manifest = manifest();
+        let tool = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/a.ts");
+        let agent = implementation(ImplementationDetails::Agent(manifest.agents[0].clone()), "src/b.ts");
+
+        let error = AriaBundle::create(manifest, vec![tool, agent], HashMap::new()).unwrap_err();
+
+        assert!(error.to_string().contains("'greet' is defined twice: tool in src/a.ts, agent in src/b.ts"), "{error}");
+    }
+
+    fn greet_bundle() -> AriaBundle {
+        let manifest = manifest();
+        let tool = implementation(ImplementationDetails::Tool(manifest.tools[0].clone()), "src/greet.ts");
+        let compiled = HashMap::from([(PathBuf::from("src/greet.ts"), "export function greet() {}".to_string())]);
+        AriaBundle::create(manifest, vec![tool], compiled).unwrap()
+    }
+
+    /// Names of the entries in `dir`, sorted
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn failed_saves_leave_no_bundle_or_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory in the way makes moving the written bundle into place fail
+        let output = dir.path().join("app.aria");
+        std::fs::create_dir(&output).unwrap();
+
+        let error = greet_bundle().save_to_file_staged(&output, None).await.unwrap_err();
+
+        assert!(format!("{error:#}").contains("Failed to write bundle"), "{error:#}");
+        assert!(output.is_dir());
+        assert_eq!(entries(dir.path()), ["app.aria"]);
+    }
+
+    #[tokio::test]
+    async fn failed_staged_saves_clean_up_the_staging_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out").join("app.aria");
+        std::fs::create_dir_all(&output).unwrap();
+        let staging = dir.path().join("staging");
+
+        greet_bundle().save_to_file_staged(&output, Some(&staging)).await.unwrap_err();
+
+        assert!(output.is_dir());
+        assert_eq!(entries(&dir.path().join("out")), ["app.aria"]);
+        assert!(entries(&staging).is_empty(), "{:?}", entries(&staging));
+    }
+}
@@ -0,0 +1,161 @@
+//! Streaming access to the implementations in a bundle file.
+//!
+//! `AriaBundle::load_from_file` decodes every transpiled source up front.
+//! A [`BundleReader`] keeps only the compressed `bundle.zip` in memory and
+//! reads each implementation's source as iteration reaches it, so consumers
+//! of very large bundles hold one source at a time.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+use zip::ZipArchive;
+
+use crate::compiler::Implementation;
+use crate::compiler::schema::{AriaManifest, SourceEncoding};
+use super::{BundleMetadata, OpenedBundle, open_bundle, read_archive_file, read_source, recorded_provenance, source_js_name, stub_implementation, stub_source};
+
+/// A bundle file opened for reading implementations one at a time
+pub struct BundleReader {
+    pub manifest: AriaManifest,
+    pub metadata: BundleMetadata,
+    archive: ZipArchive<Cursor<Vec<u8>>>,
+    /// Re-export stub entries, in archive order
+    stubs: Vec<String>,
+    /// Original path of each transpiled source, keyed by the `.js` path stubs import
+    source_paths: HashMap<String, PathBuf>,
+}
+
+impl BundleReader {
+    /// Open a .aria file and verify its build hash. No sources are read yet.
+    pub fn open(path: &str) -> Result<Self> {
+        let OpenedBundle { metadata, manifest, archive } = open_bundle(path)?;
+
+        let recorded: HashMap<&str, &PathBuf> = metadata.sources.iter()
+            .map(|record| (record.bundle_path.as_str(), &record.source_path))
+            .collect();
+        let mut stubs = Vec::new();
+        let mut source_paths = HashMap::new();
+        for file_name in archive.file_names() {
+            if let Some(js_name) = source_js_name(file_name, manifest.source_encoding) {
+                let source_path = recorded.get(file_name)
+                    .map(|p| (*p).clone())
+                    .unwrap_or_else(|| PathBuf::from(js_name));
+                source_paths.insert(js_name.to_string(), source_path);
+            } else if stub_implementation(&manifest, file_name).is_some() {
+                stubs.push(file_name.to_string());
+            }
+        }
+
+        Ok(Self { manifest, metadata, archive, stubs, source_paths })
+    }
+
+    /// Iterate over the bundle's implementations, each with its compiled
+    /// source. A source shared by several implementations is read once for each.
+    pub fn iter_implementations(&mut self) -> Implementations<'_> {
+        Implementations { reader: self, next: 0 }
+    }
+
+    /// Read the implementation defined by the stub at `stub` and its source
+    fn read_implementation(&mut self, stub: &str) -> Result<(Implementation, String)> {
+        let (name, details) = stub_implementation(&self.manifest, stub)
+            .context("Stub does not match a manifest entry")?;
+
+        let content = read_archive_file(&mut self.archive, stub)?;
+        let js_name = stub_source(stub, &content)
+            .with_context(|| format!("{} does not re-export a bundled source", stub))?;
+        let source_file_path = self.source_paths.get(&js_name)
+            .cloned()
+            .with_context(|| format!("{} re-exports {}, which is not in the bundle", stub, js_name))?;
+        let file_name = match self.manifest.source_encoding {
+            SourceEncoding::Identity => js_name,
+            SourceEncoding::Gzip => format!("{}.gz", js_name),
+        };
+        let code = read_source(&mut self.archive, &file_name, self.manifest.source_encoding)?;
+
+        let (source_language, compiler_version) = recorded_provenance(&self.manifest, &details, name);
+        let implementation = Implementation {
+            name: name.to_string(),
+            details,
+            source_file_path,
+            source_language,
+            compiler_version,
+        };
+        Ok((implementation, code))
+    }
+}
+
+/// Iterator over a [`BundleReader`]'s implementations, yielding each with
+/// its compiled source in archive order
+pub struct Implementations<'a> {
+    reader: &'a mut BundleReader,
+    next: usize,
+}
+
+impl Iterator for Implementations<'_> {
+    type Item = Result<(Implementation, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stub = self.reader.stubs.get(self.next)?.clone();
+        self.next += 1;
+        Some(self.reader.read_implementation(&stub))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.reader.stubs.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::AriaBundle;
+    use crate::compiler::{ImplementationDetails, SourceLanguage};
+
+    fn tool(name: &str, source: &str) -> Implementation {
+        let manifest = serde_json::from_value(serde_json::json!({ "name": name, "description": name, "inputs": {} })).unwrap();
+        Implementation {
+            name: name.to_string(),
+            details: ImplementationDetails::Tool(manifest),
+            source_file_path: PathBuf::from(source),
+            source_language: SourceLanguage::TypeScript,
+            compiler_version: "0.0.0".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_each_implementation_with_its_source() {
+        let implementations = vec![tool("greet", "src/greet.ts"), tool("wave", "src/nested/wave.ts"), tool("nod", "src/greet.ts")];
+        let manifest = serde_json::from_value(serde_json::json!({
+            "name": "app", "version": "1.0.0",
+            "tools": implementations.iter().map(|i| match &i.details {
+                ImplementationDetails::Tool(tool) => tool.clone(),
+                _ => unreachable!(),
+            }).collect::<Vec<_>>(),
+            "agents": [], "teams": [], "pipelines": [],
+        })).unwrap();
+        let compiled_code = HashMap::from([
+            (PathBuf::from("src/greet.ts"), "export const greet = 1;".to_string()),
+            (PathBuf::from("src/nested/wave.ts"), "export const wave = 2;".to_string()),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.aria");
+        AriaBundle::create(manifest, implementations, compiled_code).unwrap()
+            .save_to_file(&path).await.unwrap();
+
+        let mut reader = BundleReader::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(reader.iter_implementations().size_hint(), (3, Some(3)));
+        let mut streamed: Vec<(String, PathBuf, String)> = reader.iter_implementations()
+            .map(|item| item.map(|(implementation, code)| (implementation.name, implementation.source_file_path, code)))
+            .collect::<Result<_>>()
+            .unwrap();
+        streamed.sort();
+
+        assert_eq!(streamed, vec![
+            ("greet".to_string(), PathBuf::from("src/greet.ts"), "export const greet = 1;".to_string()),
+            ("nod".to_string(), PathBuf::from("src/greet.ts"), "export const greet = 1;".to_string()),
+            ("wave".to_string(), PathBuf::from("src/nested/wave.ts"), "export const wave = 2;".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Ed25519 signatures over a bundle's build hash.
+//!
+//! A signed bundle carries `metadata/signature` beside `metadata/build.json`
+//! in its outer archive:
+//!
+//! ```json
+//! { "algorithm": "ed25519", "signer": "release@example.com", "signature": "<128 hex digits>" }
+//! ```
+//!
+//! The signature covers the UTF-8 bytes of `build_hash`, which in turn covers
+//! everything in `bundle.zip`.
+
+use anyhow::{Context, Result, anyhow};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Serialize, Deserialize};
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+use super::{read_archive_file, BundleMetadata};
+
+/// Outer archive entry holding the signature
+pub const SIGNATURE_ENTRY: &str = "metadata/signature";
+
+/// Signature stored in a bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSignature {
+    /// Always `ed25519`
+    pub algorithm: String,
+    /// Who signed the bundle, such as an email address or key name
+    pub signer: String,
+    /// Hex-encoded 64-byte signature
+    pub signature: String,
+}
+
+impl BundleSignature {
+    /// Check the signature over `build_hash` against `public_key`
+    pub fn verify(&self, build_hash: &str, public_key: &VerifyingKey) -> Result<()> {
+        if self.algorithm != "ed25519" {
+            return Err(anyhow!("Unsupported signature algorithm '{}'", self.algorithm));
+        }
+        let bytes: [u8; 64] = decode_hex(&self.signature)?
+            .try_into()
+            .map_err(|_| anyhow!("Signature must be 64 bytes"))?;
+        public_key.verify_strict(build_hash.as_bytes(), &Signature::from_bytes(&bytes))
+            .map_err(|_| anyhow!("Signature by '{}' does not match this bundle and key", self.signer))
+    }
+}
+
+/// Recompute the hash of a bundle's `bundle.zip` and check it against its
+/// build metadata. Returns the metadata and the bundle's signature, if any.
+pub fn verify_build_hash(path: &str) -> Result<(BundleMetadata, Option<BundleSignature>)> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = ZipArchive::new(file)?;
+    
+    let metadata: BundleMetadata = serde_json::from_str(&read_archive_file(&mut archive, "metadata/build.json")
+        .with_context(|| format!("{} has no build metadata", path))?)?;
+    
+    let mut bundle_data = Vec::new();
+    archive.by_name("bundle.zip")?.read_to_end(&mut bundle_data)?;
+    let actual = metadata.hash_algorithm.digest(&bundle_data);
+    if actual != metadata.build_hash {
+        return Err(anyhow!(
+            "Bundle contents do not match its build hash: recorded {} {}, computed {}",
+            metadata.hash_algorithm, metadata.build_hash, actual
+        ));
+    }
+    
+    let signature = match archive.by_name(SIGNATURE_ENTRY) {
+        Ok(mut entry) => {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            Some(serde_json::from_str(&content).with_context(|| format!("Invalid {} in {}", SIGNATURE_ENTRY, path))?)
+        }
+        Err(zip::result::ZipError::FileNotFound) => None,
+        Err(e) => return Err(e.into()),
+    };
+    
+    Ok((metadata, signature))
+}
+
+/// Read an ed25519 public key file: 32 raw bytes or 64 hex digits
+pub fn read_public_key(path: &Path) -> Result<VerifyingKey> {
+    let content = std::fs::read(path)
+        .with_context(|| format!("Failed to read public key {}", path.display()))?;
+    let bytes = match content.len() {
+        32 => content,
+        _ => decode_hex(String::from_utf8_lossy(&content).trim())
+            .with_context(|| format!("Invalid public key {}", path.display()))?,
+    };
+    let bytes: [u8; 32] = bytes.try_into()
+        .map_err(|_| anyhow!("Public key {} must be 32 bytes", path.display()))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow!("Invalid public key {}: {}", path.display(), e))
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(anyhow!("expected an even number of hex digits"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow!("invalid hex digit in '{}'", &hex[i..i + 2])))
+        .collect()
+}
@@ -1,55 +1,241 @@
 use clap::ArgMatches;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use fs4::FileExt;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
 
-use crate::cli::{print_status, print_error, print_info, print_warning};
-use crate::compiler::AriaCompiler;
+use crate::cli::{print_status, print_error, print_info, print_warning, ErrorFormat};
+use crate::cli::upload::{determine_daemon_address, upload_bundle, UploadOptions};
+use crate::bundle::{AriaBundle, DEFAULT_FILE_PERMISSIONS};
+use crate::bundle::diff::BundleDiff;
+use crate::cli::diff::print_item_changes;
+use crate::compiler::{AriaCompiler, CompilationResult, STDIN_PATH};
+use crate::compiler::diagnostics::WarningKind;
 use crate::config::ProjectConfig;
 
+/// How often watch mode looks for changed sources
+pub(crate) const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// File locked in the output directory while a build writes to it
+const BUILD_LOCK_FILE_NAME: &str = ".arc-build.lock";
+
+/// Directory at the repository root holding compiled output reused by
+/// `--since`, unless a target directory is set
+pub(crate) const COMPILE_CACHE_DIR_NAME: &str = ".arc-cache";
+
 /// Handle the 'arc build' command
 pub async fn handle_build_command(matches: &ArgMatches) -> Result<()> {
-    let input_path = matches.get_one::<String>("input").unwrap();
+    let input_paths: Vec<&str> = matches.get_many::<String>("input").unwrap().map(|s| s.as_str()).collect();
+    let input_path = input_paths[0];
     let output_path = matches.get_one::<String>("output");
+    let output_template = matches.get_one::<String>("output-template");
     let watch_mode = matches.get_flag("watch");
-    let verbose = matches.get_flag("verbose");
+    let verbose = matches.get_count("verbose") > 0;
+    let profile = matches.get_one::<String>("profile").map(|s| s.as_str());
+    let keep_going = matches.get_flag("keep-going");
+    let frozen = matches.get_flag("frozen");
+    let lock = !matches.get_flag("no-lock") && !matches.get_flag("emit-js-only");
+    let no_upload = matches.get_flag("no-upload");
+    let error_format = ErrorFormat::from_arg(matches.get_one::<String>("error-format"));
+    
+    // --target-dir wins over ARC_TARGET_DIR; with neither, intermediates stay
+    // beside the output bundle
+    let target_dir = matches.get_one::<String>("target-dir").cloned()
+        .or_else(|| std::env::var("ARC_TARGET_DIR").ok().filter(|dir| !dir.is_empty()))
+        .map(PathBuf::from);
+    
+    let stdin_source = if matches.get_flag("stdin") {
+        Some(read_stdin().await?)
+    } else {
+        None
+    };
     
-    print_info(&format!("Building Aria project from: {}", input_path));
+    if stdin_source.is_some() {
+        print_info(&format!("Building Aria source from: {}", STDIN_PATH));
+    } else {
+        print_info(&format!("Building Aria project from: {}", input_paths.join(", ")));
+    }
     
     // Load project configuration
-    let config = load_project_config(input_path).await?;
+    let config_file = matches.get_one::<String>("config").map(Path::new);
+    let config = load_project_config(input_path, config_file, profile, frozen).await?;
+    let auto_upload = config.deploy.auto_upload.unwrap_or(false) && !no_upload;
     
     // Determine output path
-    let output = determine_output_path(output_path, &config, input_path)?;
+    let output = determine_output_path(output_path, output_template, &config, input_path)?;
+    
+    // --metadata entries override build.metadata keys of the same name
+    let mut metadata = config.build.metadata.clone();
+    for entry in matches.get_many::<String>("metadata").into_iter().flatten() {
+        let (key, value) = parse_metadata_entry(entry)?;
+        metadata.insert(key, value);
+    }
+    
+    // Initialize compiler
+    let mut compiler = AriaCompiler::new()
+        .keep_going(keep_going)
+        .target_dir(target_dir.clone())
+        .hash_algorithm(config.build.hash_algorithm.unwrap_or_default())
+        .source_encoding(config.build.source_encoding.unwrap_or_default())
+        .manifest_format(config.manifest_format())
+        .file_permissions(config.build.file_permissions.unwrap_or(DEFAULT_FILE_PERMISSIONS))
+        .dependencies(config.build.dependencies.iter().map(PathBuf::from).collect())
+        .exclude(config.build.exclude.clone())
+        .compress(!matches.get_flag("no-compress"))
+        .emit_js_dir(matches.get_one::<String>("emit-js-dir").map(PathBuf::from))
+        .emit_js_only(matches.get_flag("emit-js-only"))
+        .include_js(matches.get_flag("include-js"))
+        .stdin_source(stdin_source)
+        .metadata(metadata)
+        .validate_references(!matches.get_flag("no-validate"))
+        .check_packages(matches.get_flag("check-deps"))
+        .lint_unused_imports(matches.get_flag("lint-unused-imports"))
+        .only(matches.get_many::<String>("only").into_iter().flatten().cloned().collect())
+        .features(matches.get_many::<String>("features").into_iter().flatten().cloned().collect());
+    if let Some(&jobs) = matches.get_one::<u64>("jobs") {
+        compiler = compiler.jobs(jobs as usize);
+    }
+    if let Some(git_ref) = matches.get_one::<String>("since") {
+        let (root, changed) = changed_files_since(&input_paths, git_ref)?;
+        print_info(&format!("Recompiling files changed since {} ({} changed)", git_ref, changed.len()));
+        let cache_dir = target_dir.map_or_else(|| root.join(COMPILE_CACHE_DIR_NAME), |dir| dir.join("cache"));
+        compiler = compiler.incremental(cache_dir, changed);
+    }
+    
+    // Read the reference bundle up front, so a bad path fails before building
+    let deployed_manifest = match matches.get_one::<String>("compare-manifest") {
+        Some(path) => Some(AriaBundle::load_from_file(path).await
+            .map_err(|e| anyhow!("Failed to read --compare-manifest bundle {}: {}", path, e))?
+            .manifest),
+        None => None,
+    };
     
     if watch_mode {
         print_info("Starting watch mode...");
-        start_watch_mode(input_path, &output, verbose).await?;
+        start_watch_mode(&compiler, &input_paths, &output, verbose, error_format, lock).await?;
     } else {
-        build_project(input_path, &output, verbose).await?;
+        // Held until the build finishes, so a concurrent build of the same
+        // output waits instead of racing on it
+        let build_lock = if lock { Some(lock_output_dir(&output).await?) } else { None };
+        let result = build_project(&compiler, &input_paths, &output, verbose, error_format).await?;
+        drop(build_lock);
+        
+        if let Some(bundle_path) = result.output_path.as_ref().filter(|_| auto_upload) {
+            let address = determine_daemon_address(None, Some(config.clone())).await?;
+            upload_bundle(&bundle_path.to_string_lossy(), &address, UploadOptions::default()).await?;
+        }
+        
+        if let Some(deployed) = deployed_manifest {
+            let diff = BundleDiff::between_manifests(&deployed, &result.manifest);
+            if diff.is_empty() {
+                print_status("Identical", "Manifest matches the --compare-manifest bundle");
+            } else {
+                print_item_changes(&diff.items);
+                // Fail so the build can gate deploys
+                return Err(anyhow!("{} item(s) differ from the --compare-manifest bundle", diff.items.len()));
+            }
+        }
     }
     
     Ok(())
 }
 
-/// Load project configuration from aria.toml
-async fn load_project_config(input_path: &str) -> Result<ProjectConfig> {
-    let config_path = find_config_file(input_path)?;
+/// Split a `--metadata key=value` entry
+fn parse_metadata_entry(entry: &str) -> Result<(String, String)> {
+    match entry.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+        _ => Err(anyhow!("Invalid --metadata entry '{}': expected key=value", entry)),
+    }
+}
+
+/// Read a single source file from standard input
+pub(crate) async fn read_stdin() -> Result<String> {
+    let mut source = String::new();
+    tokio::io::stdin().read_to_string(&mut source).await
+        .map_err(|e| anyhow!("Failed to read source from stdin: {}", e))?;
+    Ok(source)
+}
+
+/// Load project configuration from aria.toml, applying the selected profile.
+/// When `frozen`, a missing aria.toml or required field is an error instead
+/// of falling back to defaults.
+pub(crate) async fn load_project_config(input_path: &str, config_file: Option<&Path>, profile: Option<&str>, frozen: bool) -> Result<ProjectConfig> {
+    let (config, config_path) = resolve_project_config(input_path, config_file, profile).await?;
     
-    match config_path {
-        Some(path) => {
-            print_info(&format!("Found configuration: {}", path.display()));
-            ProjectConfig::load_from_file(&path).await
+    if frozen {
+        let path = config_path.as_ref()
+            .ok_or_else(|| anyhow!("No aria.toml found for {} (required by --frozen)", input_path))?;
+        let missing = ProjectConfig::missing_required_fields(path).await?;
+        if !missing.is_empty() {
+            return Err(anyhow!(
+                "{} does not set required fields (required by --frozen): {}",
+                path.display(),
+                missing.join(", ")
+            ));
         }
-        None => {
-            print_warning("No aria.toml found, using default configuration");
+    }
+    
+    match config_path {
+        Some(path) => print_info(&format!("Found configuration: {}", path.display())),
+        None => print_warning("No aria.toml found, using default configuration"),
+    }
+    
+    if let Some(name) = profile {
+        print_info(&format!("Using profile: {}", name));
+    }
+    
+    Ok(config)
+}
+
+/// Configuration for commands that can run without an aria.toml. An
+/// aria.toml found by searching upward that fails to load is reported as
+/// a warning, and defaults are used instead; an explicit `config_file` must load.
+pub(crate) async fn optional_project_config(input_path: &str, config_file: Option<&Path>) -> Result<ProjectConfig> {
+    if config_file.is_some() {
+        return Ok(resolve_project_config(input_path, config_file, None).await?.0);
+    }
+    let Some(path) = find_config_file(input_path)? else {
+        return Ok(ProjectConfig::default());
+    };
+    match ProjectConfig::load_from_file(&path).await {
+        Ok(config) => Ok(config),
+        Err(e) => {
+            print_warning(&format!("Ignoring {}, using default configuration: {}", path.display(), e));
             Ok(ProjectConfig::default())
         }
     }
 }
 
+/// Resolve the effective project configuration without printing anything.
+/// `config_file` is loaded instead of searching upward from `input_path`
+/// for aria.toml. Returns the config along with the file it was loaded
+/// from, if any.
+pub(crate) async fn resolve_project_config(input_path: &str, config_file: Option<&Path>, profile: Option<&str>) -> Result<(ProjectConfig, Option<PathBuf>)> {
+    let config_path = match config_file {
+        Some(path) if path.is_file() => Some(path.to_path_buf()),
+        Some(path) => return Err(anyhow!("Config file not found: {}", path.display())),
+        None => find_config_file(input_path)?,
+    };
+    
+    let config = match &config_path {
+        Some(path) => ProjectConfig::load_from_file(path).await?,
+        None => ProjectConfig::default(),
+    };
+    
+    let config = match profile {
+        Some(name) => config.with_profile(name)?,
+        None => config,
+    };
+    
+    Ok((config, config_path))
+}
+
 /// Find aria.toml configuration file
-fn find_config_file(start_path: &str) -> Result<Option<PathBuf>> {
+pub(crate) fn find_config_file(start_path: &str) -> Result<Option<PathBuf>> {
     let mut current = Path::new(start_path).canonicalize()?;
     
     loop {
@@ -65,9 +251,59 @@ fn find_config_file(start_path: &str) -> Result<Option<PathBuf>> {
     }
 }
 
+/// Files changed since `git_ref` in the git repository containing every
+/// input path, as absolute paths: modified, staged and untracked files.
+/// Also returns the repository root.
+pub(crate) fn changed_files_since(input_paths: &[&str], git_ref: &str) -> Result<(PathBuf, HashSet<PathBuf>)> {
+    let root = repository_root(input_paths[0])?;
+    // Changes are only listed for one repository, so files in another would look unchanged
+    for input_path in &input_paths[1..] {
+        if repository_root(input_path)? != root {
+            return Err(anyhow!(
+                "--since needs every input in one git repository, but {} is outside {}",
+                input_path, root.display()
+            ));
+        }
+    }
+    
+    run_git(&root, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", git_ref)])
+        .map_err(|_| anyhow!("--since: '{}' is not a valid git ref", git_ref))?;
+    
+    // Both list paths relative to the root, NUL-separated to avoid quoting
+    let diff = run_git(&root, &["diff", "--name-only", "-z", git_ref, "--"])?;
+    let untracked = run_git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    let changed = diff.split('\0').chain(untracked.split('\0'))
+        .filter(|name| !name.is_empty())
+        .map(|name| root.join(name))
+        .collect();
+    
+    Ok((root, changed))
+}
+
+/// Root of the git repository containing `start_path`
+fn repository_root(start_path: &str) -> Result<PathBuf> {
+    let start = Path::new(start_path).canonicalize()?;
+    let start = if start.is_file() { start.parent().unwrap_or(&start).to_path_buf() } else { start };
+    
+    let root = run_git(&start, &["rev-parse", "--show-toplevel"])
+        .map_err(|e| anyhow!("--since requires a git repository: {}", e))?;
+    Ok(Path::new(root.trim()).canonicalize()?)
+}
+
+/// Run git in `dir` and return its stdout, or its stderr as the error
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").args(args).current_dir(dir).output()
+        .map_err(|e| anyhow!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(anyhow!("{}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Determine the output path for the .aria bundle
 fn determine_output_path(
     output_arg: Option<&String>, 
+    output_template: Option<&String>,
     config: &ProjectConfig, 
     input_path: &str
 ) -> Result<PathBuf> {
@@ -75,8 +311,12 @@ fn determine_output_path(
         return Ok(PathBuf::from(output));
     }
     
+    if let Some(template) = output_template {
+        return Ok(PathBuf::from(config.render_output_template(template)?));
+    }
+    
     if let Some(output) = &config.build.output {
-        return Ok(PathBuf::from(output));
+        return Ok(PathBuf::from(config.render_output_template(output)?));
     }
     
     // Default: create output based on input directory name
@@ -89,17 +329,42 @@ fn determine_output_path(
     Ok(PathBuf::from(format!("dist/{}.aria", dir_name)))
 }
 
-/// Build the project once
-async fn build_project(input_path: &str, output_path: &PathBuf, verbose: bool) -> Result<()> {
+/// Take an exclusive lock on the directory `output_path` is written to,
+/// waiting for any other build holding it. The lock is released when the
+/// returned file is dropped, including when the process exits.
+async fn lock_output_dir(output_path: &Path) -> Result<File> {
+    let dir = output_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    tokio::fs::create_dir_all(dir).await?;
+    let lock_path = dir.join(BUILD_LOCK_FILE_NAME);
+    let file = std::fs::OpenOptions::new().create(true).write(true).truncate(false).open(&lock_path)
+        .map_err(|e| anyhow!("Failed to open build lock {}: {}", lock_path.display(), e))?;
+    
+    if file.try_lock_exclusive().is_ok() {
+        return Ok(file);
+    }
+    
+    print_info(&format!("Another build is in progress; waiting for lock {}", lock_path.display()));
+    tokio::task::spawn_blocking(move || file.lock_exclusive().map(|_| file)).await?
+        .map_err(|e| anyhow!("Failed to lock {}: {}", lock_path.display(), e))
+}
+
+/// Build the project once. The result's `output_path` is the bundle written, if any.
+async fn build_project(compiler: &AriaCompiler, input_paths: &[&str], output_path: &Path, verbose: bool, error_format: ErrorFormat) -> Result<CompilationResult> {
     let start_time = Instant::now();
     
     print_status("Compiling", "TypeScript sources...");
     
-    // Initialize compiler
-    let compiler = AriaCompiler::new();
+    // Compile the project, cleaning up after ourselves if interrupted
+    let compilation = tokio::select! {
+        result = compiler.compile_project(input_paths, output_path) => result,
+        _ = tokio::signal::ctrl_c() => {
+            remove_partial_output(&compiler.staging_path(output_path));
+            print_error("Build cancelled");
+            std::process::exit(130);
+        }
+    };
     
-    // Compile the project
-    match compiler.compile_project(input_path, output_path, verbose).await {
+    match compilation {
         Ok(result) => {
             let duration = start_time.elapsed();
             
@@ -108,39 +373,76 @@ async fn build_project(input_path: &str, output_path: &PathBuf, verbose: bool) -
                 duration.as_secs_f64()
             ));
             
-            print_info(&format!("Bundle created: {}", output_path.display()));
+            // Without an output path only JavaScript was emitted (--emit-js-only)
+            match &result.output_path {
+                Some(bundle_path) => print_info(&format!("Bundle created: {}", bundle_path.display())),
+                None => print_info("Bundle skipped (--emit-js-only)"),
+            }
             print_info(&format!("  - Tools: {}", result.tools_count));
             print_info(&format!("  - Agents: {}", result.agents_count));
             print_info(&format!("  - Teams: {}", result.teams_count));
             print_info(&format!("  - Pipelines: {}", result.pipelines_count));
-            print_info(&format!("Bundle size: {:.2} KB", result.bundle_size_kb));
+            print_info(&format!("  - Workflows: {}", result.workflows_count));
+            if result.excluded_count > 0 {
+                print_info(&format!("  - Excluded by --features: {}", result.excluded_count));
+            }
+            if result.output_path.is_some() {
+                print_info(&format!("Bundle size: {:.2} KB", result.bundle_size_kb));
+            }
+            
+            // These are errors unless --no-validate was given, so show them even without -v
+            for warning in result.warnings.iter().filter(|w| w.kind == WarningKind::UnresolvedReference) {
+                print_warning(&warning.to_string());
+            }
             
             if verbose {
                 print_diagnostics(&result);
             }
+            
+            Ok(result)
         }
         Err(e) => {
-            print_error(&format!("Build failed: {}", e));
-            return Err(e);
+            error_format.report("Build failed", &e)?;
+            Err(e)
         }
     }
-    
-    Ok(())
 }
 
-/// Start watch mode for continuous building
-async fn start_watch_mode(_input_path: &str, _output_path: &PathBuf, _verbose: bool) -> Result<()> {
-    print_info("Watch mode not yet implemented");
-    print_info("For now, use: arc build ./src");
-    
-    // TODO: Implement file watching with notify crate
-    // This would:
-    // 1. Watch for changes in input_path
-    // 2. Debounce rapid changes
-    // 3. Rebuild on changes
-    // 4. Show incremental build times
+/// Remove whatever an interrupted build left behind. The bundle is written to
+/// a staging file and renamed into place, so only that file can be partial;
+/// a bundle renamed in before the interrupt is complete and is kept.
+fn remove_partial_output(staging_path: &Path) {
+    let _ = std::fs::remove_file(staging_path);
+}
+
+/// Rebuild whenever a source file changes, until interrupted. Each rebuild
+/// holds the output directory lock when `lock` is set, so a watcher and a
+/// manual build of the same output take turns instead of racing.
+async fn start_watch_mode(
+    compiler: &AriaCompiler,
+    input_paths: &[&str],
+    output_path: &Path,
+    verbose: bool,
+    error_format: ErrorFormat,
+    lock: bool,
+) -> Result<()> {
+    let mut last_hashes = BTreeMap::new();
     
-    Ok(())
+    loop {
+        let hashes = compiler.source_hashes(input_paths).await?;
+        
+        if hashes != last_hashes {
+            let build_lock = if lock { Some(lock_output_dir(output_path).await?) } else { None };
+            // A failed rebuild is already reported; keep watching for a fix
+            let _ = build_project(compiler, input_paths, output_path, verbose, error_format).await;
+            drop(build_lock);
+            
+            last_hashes = hashes;
+            print_info("Watching for changes...");
+        }
+        
+        tokio::time::sleep(WATCH_INTERVAL).await;
+    }
 }
 
 /// Print detailed build diagnostics
@@ -148,21 +450,31 @@ fn print_diagnostics(result: &crate::compiler::CompilationResult) {
     if !result.warnings.is_empty() {
         print_warning(&format!("Build completed with {} warnings:", result.warnings.len()));
         for warning in &result.warnings {
-            println!("    - {}", warning);
+            print_warning(&format!("  - {}", warning));
         }
     }
     
-    if verbose_enabled() {
-        print_info("Detailed build information:");
-        println!("    - Source files processed: {}", result.source_files_count);
-        println!("    - Dependencies resolved: {}", result.dependencies_count);
-        println!("    - Compilation time: {:.2}s", result.compilation_time_secs);
-        println!("    - Bundle compression: {:.1}%", result.compression_ratio * 100.0);
+    print_info("Detailed build information:");
+    if result.manifest_only_rebuild {
+        print_info("  - Manifest-only rebuild: every source reused from the previous bundle");
+    }
+    print_info(&format!("  - Source files discovered: {}", result.discovered_files_count));
+    print_info(&format!("  - Source files compiled: {}", result.source_files_count));
+    print_info(&format!("  - Source files skipped: {}", result.skipped_files_count));
+    print_info(&format!("  - Dependencies resolved: {}", result.dependencies_count));
+    print_info(&format!("  - Compilation time: {:.2}s", result.compilation_time_secs));
+    print_info(&format!(
+        "  - Bundle contents: {:.2} KB uncompressed, {:.2} KB on disk ({:.1}%)",
+        result.uncompressed_size_kb,
+        result.bundle_size_kb,
+        result.compression_ratio * 100.0
+    ));
+    if result.manifest_bytes_saved > 0 {
+        print_info(&format!("  - Compact manifest: {} bytes smaller than pretty", result.manifest_bytes_saved));
+    }
+    
+    print_info("Phase timings:");
+    for (phase, secs) in &result.phase_timings {
+        print_info(&format!("  - {}: {:.3}s", phase, secs));
     }
 }
-
-/// Check if verbose mode is enabled (stub for now)
-fn verbose_enabled() -> bool {
-    // TODO: Pass verbose flag through properly
-    false
-} 
\ No newline at end of file
@@ -1,41 +1,334 @@
 use clap::ArgMatches;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
-use crate::cli::{print_status, print_error, print_info};
-use crate::compiler::AriaCompiler;
+use crate::cli::{print_status, print_error, print_info, print_warning, ErrorFormat};
+use crate::cli::build::{changed_files_since, load_project_config, optional_project_config, read_stdin, COMPILE_CACHE_DIR_NAME, WATCH_INTERVAL};
+use crate::compiler::{AriaCompiler, CompilationResult, CompiledFile, STDIN_PATH};
+use crate::compiler::diagnostics::{Warning, WarningKind};
 
 /// Handle the 'arc check' command
 pub async fn handle_check_command(matches: &ArgMatches) -> Result<()> {
-    let input_path = matches.get_one::<String>("input").unwrap();
-    let verbose = matches.get_flag("verbose");
-
-    let start_time = Instant::now();
+    let input_paths: Vec<&str> = matches.get_many::<String>("input").unwrap().map(|s| s.as_str()).collect();
+    let input_path = input_paths[0];
+    let verbose = matches.get_count("verbose") > 0;
+    let profile = matches.get_one::<String>("profile").map(|s| s.as_str());
+    let keep_going = matches.get_flag("keep-going");
+    let frozen = matches.get_flag("frozen");
+    let error_format = ErrorFormat::from_arg(matches.get_one::<String>("error-format"));
+    let filter_tag = matches.get_one::<String>("filter-tag").map(|s| s.as_str());
+    
+    let stdin_source = if matches.get_flag("stdin") {
+        Some(read_stdin().await?)
+    } else {
+        None
+    };
+    
+    if stdin_source.is_some() {
+        print_info(&format!("Checking Aria source from: {}", STDIN_PATH));
+    } else {
+        print_info(&format!("Checking Aria project in: {}", input_paths.join(", ")));
+    }
     
-    print_info(&format!("Checking Aria project in: {}", input_path));
+    // Only report on configuration when a profile or --frozen was requested;
+    // otherwise read it quietly so a plain check works without a valid aria.toml
+    let config_file = matches.get_one::<String>("config").map(Path::new);
+    let config = if profile.is_some() || frozen {
+        load_project_config(input_path, config_file, profile, frozen).await?
+    } else {
+        optional_project_config(input_path, config_file).await?
+    };
     
-    let compiler = AriaCompiler::new();
+    let mut compiler = AriaCompiler::new()
+        .keep_going(keep_going)
+        .strict(matches.get_flag("strict"))
+        .dump_ast(matches.get_flag("dump-ast"))
+        .lint_unused_imports(matches.get_flag("lint-unused-imports"))
+        .include_js(matches.get_flag("include-js"))
+        .only(matches.get_many::<String>("only").into_iter().flatten().cloned().collect())
+        .features(matches.get_many::<String>("features").into_iter().flatten().cloned().collect())
+        .stdin_source(stdin_source)
+        .dependencies(config.build.dependencies.iter().map(PathBuf::from).collect())
+        .exclude(config.build.exclude.clone());
+    if let Some(&jobs) = matches.get_one::<u64>("jobs") {
+        compiler = compiler.jobs(jobs as usize);
+    }
+    if let Some(git_ref) = matches.get_one::<String>("since") {
+        let (root, changed) = changed_files_since(&input_paths, git_ref)?;
+        print_info(&format!("Rechecking files changed since {} ({} changed)", git_ref, changed.len()));
+        compiler = compiler.incremental(root.join(COMPILE_CACHE_DIR_NAME), changed);
+    }
+    
+    if matches.get_flag("list-files") {
+        return list_files(&compiler, &input_paths, matches.get_flag("json")).await;
+    }
     
-    match compiler.check_project(input_path, verbose).await {
+    if matches.get_flag("watch") {
+        return watch_check(&compiler, &input_paths, verbose, matches.get_flag("all"), filter_tag, error_format).await;
+    }
+    
+    let start_time = Instant::now();
+    
+    // Print what the visitor found before validation, which may fail
+    let emit = matches.get_flag("emit-items");
+    match compiler.check_project_with(&input_paths, &mut |files| if emit { emit_items(files) } else { Ok(()) }).await {
         Ok(result) => {
-            let duration = start_time.elapsed();
+            let warnings: Vec<&Warning> = result.warnings.iter().collect();
+            print_check_result(&result, start_time, verbose, &warnings, filter_tag);
             
-            print_status("Finished", &format!(
-                "Check completed in {:.2}s", 
-                duration.as_secs_f64()
-            ));
-            
-            print_info("Project analysis:");
-            print_info(&format!("  - Tools: {}", result.tools_count));
-            print_info(&format!("  - Agents: {}", result.agents_count));
-            print_info(&format!("  - Teams: {}", result.teams_count));
-            print_info(&format!("  - Pipelines: {}", result.pipelines_count));
+            if let Some(&max_warnings) = matches.get_one::<u64>("max-warnings") {
+                if result.warnings.len() as u64 > max_warnings {
+                    let message = format!(
+                        "{} warnings exceed the budget of {} set by --max-warnings",
+                        result.warnings.len(), max_warnings
+                    );
+                    print_error(&message);
+                    return Err(anyhow!(message));
+                }
+            }
         }
         Err(e) => {
-            print_error(&format!("Check failed: {}", e));
+            error_format.report("Check failed", &e)?;
             return Err(e);
         }
     }
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Re-run the check whenever a source file changes, until interrupted.
+/// Unless `show_all`, warnings are only printed for files that changed since
+/// their warnings were last shown.
+async fn watch_check(
+    compiler: &AriaCompiler,
+    input_paths: &[&str],
+    verbose: bool,
+    show_all: bool,
+    filter_tag: Option<&str>,
+    error_format: ErrorFormat,
+) -> Result<()> {
+    let mut cache = WarningCache::default();
+    let mut last_hashes = BTreeMap::new();
+    
+    loop {
+        let hashes = compiler.source_hashes(input_paths).await?;
+        
+        if hashes != last_hashes {
+            let start_time = Instant::now();
+            
+            match compiler.check_project(input_paths).await {
+                Ok(result) => {
+                    let (warnings, unchanged): (Vec<&Warning>, usize) = if show_all {
+                        (result.warnings.iter().collect(), 0)
+                    } else {
+                        cache.filter(&result.warnings, &hashes)
+                    };
+                    
+                    print_check_result(&result, start_time, verbose, &warnings, filter_tag);
+                    
+                    if unchanged > 0 {
+                        print_info(&format!("{} unchanged warnings", unchanged));
+                    }
+                }
+                Err(e) => error_format.report("Check failed", &e)?,
+            }
+            
+            last_hashes = hashes;
+            print_info("Watching for changes...");
+        }
+        
+        tokio::time::sleep(WATCH_INTERVAL).await;
+    }
+}
+
+/// Print the items extracted from each source file as JSON on stdout
+fn emit_items(compiled: &[CompiledFile]) -> Result<()> {
+    let files: Vec<serde_json::Value> = compiled.iter()
+        .map(|file| serde_json::json!({ "file": file.source.path, "items": file.items }))
+        .collect();
+    
+    println!("{}", serde_json::to_string_pretty(&files)?);
+    
+    Ok(())
+}
+
+/// Print the source files discovery finds, one per line or as a JSON array
+async fn list_files(compiler: &AriaCompiler, input_paths: &[&str], json: bool) -> Result<()> {
+    let paths = compiler.source_paths(input_paths).await?;
+    
+    if json {
+        println!("{}", serde_json::to_string_pretty(&paths)?);
+    } else {
+        for path in &paths {
+            println!("{}", path.display());
+        }
+    }
+    
+    Ok(())
+}
+
+/// Print the outcome of a successful check, showing only `warnings` in full
+/// and, with `filter_tag`, the tools and agents carrying that tag
+fn print_check_result(result: &CompilationResult, start_time: Instant, verbose: bool, warnings: &[&Warning], filter_tag: Option<&str>) {
+    let duration = start_time.elapsed();
+    
+    print_status("Finished", &format!(
+        "Check completed in {:.2}s",
+        duration.as_secs_f64()
+    ));
+    
+    print_info("Project analysis:");
+    print_info(&format!("  - Tools: {}", result.tools_count));
+    print_info(&format!("  - Agents: {}", result.agents_count));
+    print_info(&format!("  - Teams: {}", result.teams_count));
+    print_info(&format!("  - Pipelines: {}", result.pipelines_count));
+    print_info(&format!("  - Workflows: {}", result.workflows_count));
+    if result.excluded_count > 0 {
+        print_info(&format!("  - Excluded by --features: {}", result.excluded_count));
+    }
+    
+    if let Some(tag) = filter_tag {
+        print_tagged_items(result, tag);
+    }
+    
+    for warning in warnings {
+        print_warning(&warning.message);
+    }
+    
+    print_warning_summary(&result.warnings);
+    
+    if verbose {
+        print_info("Source files:");
+        print_info(&format!("  - Discovered: {}", result.discovered_files_count));
+        print_info(&format!("  - Compiled: {}", result.source_files_count));
+        print_info(&format!("  - Skipped: {}", result.skipped_files_count));
+        print_info("Phase timings:");
+        for (phase, secs) in &result.phase_timings {
+            print_info(&format!("  - {}: {:.3}s", phase, secs));
+        }
+    }
+}
+
+/// List the tools and agents tagged with `tag`
+fn print_tagged_items(result: &CompilationResult, tag: &str) {
+    let tools: Vec<&str> = result.manifest.tools.iter()
+        .filter(|tool| tool.tags.iter().any(|t| t == tag))
+        .map(|tool| tool.name.as_str())
+        .collect();
+    let agents: Vec<&str> = result.manifest.agents.iter()
+        .filter(|agent| agent.tags.iter().any(|t| t == tag))
+        .map(|agent| agent.name.as_str())
+        .collect();
+    
+    print_info(&format!("Tagged '{}':", tag));
+    for tool in &tools {
+        print_info(&format!("  - tool {}", tool));
+    }
+    for agent in &agents {
+        print_info(&format!("  - agent {}", agent));
+    }
+    if tools.is_empty() && agents.is_empty() {
+        print_info("  - (none)");
+    }
+}
+
+/// Print warning counts grouped by category, followed by the total
+fn print_warning_summary(warnings: &[Warning]) {
+    if warnings.is_empty() {
+        return;
+    }
+    
+    let mut counts: BTreeMap<WarningKind, usize> = BTreeMap::new();
+    for warning in warnings {
+        *counts.entry(warning.kind).or_default() += 1;
+    }
+    
+    print_info("Warning summary:");
+    for (kind, count) in &counts {
+        print_info(&format!("  - {}: {}", kind, count));
+    }
+    print_info(&format!("  - Total: {}", warnings.len()));
+}
+
+/// Content hash of each file at the time its warnings were last printed,
+/// along with those warnings
+#[derive(Default)]
+struct WarningCache {
+    reported: HashMap<PathBuf, (String, HashSet<(WarningKind, String)>)>,
+}
+
+impl WarningCache {
+    /// Split `warnings` into those to print and a count of those already
+    /// printed for an unchanged file. A warning is only held back if the same
+    /// warning was printed for the same content; warnings not tied to a file
+    /// always print.
+    fn filter<'a>(&mut self, warnings: &'a [Warning], hashes: &BTreeMap<PathBuf, String>) -> (Vec<&'a Warning>, usize) {
+        let mut shown = Vec::new();
+        let mut unchanged = 0;
+        
+        for warning in warnings {
+            let current = warning.file.as_ref().and_then(|file| hashes.get(file));
+            let previous = warning.file.as_ref().and_then(|file| self.reported.get(file));
+            match (current, previous) {
+                (Some(current), Some((hash, reported)))
+                    if current == hash && reported.contains(&(warning.kind, warning.message.clone())) => unchanged += 1,
+                _ => shown.push(warning),
+            }
+        }
+        
+        let mut reported: HashMap<PathBuf, (String, HashSet<(WarningKind, String)>)> = HashMap::new();
+        for warning in warnings {
+            let Some(file) = &warning.file else { continue };
+            let Some(hash) = hashes.get(file) else { continue };
+            reported.entry(file.clone())
+                .or_insert_with(|| (hash.clone(), HashSet::new()))
+                .1
+                .insert((warning.kind, warning.message.clone()));
+        }
+        self.reported = reported;
+        
+        (shown, unchanged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn warning(file: &str, message: &str) -> Warning {
+        Warning::new(WarningKind::UnresolvedReference, message).in_file(Path::new(file))
+    }
+    
+    /// Messages of the warnings `cache` shows, and how many it holds back
+    fn shown(cache: &mut WarningCache, warnings: &[Warning], hashes: &BTreeMap<PathBuf, String>) -> (Vec<String>, usize) {
+        let (shown, unchanged) = cache.filter(warnings, hashes);
+        (shown.iter().map(|w| w.message.clone()).collect(), unchanged)
+    }
+    
+    #[test]
+    fn only_new_warnings_for_unchanged_files_are_shown() {
+        let hashes = BTreeMap::from([(PathBuf::from("a.ts"), "1".to_string())]);
+        let mut cache = WarningCache::default();
+        
+        let first = [warning("a.ts", "old")];
+        assert_eq!(shown(&mut cache, &first, &hashes), (vec!["old".to_string()], 0));
+        assert_eq!(shown(&mut cache, &first, &hashes), (vec![], 1));
+        
+        // Another file changed and made a new warning appear in a.ts
+        let second = [warning("a.ts", "old"), warning("a.ts", "new")];
+        assert_eq!(shown(&mut cache, &second, &hashes), (vec!["new".to_string()], 1));
+        assert_eq!(shown(&mut cache, &second, &hashes), (vec![], 2));
+    }
+    
+    #[test]
+    fn changed_files_show_all_their_warnings() {
+        let mut cache = WarningCache::default();
+        let warnings = [warning("a.ts", "old")];
+        cache.filter(&warnings, &BTreeMap::from([(PathBuf::from("a.ts"), "1".to_string())]));
+        
+        let changed = BTreeMap::from([(PathBuf::from("a.ts"), "2".to_string())]);
+        assert_eq!(shown(&mut cache, &warnings, &changed), (vec!["old".to_string()], 0));
+    }
+}
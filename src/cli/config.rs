@@ -0,0 +1,32 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+
+use crate::cli::build::resolve_project_config;
+
+/// Handle the 'arc config' command
+pub async fn handle_config_command(matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        Some(("show", sub_matches)) => handle_config_show(sub_matches).await,
+        _ => unreachable!(),
+    }
+}
+
+/// Print the effective configuration that `arc build` would use
+async fn handle_config_show(matches: &ArgMatches) -> Result<()> {
+    let input_path = matches.get_one::<String>("input").unwrap();
+    let profile = matches.get_one::<String>("profile").map(|s| s.as_str());
+    let json = matches.get_flag("json");
+    
+    let (config, _) = resolve_project_config(input_path, None, profile).await?;
+    
+    let output = if json {
+        serde_json::to_string_pretty(&config)?
+    } else {
+        toml::to_string_pretty(&config)
+            .map_err(|e| anyhow!("Failed to serialize config: {}", e))?
+    };
+    
+    println!("{}", output);
+    
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+
+use crate::cli::{print_status, print_info};
+use crate::bundle::AriaBundle;
+use crate::bundle::diff::{BundleDiff, ItemChange};
+
+/// Handle the 'arc diff' command
+pub async fn handle_diff_command(matches: &ArgMatches) -> Result<()> {
+    let old_path = matches.get_one::<String>("old").unwrap();
+    let new_path = matches.get_one::<String>("new").unwrap();
+    
+    print_info(&format!("Comparing {} -> {}", old_path, new_path));
+    
+    let old_bundle = AriaBundle::load_from_file(old_path).await?;
+    let new_bundle = AriaBundle::load_from_file(new_path).await?;
+    
+    let diff = BundleDiff::between(&old_bundle, &new_bundle);
+    
+    if diff.is_empty() {
+        print_status("Identical", "No differences between bundles");
+        return Ok(());
+    }
+    
+    print_item_changes(&diff.items);
+    
+    if !diff.sources.is_empty() {
+        print_info("Source files:");
+        for source in &diff.sources {
+            println!("    - {} {}", source.change, source.path.display());
+        }
+    }
+    
+    // Fail so the command can gate deploys
+    Err(anyhow!(
+        "Bundles differ: {} implementation(s) and {} source file(s) changed",
+        diff.items.len(),
+        diff.sources.len()
+    ))
+}
+
+/// List changed implementations, if there are any
+pub(crate) fn print_item_changes(items: &[ItemChange]) {
+    if !items.is_empty() {
+        print_info("Implementations:");
+        for item in items {
+            println!("    - {} {} '{}'", item.change, item.kind, item.name);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+use std::process::Command;
+
+use crate::cli::{print_status, print_info, print_error, print_warning};
+use crate::cli::build::resolve_project_config;
+use crate::cli::upload::determine_daemon_address;
+use crate::grpc::QuiltClient;
+
+/// Result of one environment check
+enum Outcome {
+    Pass(String),
+    /// Something that limits what arc can do, with a remediation hint
+    Warn(String, String),
+    /// Something that stops projects from building or running, with a remediation hint
+    Fail(String, String),
+}
+
+/// Handle the 'arc doctor' command
+pub async fn handle_doctor_command(matches: &ArgMatches) -> Result<()> {
+    let input_path = matches.get_one::<String>("input").unwrap();
+    
+    let checks = [
+        ("arc", Outcome::Pass(format!(
+            "{} ({})",
+            env!("CARGO_PKG_VERSION"),
+            option_env!("VERGEN_GIT_SHA").unwrap_or("unknown")
+        ))),
+        ("git", check_tool("git", "Install git to create projects with `arc new`", false)),
+        ("bun", check_tool("bun", "Install Bun from https://bun.sh; bundles run on it", true)),
+        ("node", check_tool("node", "Install Node.js if your tools need it; Bun alone is enough otherwise", false)),
+        ("aria.toml", check_config(input_path).await),
+        ("quilt", check_quilt_socket(input_path, matches.get_one::<String>("socket")).await),
+    ];
+    
+    let mut failures = 0;
+    for (name, outcome) in checks {
+        match outcome {
+            Outcome::Pass(detail) => print_status("ok", &format!("{}: {}", name, detail)),
+            Outcome::Warn(detail, hint) => {
+                print_warning(&format!("{}: {}", name, detail));
+                print_info(&format!("  hint: {}", hint));
+            }
+            Outcome::Fail(detail, hint) => {
+                failures += 1;
+                print_error(&format!("{}: {}", name, detail));
+                print_info(&format!("  hint: {}", hint));
+            }
+        }
+    }
+    
+    if failures > 0 {
+        return Err(anyhow!("{} check(s) failed", failures));
+    }
+    
+    Ok(())
+}
+
+/// Check that `program --version` runs, reporting the first line it prints
+fn check_tool(program: &str, hint: &str, required: bool) -> Outcome {
+    match Command::new(program).arg("--version").output() {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout);
+            Outcome::Pass(version.lines().next().unwrap_or_default().trim().to_string())
+        }
+        Ok(output) => {
+            let detail = format!("`{} --version` exited with {}", program, output.status);
+            if required { Outcome::Fail(detail, hint.to_string()) } else { Outcome::Warn(detail, hint.to_string()) }
+        }
+        Err(_) => {
+            let detail = format!("{} not found on PATH", program);
+            if required { Outcome::Fail(detail, hint.to_string()) } else { Outcome::Warn(detail, hint.to_string()) }
+        }
+    }
+}
+
+/// Check that an aria.toml is found above `input_path` and parses
+async fn check_config(input_path: &str) -> Outcome {
+    match resolve_project_config(input_path, None, None).await {
+        Ok((config, Some(path))) => match config.validate() {
+            Ok(()) => Outcome::Pass(path.display().to_string()),
+            Err(e) => Outcome::Fail(
+                format!("{} is invalid: {}", path.display(), e),
+                "Fix the reported field, or run `arc upgrade` if it was written by an older arc".to_string(),
+            ),
+        },
+        Ok((_, None)) => Outcome::Warn(
+            format!("no aria.toml found above {}", input_path),
+            "Run `arc new <name>` to create a project, or pass the project directory".to_string(),
+        ),
+        Err(e) => Outcome::Fail(
+            format!("failed to load configuration: {}", e),
+            "Fix the syntax error in aria.toml".to_string(),
+        ),
+    }
+}
+
+/// Check that the Quilt daemon `arc upload` would use accepts connections
+async fn check_quilt_socket(input_path: &str, socket_arg: Option<&String>) -> Outcome {
+    // An unreadable aria.toml is reported by the config check
+    let config = resolve_project_config(input_path, None, None).await.ok().map(|(config, _)| config);
+    let address = match determine_daemon_address(socket_arg, config).await {
+        Ok(address) => address,
+        Err(e) => return Outcome::Warn(
+            format!("could not determine the daemon address: {}", e),
+            "Pass --socket or set ARC_QUILT_SOCKET".to_string(),
+        ),
+    };
+    
+    match QuiltClient::connect_to(&address).await {
+        Ok(_) => Outcome::Pass(format!("daemon reachable at {}", address)),
+        Err(e) => Outcome::Warn(
+            e.to_string(),
+            "Start the Quilt daemon, or point --socket / ARC_QUILT_SOCKET at it; only `arc upload` needs it".to_string(),
+        ),
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+
+use crate::cli::{print_error, print_info};
+use crate::compiler::diagnostics::Diagnostic;
+
+/// How a failed build or check reports its errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// Colored terminal output
+    Human,
+    /// GitHub Actions `::error` workflow commands on stdout
+    Github,
+    /// A JSON array of diagnostics on stdout
+    Json,
+}
+
+impl ErrorFormat {
+    /// Parse the `--error-format` value; clap restricts it to the known names
+    pub fn from_arg(value: Option<&String>) -> Self {
+        match value.map(|s| s.as_str()) {
+            Some("github") => ErrorFormat::Github,
+            Some("json") => ErrorFormat::Json,
+            _ => ErrorFormat::Human,
+        }
+    }
+
+    /// Report a failed command. `summary` prefixes the human-readable form.
+    pub fn report(&self, summary: &str, error: &anyhow::Error) -> Result<()> {
+        match self {
+            ErrorFormat::Human => {
+                print_error(&format!("{}: {}", summary, error));
+                
+                let code = Diagnostic::from_error(error).into_iter().find_map(|diagnostic| diagnostic.code);
+                if let Some(code) = code {
+                    print_info(&format!("For more information about an error, try `arc explain {}`", code));
+                }
+            }
+            ErrorFormat::Github => {
+                for diagnostic in Diagnostic::from_error(error) {
+                    println!("{}", github_command(&diagnostic));
+                }
+            }
+            ErrorFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&Diagnostic::from_error(error))?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Render a diagnostic as a GitHub Actions `::error` workflow command
+fn github_command(diagnostic: &Diagnostic) -> String {
+    let mut properties = Vec::new();
+    if let Some(file) = &diagnostic.file {
+        // Annotations resolve paths against the workspace root
+        let file = std::env::current_dir().ok()
+            .and_then(|cwd| file.strip_prefix(cwd).ok().map(|p| p.to_path_buf()))
+            .unwrap_or_else(|| file.clone());
+        properties.push(format!("file={}", escape_property(&file.display().to_string())));
+    }
+    if let Some(line) = diagnostic.line {
+        properties.push(format!("line={}", line));
+    }
+    if let Some(code) = diagnostic.code {
+        properties.push(format!("title={}", code));
+    }
+
+    let properties = if properties.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", properties.join(","))
+    };
+
+    format!("::error{}::{}", properties, escape_data(&diagnostic.message))
+}
+
+/// Escape a workflow command message
+fn escape_data(value: &str) -> String {
+    value.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
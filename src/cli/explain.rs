@@ -0,0 +1,19 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+
+use crate::compiler::diagnostics::DiagnosticCode;
+
+/// Handle the 'arc explain' command
+pub async fn handle_explain_command(matches: &ArgMatches) -> Result<()> {
+    let code = matches.get_one::<String>("code").unwrap();
+    
+    let code = DiagnosticCode::parse(code).ok_or_else(|| {
+        let known: Vec<&str> = DiagnosticCode::ALL.iter().map(|c| c.as_str()).collect();
+        anyhow!("Unknown diagnostic code '{}' (known codes: {})", code, known.join(", "))
+    })?;
+    
+    println!("{}\n", code);
+    println!("{}", code.explanation());
+    
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use clap::ArgMatches;
+use anyhow::Result;
+
+/// Handle the 'arc info' command
+pub async fn handle_info_command(matches: &ArgMatches) -> Result<()> {
+    let json = matches.get_flag("json");
+    
+    let fields = [
+        ("version", env!("CARGO_PKG_VERSION")),
+        ("git_sha", option_env!("VERGEN_GIT_SHA").unwrap_or("unknown")),
+        ("build_timestamp", option_env!("VERGEN_BUILD_TIMESTAMP").unwrap_or("unknown")),
+        ("target", env!("ARC_TARGET_TRIPLE")),
+        ("swc_core", env!("ARC_SWC_VERSION")),
+    ];
+    
+    if json {
+        let info: serde_json::Map<String, serde_json::Value> = fields.iter()
+            .map(|(key, value)| (key.to_string(), serde_json::Value::String(value.to_string())))
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else {
+        println!("arc {}", env!("CARGO_PKG_VERSION"));
+        for (key, value) in &fields[1..] {
+            println!("    {:<16} {}", key, value);
+        }
+    }
+    
+    Ok(())
+}
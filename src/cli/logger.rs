@@ -1,33 +1,154 @@
+use anyhow::{Result, anyhow};
 use console;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing::{Event, Level, Subscriber};
+use tracing::field::{Field, Visit};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
 
 /// Print status message with proper formatting
 pub fn print_status(status: &str, message: &str) {
-    println!("    {} {}", 
-        console::style(status).bold().green(), 
-        message
-    );
+    tracing::info!(status = status, "{}", message);
 }
 
 /// Print error message with proper formatting
 pub fn print_error(message: &str) {
-    eprintln!("    {} {}", 
-        console::style("error").bold().red(), 
-        message
-    );
+    tracing::error!("{}", message);
 }
 
 /// Print warning message with proper formatting
 pub fn print_warning(message: &str) {
-    println!("    {} {}", 
-        console::style("warning").bold().yellow(), 
-        message
-    );
+    tracing::warn!("{}", message);
 }
 
 /// Print info message with proper formatting
 pub fn print_info(message: &str) {
-    println!("    {} {}", 
-        console::style("info").bold().blue(), 
-        message
-    );
-} 
\ No newline at end of file
+    tracing::info!("{}", message);
+}
+
+/// Install the CLI subscriber, mapping `-v` occurrences to tracing levels
+/// (none: info, `-v`: debug, `-vv` and above: trace). With `to_stderr` every
+/// event goes to stderr, keeping stdout free for machine-readable output.
+/// With `log_file`, every event is also written to that file, truncating it.
+pub fn init_logging(verbosity: u8, to_stderr: bool, log_file: Option<&Path>) -> Result<()> {
+    let max_level = match verbosity {
+        0 => Level::INFO,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+
+    // Only render our own events; dependencies such as tonic and h2 emit
+    // their own debug/trace output that is not meant for the terminal
+    let filter = filter_fn(move |metadata| {
+        metadata.target().starts_with(env!("CARGO_CRATE_NAME")) && *metadata.level() <= max_level
+    });
+
+    let file_layer = match log_file {
+        Some(path) => {
+            let layer = FileLayer::create(path)
+                .map_err(|e| anyhow!("Failed to create log file {}: {}", path.display(), e))?;
+            Some(layer.with_filter(filter.clone()))
+        }
+        None => None,
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(CliLayer::new(to_stderr).with_filter(filter))
+        .with(file_layer)
+        .try_init();
+    Ok(())
+}
+
+/// Subscriber layer that renders events in the human-facing CLI format
+pub struct CliLayer {
+    to_stderr: bool,
+}
+
+impl CliLayer {
+    /// Create a layer; errors always go to stderr, other events only when `to_stderr` is set
+    pub fn new(to_stderr: bool) -> Self {
+        Self { to_stderr }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for CliLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = CliFields::default();
+        event.record(&mut fields);
+
+        let level = *event.metadata().level();
+        let label = event_label(level, fields.status);
+
+        if level == Level::ERROR || self.to_stderr {
+            eprintln!("    {} {}", label, fields.message);
+        } else {
+            println!("    {} {}", label, fields.message);
+        }
+    }
+}
+
+/// Subscriber layer that writes events to a log file in the CLI format, without colors
+pub struct FileLayer {
+    // Unbuffered, so nothing is lost when a command exits early with `process::exit`
+    file: Mutex<File>,
+}
+
+impl FileLayer {
+    /// Create or truncate the log file at `path`
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(Self { file: Mutex::new(File::create(path)?) })
+    }
+}
+
+impl<S: Subscriber> Layer<S> for FileLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = CliFields::default();
+        event.record(&mut fields);
+
+        let label = event_label(*event.metadata().level(), fields.status).force_styling(false);
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "    {} {}", label, fields.message);
+        }
+    }
+}
+
+/// Label printed before an event's message
+fn event_label(level: Level, status: Option<String>) -> console::StyledObject<String> {
+    match (level, status) {
+        (Level::ERROR, _) => console::style("error".to_string()).bold().red(),
+        (Level::WARN, _) => console::style("warning".to_string()).bold().yellow(),
+        (Level::INFO, Some(status)) => console::style(status).bold().green(),
+        (Level::INFO, None) => console::style("info".to_string()).bold().blue(),
+        (_, _) => console::style(level.as_str().to_lowercase()).dim(),
+    }
+}
+
+/// Fields recorded from a single event
+#[derive(Default)]
+struct CliFields {
+    message: String,
+    status: Option<String>,
+}
+
+impl Visit for CliFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message = value.to_string(),
+            "status" => self.status = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.message = format!("{:?}", value),
+            "status" => self.status = Some(format!("{:?}", value)),
+            _ => {}
+        }
+    }
+}
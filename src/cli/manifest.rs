@@ -0,0 +1,44 @@
+use clap::ArgMatches;
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::cli::{print_error, print_warning};
+use crate::cli::build::{load_project_config, optional_project_config};
+use crate::compiler::AriaCompiler;
+
+/// Handle the 'arc manifest' command
+///
+/// Stdout carries only the manifest JSON; all log output is routed to stderr
+/// when the logger is installed for this command.
+pub async fn handle_manifest_command(matches: &ArgMatches) -> Result<()> {
+    let input_paths: Vec<&str> = matches.get_many::<String>("input").unwrap().map(|s| s.as_str()).collect();
+    let input_path = input_paths[0];
+    let profile = matches.get_one::<String>("profile").map(|s| s.as_str());
+    let keep_going = matches.get_flag("keep-going");
+
+    let config = match profile {
+        Some(_) => load_project_config(input_path, None, profile, false).await?,
+        None => optional_project_config(input_path, None).await?,
+    };
+
+    let compiler = AriaCompiler::new()
+        .keep_going(keep_going)
+        .dependencies(config.build.dependencies.iter().map(PathBuf::from).collect())
+        .exclude(config.build.exclude.clone());
+
+    match compiler.check_project(&input_paths).await {
+        Ok(result) => {
+            for warning in &result.warnings {
+                print_warning(&warning.message);
+            }
+
+            println!("{}", serde_json::to_string_pretty(&result.manifest)?);
+        }
+        Err(e) => {
+            print_error(&format!("Manifest generation failed: {}", e));
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
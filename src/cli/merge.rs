@@ -0,0 +1,45 @@
+use clap::ArgMatches;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::cli::{print_status, print_info};
+use crate::bundle::AriaBundle;
+
+/// Handle the 'arc merge' command
+pub async fn handle_merge_command(matches: &ArgMatches) -> Result<()> {
+    let paths: Vec<&String> = matches.get_many::<String>("bundles").unwrap().collect();
+    
+    let start_time = Instant::now();
+    
+    print_info(&format!("Merging {} bundles", paths.len()));
+    
+    let mut bundles = Vec::new();
+    for path in &paths {
+        bundles.push(AriaBundle::load_from_file(path).await?);
+    }
+    let mut bundle = AriaBundle::merge(bundles)?;
+    
+    let output = match matches.get_one::<String>("output") {
+        Some(output) => PathBuf::from(output),
+        None => bundle.default_output_path()?,
+    };
+    
+    bundle.save_to_file(&output).await?;
+    
+    print_status("Finished", &format!(
+        "Merge completed in {:.2}s", 
+        start_time.elapsed().as_secs_f64()
+    ));
+    print_info(&format!("Bundle created: {}", output.display()));
+    print_info(&format!("  - Tools: {}", bundle.manifest.tools.len()));
+    print_info(&format!("  - Agents: {}", bundle.manifest.agents.len()));
+    print_info(&format!("  - Teams: {}", bundle.manifest.teams.len()));
+    print_info(&format!("  - Pipelines: {}", bundle.manifest.pipelines.len()));
+    print_info(&format!("  - Workflows: {}", bundle.manifest.workflows.len()));
+    if !bundle.manifest.externals.is_empty() {
+        print_info(&format!("  - External references: {}", bundle.manifest.externals.len()));
+    }
+    
+    Ok(())
+}
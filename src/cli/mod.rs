@@ -1,11 +1,33 @@
 pub mod build;
 pub mod check;
+pub mod config;
+pub mod diff;
+pub mod doctor;
+pub mod explain;
+pub mod info;
+pub mod manifest;
+pub mod merge;
 pub mod new;
+pub mod package;
+pub mod upgrade;
 pub mod upload;
+pub mod verify;
+mod error_format;
 mod logger;
 
 pub use self::build::handle_build_command;
 pub use self::check::handle_check_command;
+pub use self::config::handle_config_command;
+pub use self::diff::handle_diff_command;
+pub use self::doctor::handle_doctor_command;
+pub use self::explain::handle_explain_command;
+pub use self::info::handle_info_command;
+pub use self::manifest::handle_manifest_command;
+pub use self::merge::handle_merge_command;
 pub use self::new::handle_new_command;
+pub use self::package::handle_package_command;
+pub use self::upgrade::handle_upgrade_command;
 pub use self::upload::handle_upload_command;
-pub use self::logger::{print_info, print_status, print_error, print_warning}; 
\ No newline at end of file
+pub use self::verify::handle_verify_command;
+pub use self::error_format::ErrorFormat;
+pub use self::logger::{print_info, print_status, print_error, print_warning, init_logging, CliLayer}; 
\ No newline at end of file
@@ -1,5 +1,7 @@
 use clap::ArgMatches;
 use anyhow::{Result, anyhow, Context};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
@@ -51,7 +53,8 @@ async fn create_project_structure(project_name: &str, _template: &str) -> Result
     // ├── config/
     // │   └── package.json
     // ├── aria.toml
-    // └── llm.xml
+    // ├── llm.xml
+    // └── .gitignore
     
     fs::create_dir_all(base_path.join("src"))
         .context("Failed to create src/ directory")?;
@@ -86,13 +89,20 @@ async fn create_project_structure(project_name: &str, _template: &str) -> Result
         &project_class_name
     )?;
     
-    // Create llm.xml from template (optional but included by default)
-    create_file_from_template(
-        &base_path.join("llm.xml"),
+    // Create llm.xml from template (optional but included by default),
+    // with the names escaped for XML
+    let llm_xml = render_template(
         include_str!("../templates/llm.xml.template"),
-        project_name,
-        &project_class_name
-    )?;
+        &quick_xml::escape::escape(project_name),
+        &quick_xml::escape::escape(&project_class_name)
+    );
+    check_well_formed_xml(&llm_xml).context("Generated llm.xml is not well-formed XML")?;
+    fs::write(base_path.join("llm.xml"), llm_xml)
+        .context("Failed to create file: llm.xml")?;
+    
+    // Keep dependencies, build output and the incremental compile cache out of git
+    fs::write(base_path.join(".gitignore"), include_str!("../templates/gitignore.template"))
+        .context("Failed to create file: .gitignore")?;
     
     // Initialize git repository as specified in NEWSDK.md
     init_git_repository(base_path)?;
@@ -107,9 +117,7 @@ fn create_file_from_template(
     project_name: &str,
     project_class_name: &str
 ) -> Result<()> {
-    let content = template_content
-        .replace("{{PROJECT_NAME}}", project_name)
-        .replace("{{PROJECT_CLASS_NAME}}", project_class_name);
+    let content = render_template(template_content, project_name, project_class_name);
     
     fs::write(file_path, content)
         .with_context(|| format!("Failed to create file: {}", file_path.display()))?;
@@ -117,6 +125,48 @@ fn create_file_from_template(
     Ok(())
 }
 
+/// Replace the placeholders in a template
+fn render_template(template_content: &str, project_name: &str, project_class_name: &str) -> String {
+    template_content
+        .replace("{{PROJECT_NAME}}", project_name)
+        .replace("{{PROJECT_CLASS_NAME}}", project_class_name)
+}
+
+/// Check that `content` is a well-formed XML document: tags balanced,
+/// comments and entity references valid
+fn check_well_formed_xml(content: &str) -> Result<()> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().check_comments = true;
+    let mut depth = 0usize;
+    
+    loop {
+        let event = reader.read_event()
+            .map_err(|e| anyhow!("at byte {}: {}", reader.error_position(), e))?;
+        match event {
+            Event::Start(start) => {
+                check_xml_attributes(&start)?;
+                depth += 1;
+            }
+            Event::Empty(start) => check_xml_attributes(&start)?,
+            Event::End(_) => depth -= 1,
+            Event::Text(text) => {
+                text.unescape()?;
+            }
+            Event::Eof if depth == 0 => return Ok(()),
+            Event::Eof => return Err(anyhow!("{} element(s) are not closed", depth)),
+            _ => {}
+        }
+    }
+}
+
+/// Check that every attribute of an element parses and unescapes
+fn check_xml_attributes(start: &BytesStart) -> Result<()> {
+    for attribute in start.attributes() {
+        attribute?.unescape_value()?;
+    }
+    Ok(())
+}
+
 /// Initialize git repository as specified in NEWSDK.md
 fn init_git_repository(project_path: &Path) -> Result<()> {
     let output = Command::new("git")
@@ -0,0 +1,39 @@
+use clap::ArgMatches;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::cli::{print_status, print_info};
+use crate::bundle::AriaBundle;
+
+/// Handle the 'arc package' command
+pub async fn handle_package_command(matches: &ArgMatches) -> Result<()> {
+    let manifest_path = matches.get_one::<String>("manifest").unwrap();
+    let impl_dir = matches.get_one::<String>("impl-dir").unwrap();
+    
+    let start_time = Instant::now();
+    
+    print_info(&format!("Packaging {} with implementations from: {}", manifest_path, impl_dir));
+    
+    let mut bundle = AriaBundle::from_manifest_and_dir(Path::new(manifest_path), Path::new(impl_dir)).await?;
+    
+    let output = match matches.get_one::<String>("output") {
+        Some(output) => PathBuf::from(output),
+        None => bundle.default_output_path()?,
+    };
+    
+    bundle.save_to_file(&output).await?;
+    
+    print_status("Finished", &format!(
+        "Package completed in {:.2}s", 
+        start_time.elapsed().as_secs_f64()
+    ));
+    print_info(&format!("Bundle created: {}", output.display()));
+    print_info(&format!("  - Tools: {}", bundle.manifest.tools.len()));
+    print_info(&format!("  - Agents: {}", bundle.manifest.agents.len()));
+    print_info(&format!("  - Teams: {}", bundle.manifest.teams.len()));
+    print_info(&format!("  - Pipelines: {}", bundle.manifest.pipelines.len()));
+    print_info(&format!("  - Workflows: {}", bundle.manifest.workflows.len()));
+    
+    Ok(())
+}
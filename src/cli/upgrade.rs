@@ -0,0 +1,96 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+use tokio::fs;
+
+use crate::cli::{print_status, print_info, print_warning};
+use crate::cli::build::find_config_file;
+use crate::config::ProjectConfig;
+
+/// Handle the 'arc upgrade' command
+pub async fn handle_upgrade_command(matches: &ArgMatches) -> Result<()> {
+    let input_path = matches.get_one::<String>("input").unwrap();
+    let dry_run = matches.get_flag("dry-run");
+
+    let config_path = find_config_file(input_path)?
+        .ok_or_else(|| anyhow!("No aria.toml found for {}", input_path))?;
+
+    print_info(&format!("Upgrading configuration: {}", config_path.display()));
+
+    let original = fs::read_to_string(&config_path).await?;
+    let config = ProjectConfig::upgrade_file(&config_path).await?;
+    let upgraded = toml::to_string_pretty(&config)
+        .map_err(|e| anyhow!("Failed to serialize config: {}", e))?;
+
+    let changes = line_diff(&original, &upgraded);
+    if changes.iter().all(|change| matches!(change, LineChange::Same)) {
+        print_status("Up to date", &format!("{} already matches the current schema", config_path.display()));
+        return Ok(());
+    }
+
+    for change in &changes {
+        match change {
+            LineChange::Removed(line) => println!("-{}", line),
+            LineChange::Added(line) => println!("+{}", line),
+            LineChange::Same => {}
+        }
+    }
+
+    if original.lines().any(|line| line.trim_start().starts_with('#')) {
+        print_warning("Comments in the original file are not preserved");
+    }
+
+    if dry_run {
+        print_info(&format!("Dry run: {} was not modified", config_path.display()));
+        return Ok(());
+    }
+
+    config.save_to_file(&config_path).await?;
+    print_status("Upgraded", &config_path.display().to_string());
+
+    Ok(())
+}
+
+/// A line of a line-by-line diff
+enum LineChange<'a> {
+    Same,
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diff two texts line by line using their longest common subsequence
+fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<LineChange<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] is the LCS length of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            changes.push(LineChange::Same);
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            changes.push(LineChange::Removed(old[i]));
+            i += 1;
+        } else {
+            changes.push(LineChange::Added(new[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[i..].iter().map(|line| LineChange::Removed(line)));
+    changes.extend(new[j..].iter().map(|line| LineChange::Added(line)));
+
+    changes
+}
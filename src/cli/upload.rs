@@ -1,48 +1,240 @@
 use clap::ArgMatches;
 use anyhow::{Result, anyhow};
-use std::path::Path;
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+use tonic::codec::CompressionEncoding;
 
-use crate::cli::{print_status, print_info};
-use crate::grpc::{QuiltClient, UploadProgress};
+use crate::cli::{print_status, print_info, print_error};
+use crate::cli::build::{load_project_config, resolve_project_config};
+use crate::config::ProjectConfig;
+use crate::bundle::{AriaBundle, BundleMetadata};
+use crate::bundle::hash::HashAlgorithm;
+use crate::grpc::{QuiltClient, UploadProgress, UploadResult, DEFAULT_QUILT_SOCKET};
+
+/// Appended to a bundle's file name to name the receipt written beside it
+const RECEIPT_SUFFIX: &str = ".receipt.json";
+
+/// Record of a successful upload
+#[derive(Debug, Serialize, Deserialize)]
+struct UploadReceipt {
+    bundle_id: String,
+    bundle_path: String,
+    hash_algorithm: HashAlgorithm,
+    /// The bundle's build hash, identifying its contents
+    build_hash: String,
+    /// RFC 3339 time the upload finished
+    uploaded_at: String,
+    /// Socket or endpoint of the Quilt daemon the bundle was uploaded to
+    socket_path: String,
+    bytes_uploaded: u64,
+    upload_time_seconds: f64,
+}
+
+impl UploadReceipt {
+    /// Record the upload of the bundle at `bundle_path`, described by
+    /// `metadata`, to the daemon at `address`, finishing now
+    fn new(bundle_path: &str, metadata: &BundleMetadata, address: &str, result: UploadResult) -> Self {
+        Self {
+            bundle_id: result.bundle_id,
+            bundle_path: bundle_path.to_string(),
+            hash_algorithm: metadata.hash_algorithm,
+            build_hash: metadata.build_hash.clone(),
+            uploaded_at: chrono::Utc::now().to_rfc3339(),
+            socket_path: address.to_string(),
+            bytes_uploaded: result.bytes_uploaded,
+            upload_time_seconds: result.upload_time_seconds,
+        }
+    }
+}
+
+/// How `upload_bundle` talks to the daemon and records the result
+pub(crate) struct UploadOptions {
+    /// Check the daemon responds before streaming the bundle
+    pub test_connection: bool,
+    /// Upload rate cap in bytes per second
+    pub max_rate: Option<f64>,
+    pub compression: Option<CompressionEncoding>,
+    /// Where to write the receipt (default: beside the bundle)
+    pub receipt_path: Option<PathBuf>,
+    /// Skip the upload if the receipt shows this bundle already went to this daemon
+    pub skip_existing: bool,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            test_connection: true,
+            max_rate: None,
+            compression: None,
+            receipt_path: None,
+            skip_existing: false,
+        }
+    }
+}
 
 /// Handle the 'arc upload' command
 pub async fn handle_upload_command(matches: &ArgMatches) -> Result<()> {
     let bundle_path = matches.get_one::<String>("bundle").unwrap();
-    let socket_path = matches.get_one::<String>("socket").map(|s| s.as_str()).unwrap_or("/run/quilt/api.sock");
+    let profile = matches.get_one::<String>("profile").map(|s| s.as_str());
+    
+    // --max-rate is in MB/s; the client paces in bytes per second
+    let max_rate = match matches.get_one::<f64>("max-rate") {
+        Some(&rate) if rate > 0.0 => Some(rate * 1024.0 * 1024.0),
+        Some(rate) => return Err(anyhow!("--max-rate must be greater than 0, got {}", rate)),
+        None => None,
+    };
     
+    // clap restricts the value to none or gzip
+    let compression = match matches.get_one::<String>("grpc-compression").map(|s| s.as_str()) {
+        Some("gzip") => Some(CompressionEncoding::Gzip),
+        _ => None,
+    };
+    
+    // Resolve the profile against the project in the current directory
+    let config = match profile {
+        Some(_) => Some(load_project_config(".", None, profile, false).await?),
+        None => None,
+    };
+    
+    let address = determine_daemon_address(matches.get_one::<String>("socket"), config).await?;
+    
+    upload_bundle(bundle_path, &address, UploadOptions {
+        test_connection: !matches.get_flag("no-test"),
+        max_rate,
+        compression,
+        receipt_path: matches.get_one::<String>("receipt").map(PathBuf::from),
+        skip_existing: matches.get_flag("skip-existing"),
+    }).await
+}
+
+/// Upload the bundle at `bundle_path` to the daemon at `address` and write
+/// a receipt for it
+pub(crate) async fn upload_bundle(bundle_path: &str, address: &str, options: UploadOptions) -> Result<()> {
     print_info(&format!("Uploading bundle: {}", bundle_path));
-    print_info(&format!("Quilt daemon socket: {}", socket_path));
+    print_info(&format!("Quilt daemon: {}", address));
     
     // Validate bundle exists
     if !Path::new(bundle_path).exists() {
         return Err(anyhow!("Bundle file not found: {}", bundle_path));
     }
     
-    // Upload via gRPC to Quilt daemon
-    upload_bundle_to_quilt(bundle_path, socket_path).await?;
+    // Checksum the upload with the algorithm the bundle was built with, and
+    // forward the bundle's custom metadata
+    let metadata = AriaBundle::read_metadata(bundle_path).await?;
+    
+    let receipt_path = options.receipt_path
+        .unwrap_or_else(|| default_receipt_path(bundle_path));
+    
+    if options.skip_existing {
+        if let Some(receipt) = read_receipt(&receipt_path).await {
+            if receipt.hash_algorithm == metadata.hash_algorithm
+                && receipt.build_hash == metadata.build_hash
+                && receipt.socket_path == address
+            {
+                print_status("Skipped", &format!(
+                    "Bundle already uploaded as {} at {} (receipt: {})",
+                    receipt.bundle_id, receipt.uploaded_at, receipt_path.display()
+                ));
+                return Ok(());
+            }
+        }
+    }
+    
+    // Upload via gRPC to Quilt daemon. If interrupted, the daemon may have seen
+    // a partial stream that fails its checksum, so the upload must be re-run.
+    let result = tokio::select! {
+        result = upload_bundle_to_quilt(bundle_path, &metadata, address, options.test_connection, options.max_rate, options.compression) => result?,
+        _ = tokio::signal::ctrl_c() => {
+            print_error("Upload cancelled; re-run `arc upload` to retry");
+            std::process::exit(130);
+        }
+    };
     
     print_status("Uploaded", "Bundle deployed to Quilt daemon");
     
+    let receipt = UploadReceipt::new(bundle_path, &metadata, address, result);
+    tokio::fs::write(&receipt_path, serde_json::to_string_pretty(&receipt)?).await
+        .map_err(|e| anyhow!("Bundle uploaded, but failed to write receipt {}: {}", receipt_path.display(), e))?;
+    print_info(&format!("Receipt: {}", receipt_path.display()));
+    
     Ok(())
 }
 
-/// Upload bundle to Quilt daemon via gRPC
-async fn upload_bundle_to_quilt(bundle_path: &str, socket_path: &str) -> Result<()> {
-    print_status("Transport", "gRPC via Unix socket");
+/// Pick the daemon address: `--socket`, then `ARC_QUILT_SOCKET`, then
+/// `deploy.socket` or `deploy.endpoint` in aria.toml, then
+/// `runtime.quilt_socket`, then the built-in default socket
+pub(crate) async fn determine_daemon_address(socket_arg: Option<&String>, config: Option<ProjectConfig>) -> Result<String> {
+    if let Some(socket) = socket_arg {
+        return Ok(socket.clone());
+    }
+    
+    if let Some(socket) = std::env::var("ARC_QUILT_SOCKET").ok().filter(|s| !s.is_empty()) {
+        return Ok(socket);
+    }
+    
+    // Only read aria.toml when nothing more specific was given
+    let config = match config {
+        Some(config) => config,
+        None => resolve_project_config(".", None, None).await?.0,
+    };
+    
+    Ok(config.deploy.socket
+        .or(config.deploy.endpoint)
+        .or(config.runtime.quilt_socket)
+        .unwrap_or_else(|| DEFAULT_QUILT_SOCKET.to_string()))
+}
+
+/// Where the receipt for `bundle_path` goes without `--receipt`: beside the
+/// bundle, named after it, e.g. `dist/app.aria.receipt.json`
+fn default_receipt_path(bundle_path: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", bundle_path, RECEIPT_SUFFIX))
+}
+
+/// Read a receipt left by an earlier upload; a missing or unreadable receipt
+/// just means the bundle is uploaded again
+async fn read_receipt(path: &Path) -> Option<UploadReceipt> {
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Upload bundle to Quilt daemon via gRPC, optionally capped at `max_rate` bytes per second
+/// and with transport `compression`
+async fn upload_bundle_to_quilt(
+    bundle_path: &str,
+    metadata: &BundleMetadata,
+    address: &str,
+    test_connection: bool,
+    max_rate: Option<f64>,
+    compression: Option<CompressionEncoding>,
+) -> Result<UploadResult> {
+    print_status("Transport", if address.starts_with("http://") { "gRPC over TCP" } else { "gRPC via Unix socket" });
     
     // Connect to Quilt daemon
-    let mut client = QuiltClient::connect_to_socket(socket_path).await?;
+    let mut client = QuiltClient::connect_to(address).await?;
+    if let Some(encoding) = compression {
+        client = client.with_compression(encoding);
+    }
     
-    // Test connection
-    client.test_connection().await?;
+    // Test connection (optional, as it relies on the unrelated list_containers RPC)
+    if test_connection {
+        client.test_connection().await?;
+    }
+    
+    if let Some(rate) = max_rate {
+        print_info(&format!("Rate limit: {:.2} MB/s", rate / (1024.0 * 1024.0)));
+    }
+    if compression.is_some() {
+        print_info("gRPC compression: gzip");
+    }
     
     // Upload with progress reporting
-    let result = client.upload_bundle(bundle_path, |progress: UploadProgress| {
-        if progress.percent as u64 % 10 == 0 {  // Report every 10%
-            print_info(&format!("Progress: {:.1}% ({:.1}/{:.1} MB)", 
+    let result = client.upload_bundle(bundle_path, metadata.hash_algorithm, &metadata.custom, max_rate, |progress: UploadProgress| {
+        if (progress.percent as u64).is_multiple_of(10) {  // Report every 10%
+            print_info(&format!("Progress: {:.1}% ({:.1}/{:.1} MB, {:.2} MB/s)", 
                 progress.percent,
                 progress.bytes_uploaded as f64 / (1024.0 * 1024.0),
-                progress.total_bytes as f64 / (1024.0 * 1024.0)
+                progress.total_bytes as f64 / (1024.0 * 1024.0),
+                progress.bytes_per_second / (1024.0 * 1024.0)
             ));
                     }
     }).await?;
@@ -57,7 +249,37 @@ async fn upload_bundle_to_quilt(bundle_path: &str, socket_path: &str) -> Result<
             result.error_message.unwrap_or_else(|| "Unknown error".to_string())));
     }
     
-    Ok(())
+    Ok(result)
 }
 
- 
\ No newline at end of file
+ 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receipts_record_the_upload_result() {
+        let mut metadata = BundleMetadata::new();
+        metadata.build_hash = "abc123".to_string();
+        metadata.hash_algorithm = HashAlgorithm::Sha256;
+        let result = UploadResult {
+            bundle_id: "bundle-7".to_string(),
+            success: true,
+            bytes_uploaded: 4096,
+            upload_time_seconds: 1.5,
+            error_message: None,
+        };
+
+        let receipt = UploadReceipt::new("dist/app.aria", &metadata, "/tmp/quilt.sock", result);
+
+        assert_eq!(receipt.bundle_id, "bundle-7");
+        assert_eq!(receipt.bundle_path, "dist/app.aria");
+        assert_eq!(receipt.hash_algorithm, HashAlgorithm::Sha256);
+        assert_eq!(receipt.build_hash, "abc123");
+        assert_eq!(receipt.socket_path, "/tmp/quilt.sock");
+        assert_eq!(receipt.bytes_uploaded, 4096);
+        assert_eq!(receipt.upload_time_seconds, 1.5);
+        assert!(chrono::DateTime::parse_from_rfc3339(&receipt.uploaded_at).is_ok());
+    }
+}
@@ -0,0 +1,26 @@
+use clap::ArgMatches;
+use anyhow::{Result, anyhow};
+use std::path::Path;
+
+use crate::cli::{print_status, print_info};
+use crate::bundle::signature::{read_public_key, verify_build_hash};
+
+/// Handle the 'arc verify' command
+pub async fn handle_verify_command(matches: &ArgMatches) -> Result<()> {
+    let bundle_path = matches.get_one::<String>("bundle").unwrap();
+    let public_key = read_public_key(Path::new(matches.get_one::<String>("public-key").unwrap()))?;
+    
+    print_info(&format!("Verifying bundle: {}", bundle_path));
+    
+    let (metadata, signature) = verify_build_hash(bundle_path)?;
+    print_info(&format!("Build hash: {} {}", metadata.hash_algorithm, metadata.build_hash));
+    
+    let signature = signature
+        .ok_or_else(|| anyhow!("No signature present in {}; the bundle is unsigned", bundle_path))?;
+    print_info(&format!("Signer: {}", signature.signer));
+    
+    signature.verify(&metadata.build_hash, &public_key)?;
+    print_status("Verified", &format!("{} is signed by {}", bundle_path, signature.signer));
+    
+    Ok(())
+}
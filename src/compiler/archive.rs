@@ -0,0 +1,104 @@
+//! Source archives given as compiler inputs.
+//!
+//! A `.zip`, `.tar.gz` or `.tgz` input is extracted to a temporary directory
+//! so the usual discovery can run over it. Only regular files and
+//! directories are extracted, and never outside the temporary directory.
+
+use anyhow::{Result, anyhow};
+use flate2::read::GzDecoder;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tar::EntryType;
+use zip::ZipArchive;
+
+/// Distinguishes extraction directories created by one process
+static NEXT_EXTRACTION: AtomicUsize = AtomicUsize::new(0);
+
+/// Whether `path` names a supported source archive
+pub fn is_source_archive(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    name.ends_with(".zip") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// A source archive extracted to a temporary directory, which is removed on drop
+pub struct ExtractedArchive {
+    dir: PathBuf,
+}
+
+impl ExtractedArchive {
+    /// Extract `archive` into a fresh temporary directory
+    pub fn extract(archive: &Path) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!(
+            "arc-sources-{}-{}",
+            std::process::id(),
+            NEXT_EXTRACTION.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir(&dir)?;
+        let extracted = Self { dir: std::fs::canonicalize(&dir)? };
+
+        let name = archive.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        let result = if name.ends_with(".zip") {
+            extracted.extract_zip(archive)
+        } else {
+            extracted.extract_tar_gz(archive)
+        };
+        result.map_err(|e| anyhow!("Failed to extract {}: {}", archive.display(), e))?;
+
+        Ok(extracted)
+    }
+
+    /// Directory holding the extracted files
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    fn extract_zip(&self, archive: &Path) -> Result<()> {
+        let mut zip = ZipArchive::new(File::open(archive)?)?;
+
+        for i in 0..zip.len() {
+            let mut entry = zip.by_index(i)?;
+            // `enclosed_name` rejects absolute paths and `..` components
+            let relative = entry.enclosed_name()
+                .ok_or_else(|| anyhow!("entry '{}' escapes the archive", entry.name()))?;
+            let output = self.dir.join(relative);
+
+            if entry.is_dir() {
+                std::fs::create_dir_all(&output)?;
+            } else if entry.is_file() {
+                if let Some(parent) = output.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::io::copy(&mut entry, &mut File::create(&output)?)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn extract_tar_gz(&self, archive: &Path) -> Result<()> {
+        let mut tar = tar::Archive::new(GzDecoder::new(File::open(archive)?));
+
+        for entry in tar.entries()? {
+            let mut entry = entry?;
+            // Links could point outside the directory, so skip everything but files and directories
+            if !matches!(entry.header().entry_type(), EntryType::Regular | EntryType::Directory) {
+                continue;
+            }
+            // `unpack_in` refuses paths that would land outside `dir`
+            if !entry.unpack_in(&self.dir)? {
+                return Err(anyhow!("entry '{}' escapes the archive", entry.path()?.display()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.dir) {
+            tracing::warn!("Failed to remove {}: {}", self.dir.display(), e);
+        }
+    }
+}
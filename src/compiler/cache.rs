@@ -0,0 +1,101 @@
+//! Compiled output of individual source files, kept between builds.
+//!
+//! Entries are keyed by canonical source path and store the source's content
+//! hash, so an entry is only reused for a file that is byte-for-byte
+//! unchanged and was compiled by the same version of arc with the same options.
+
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use std::path::{Path, PathBuf};
+
+use super::{CompiledFile, SourceFile};
+use super::diagnostics::Warning;
+use super::typescript::visitor::{DescriptionFile, ExtractedItem};
+
+/// A directory of cached compiled files
+#[derive(Debug, Clone)]
+pub struct CompileCache {
+    dir: PathBuf,
+}
+
+/// What is stored for one compiled source file
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    compiler_version: String,
+    options: String,
+    content_hash: String,
+    javascript_code: String,
+    items: Vec<ExtractedItem>,
+    description_files: Vec<DescriptionFile>,
+    imports: Vec<PathBuf>,
+    packages: Vec<String>,
+    warnings: Vec<Warning>,
+}
+
+impl CompileCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cached compilation of `source`, if there is one for its current
+    /// content compiled with `options`. A missing or unreadable entry just
+    /// means the file is compiled.
+    pub async fn load(&self, source: &SourceFile, options: &str) -> Option<CompiledFile> {
+        let content = tokio::fs::read(self.entry_path(&source.path)).await.ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+        if entry.compiler_version != env!("CARGO_PKG_VERSION")
+            || entry.options != options
+            || entry.content_hash != content_hash(source)
+        {
+            return None;
+        }
+
+        Some(CompiledFile {
+            source: source.clone(),
+            javascript_code: entry.javascript_code,
+            items: entry.items,
+            description_files: entry.description_files,
+            imports: entry.imports,
+            packages: entry.packages,
+            warnings: entry.warnings,
+            parse_time_secs: 0.0,
+            transpile_time_secs: 0.0,
+            cached: true,
+        })
+    }
+
+    /// Cache `file`, compiled with `options`, for the next build
+    pub async fn store(&self, file: &CompiledFile, options: &str) -> Result<()> {
+        let entry = CacheEntry {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            options: options.to_string(),
+            content_hash: content_hash(&file.source),
+            javascript_code: file.javascript_code.clone(),
+            items: file.items.clone(),
+            description_files: file.description_files.clone(),
+            imports: file.imports.clone(),
+            packages: file.packages.clone(),
+            warnings: file.warnings.clone(),
+        };
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(self.entry_path(&file.source.path), serde_json::to_vec(&entry)?).await?;
+        Ok(())
+    }
+
+    /// One file per source, named by a hash of its canonical path
+    fn entry_path(&self, source_path: &Path) -> PathBuf {
+        let key = blake3::hash(canonical_path(source_path).to_string_lossy().as_bytes()).to_hex();
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+/// `path` with symlinks and `..` resolved, so relative source and import
+/// paths compare equal to the absolute paths git reports. A path that no
+/// longer exists is returned as is.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn content_hash(source: &SourceFile) -> String {
+    blake3::hash(source.content.as_bytes()).to_hex().to_string()
+}
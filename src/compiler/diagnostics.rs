@@ -0,0 +1,334 @@
+//! Structured compilation errors and warnings.
+//!
+//! Compilation failures are still reported as `anyhow::Error`, but carry a
+//! [`CompileError`] so callers can recover the file and line of each problem
+//! for machine-readable output.
+
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A single compilation problem, located as precisely as the compiler knows
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<DiagnosticCode>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// A diagnostic with no known location
+    pub fn message(message: impl Into<String>) -> Self {
+        Self { file: None, line: None, code: None, message: message.into() }
+    }
+
+    /// Classify the diagnostic so `arc explain` can describe it
+    pub fn with_code(mut self, code: DiagnosticCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Attribute the diagnostic to `path`
+    pub fn in_file(mut self, path: &Path) -> Self {
+        self.file = Some(path.to_path_buf());
+        self
+    }
+
+    /// Diagnostics for an error raised while compiling `path`. Structured
+    /// errors keep their own entries; anything else becomes a single entry.
+    pub fn for_file(path: &Path, error: &anyhow::Error) -> Vec<Self> {
+        match error.downcast_ref::<CompileError>() {
+            Some(compile_error) => compile_error.diagnostics.iter()
+                .cloned()
+                .map(|mut diagnostic| {
+                    diagnostic.file.get_or_insert_with(|| path.to_path_buf());
+                    diagnostic
+                })
+                .collect(),
+            None => vec![Self {
+                file: Some(path.to_path_buf()),
+                line: None,
+                code: None,
+                message: error.to_string(),
+            }],
+        }
+    }
+
+    /// Diagnostics for any error, falling back to a single unlocated entry
+    pub fn from_error(error: &anyhow::Error) -> Vec<Self> {
+        match error.downcast_ref::<CompileError>() {
+            Some(compile_error) => compile_error.diagnostics.clone(),
+            None => vec![Self::message(error.to_string())],
+        }
+    }
+}
+
+/// Displays as the message, prefixed with the code if there is one
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "[{}] {}", code, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Stable identifier for a kind of compilation error, described by `arc explain`.
+/// Codes are never reused once assigned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DiagnosticCode {
+    /// A source file that could not be parsed
+    #[serde(rename = "ARC0001")]
+    ParseError,
+    /// A reference to a name that is not defined anywhere
+    #[serde(rename = "ARC0002")]
+    UndefinedReference,
+    /// A name that is defined in more than one place
+    #[serde(rename = "ARC0003")]
+    DuplicateName,
+    /// Decorator metadata that is missing a required value
+    #[serde(rename = "ARC0004")]
+    InvalidDecorator,
+    /// An item without a description, reported by `check --strict`
+    #[serde(rename = "ARC0005")]
+    MissingDescription,
+    /// An agent without tools, reported by `check --strict`
+    #[serde(rename = "ARC0006")]
+    AgentWithoutTools,
+    /// A name that cannot be used as a bundle file name
+    #[serde(rename = "ARC0007")]
+    InvalidName,
+}
+
+/// Long-form descriptions shown by `arc explain`, one entry per code
+const EXPLANATIONS: &[(DiagnosticCode, &str)] = &[
+    (DiagnosticCode::ParseError, "\
+A source file could not be parsed as TypeScript.
+
+The compiler parses every discovered `.ts` file with decorators enabled.
+The reported line is where the parser gave up, which may be after the
+actual mistake, such as an unclosed bracket or string.
+
+Fix the syntax error, or move files that are not part of the project out
+of the input directories."),
+    (DiagnosticCode::UndefinedReference, "\
+A decorator refers to a name that is not defined in the project or its
+dependencies.
+
+This is reported for:
+  - an `@agent` whose `tools` lists a tool that does not exist
+  - a `@team` whose `members` lists an agent that does not exist
+  - a `@workflow` whose `steps` lists a name that does not exist
+
+Check the spelling, make sure the file defining the name is inside an
+input directory, or add the bundle that provides it to
+`build.dependencies` in aria.toml."),
+    (DiagnosticCode::DuplicateName, "\
+A referenced tool is defined more than once, so the reference cannot be
+resolved to a single definition.
+
+Standalone `@tool` functions and `@tool` methods share one namespace across
+the whole project. The same applies to tools provided by dependency
+bundles: if two dependencies provide a tool with the same name, a project
+that uses it cannot tell which one is meant.
+
+Rename one of the definitions with the decorator's `name` option, or drop
+one of the conflicting dependencies."),
+    (DiagnosticCode::InvalidDecorator, "\
+A decorator's metadata is missing a value the compiler needs.
+
+For example, a `@team` member written as an object must name its agent:
+
+    @team({ members: [{ agent: 'Researcher', role: 'lead' }] })
+
+Add the missing value, or use the shorthand form where one exists, such as
+a plain agent name for a team member."),
+    (DiagnosticCode::MissingDescription, "\
+A tool, agent, team, pipeline or workflow has an empty `description`.
+
+Descriptions are how published bundles are discovered, so `arc check
+--strict` requires one on every item. Without `--strict` they are optional.
+
+Add a `description` to the decorator:
+
+    @tool({ description: 'Look up the weather for a city' })"),
+    (DiagnosticCode::AgentWithoutTools, "\
+An `@agent` has an empty `tools` list.
+
+An agent without tools can only answer from its prompt, which is usually a
+mistake such as a misspelled `tools` key, so `arc check --strict` rejects
+it. Without `--strict` it is allowed.
+
+List the tools the agent may call in the decorator's `tools` option, or
+define them as `@tool` methods on the agent class."),
+    (DiagnosticCode::InvalidName, "\
+A tool, agent, team, pipeline or workflow has a name that is not safe to
+write into a bundle.
+
+Each item is stored as `implementations/<kind>/<name>.js`, so names may
+only contain ASCII letters, digits, `_`, `$`, `-` and `.`, and may not
+start with `.`. This rules out `/`, `..`, quotes and whitespace, which
+could place a file outside the bundle's `implementations/` directory or
+produce broken JavaScript.
+
+Rename the item, usually with the decorator's `name` option:
+
+    @tool({ name: 'fetch-weather' })"),
+];
+
+impl DiagnosticCode {
+    /// Every assigned code, in order
+    pub const ALL: [DiagnosticCode; 7] = [
+        DiagnosticCode::ParseError,
+        DiagnosticCode::UndefinedReference,
+        DiagnosticCode::DuplicateName,
+        DiagnosticCode::InvalidDecorator,
+        DiagnosticCode::MissingDescription,
+        DiagnosticCode::AgentWithoutTools,
+        DiagnosticCode::InvalidName,
+    ];
+
+    /// The code as printed, e.g. `ARC0001`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::ParseError => "ARC0001",
+            DiagnosticCode::UndefinedReference => "ARC0002",
+            DiagnosticCode::DuplicateName => "ARC0003",
+            DiagnosticCode::InvalidDecorator => "ARC0004",
+            DiagnosticCode::MissingDescription => "ARC0005",
+            DiagnosticCode::AgentWithoutTools => "ARC0006",
+            DiagnosticCode::InvalidName => "ARC0007",
+        }
+    }
+
+    /// Look up a code by its printed form, ignoring case
+    pub fn parse(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str().eq_ignore_ascii_case(code.trim()))
+    }
+
+    /// The long-form description of the code
+    pub fn explanation(&self) -> &'static str {
+        EXPLANATIONS.iter()
+            .find(|(code, _)| code == self)
+            .map(|(_, text)| *text)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A compilation failure made up of one or more diagnostics. Displays as the
+/// human-readable summary it was created with.
+#[derive(Debug)]
+pub struct CompileError {
+    message: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CompileError {
+    pub fn new(message: impl Into<String>, diagnostics: Vec<Diagnostic>) -> Self {
+        Self { message: message.into(), diagnostics }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+/// Returned by `AriaCompiler::compile_project_with` when its cancellation
+/// token fires before the compilation finishes
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compilation cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Category of a compiler warning, used to group warnings in summaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum WarningKind {
+    /// A `@tool` method in a class that cannot host tools
+    MisplacedTool,
+    /// An agent that lists one of its own `@tool` methods in `tools`
+    RedundantTool,
+    /// A decorator value that is only known at runtime
+    DynamicValue,
+    /// A `tags` entry that is not a string
+    InvalidTag,
+    /// A negative number in a tool's `cost`
+    NegativeCost,
+    /// No decorated functions or classes in the project
+    NoDecorators,
+    /// A source file that was discovered but not compiled
+    SkippedFile,
+    /// A cross-reference that failed validation in a build that skips it
+    UnresolvedReference,
+    /// A `@tool` on a getter, setter or method without a static name
+    UnsupportedTool,
+    /// An imported binding the module never references
+    UnusedImport,
+    /// A name listed twice in an agent's tools or a team's members
+    DuplicateReference,
+    /// A syntax error the parser recovered from
+    RecoveredSyntaxError,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarningKind::MisplacedTool => write!(f, "tool outside an agent"),
+            WarningKind::RedundantTool => write!(f, "redundant tool listing"),
+            WarningKind::DynamicValue => write!(f, "non-static decorator value"),
+            WarningKind::InvalidTag => write!(f, "invalid tag"),
+            WarningKind::NegativeCost => write!(f, "negative cost"),
+            WarningKind::NoDecorators => write!(f, "no decorators found"),
+            WarningKind::SkippedFile => write!(f, "skipped source file"),
+            WarningKind::UnresolvedReference => write!(f, "unresolved reference"),
+            WarningKind::UnsupportedTool => write!(f, "unsupported tool method"),
+            WarningKind::UnusedImport => write!(f, "unused import"),
+            WarningKind::DuplicateReference => write!(f, "duplicate reference"),
+            WarningKind::RecoveredSyntaxError => write!(f, "recovered syntax error"),
+        }
+    }
+}
+
+/// A compiler warning. Displays as its message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    /// Source file the warning is about, if it concerns a single file
+    pub file: Option<PathBuf>,
+}
+
+impl Warning {
+    pub fn new(kind: WarningKind, message: impl Into<String>) -> Self {
+        Self { kind, message: message.into(), file: None }
+    }
+    
+    /// Attribute the warning to `path`
+    pub fn in_file(mut self, path: &Path) -> Self {
+        self.file = Some(path.to_path_buf());
+        self
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
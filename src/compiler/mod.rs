@@ -1,80 +1,418 @@
+pub mod archive;
+pub mod cache;
+pub mod diagnostics;
 pub mod typescript;
 pub mod schema;
 
 use anyhow::{Result, anyhow};
-use std::path::{Path, PathBuf};
-use std::collections::{HashMap, HashSet};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::{Component, Path, PathBuf};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use serde::{Serialize, Deserialize};
-use std::sync::Arc;
-use swc_core::common::{SourceMap, sync::Lrc};
+use schemars::JsonSchema;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use swc_core::ecma::visit::Fold;
 
-use self::typescript::TypeScriptCompiler;
-use self::typescript::visitor::ExtractedItem;
-use crate::compiler::schema::{AgentManifest, ToolManifest, AriaManifest, TeamManifest, PipelineManifest};
-use crate::bundle::AriaBundle;
+use self::archive::{is_source_archive, ExtractedArchive};
+use self::cache::{canonical_path, CompileCache};
+use self::diagnostics::{Cancelled, CompileError, Diagnostic, DiagnosticCode, Warning, WarningKind};
+use self::typescript::{TransformFactory, TypeScriptCompiler};
+use self::typescript::visitor::{DescriptionFile, ExtractedItem};
+use crate::compiler::schema::{AgentManifest, ToolManifest, AriaManifest, TeamManifest, PipelineManifest, WorkflowManifest, ExternalReference, SourceEncoding, MANIFEST_VERSION, is_valid_name};
+use crate::bundle::{AriaBundle, ManifestFormat, DEFAULT_FILE_PERMISSIONS, HASH_PLACEHOLDER};
+use crate::bundle::hash::HashAlgorithm;
+
+/// Path given to source read from standard input
+pub const STDIN_PATH: &str = "<stdin>";
 
 /// Main Aria compiler that orchestrates the compilation process
 pub struct AriaCompiler {
-    typescript_compiler: Arc<TypeScriptCompiler>,
     // Future: dsl_compiler: dsl::DslCompiler,
+    keep_going: bool,
+    jobs: usize,
+    target_dir: Option<PathBuf>,
+    hash_algorithm: HashAlgorithm,
+    source_encoding: SourceEncoding,
+    dependencies: Vec<PathBuf>,
+    compress: bool,
+    manifest_format: ManifestFormat,
+    file_permissions: u32,
+    transforms: Vec<TransformFactory>,
+    emit_js_dir: Option<PathBuf>,
+    include_js: bool,
+    emit_js_only: bool,
+    strict: bool,
+    validate_references: bool,
+    check_packages: bool,
+    only: Vec<String>,
+    features: Vec<String>,
+    exclude: Vec<String>,
+    dump_ast: bool,
+    lint_unused_imports: bool,
+    cache: Option<CompileCache>,
+    changed_files: Option<HashSet<PathBuf>>,
+    stdin_source: Option<String>,
+    metadata: BTreeMap<String, String>,
+    /// Description files the last compile read or tried to read, watched
+    /// along with the sources
+    description_files: Mutex<BTreeSet<PathBuf>>,
 }
 
 impl AriaCompiler {
     /// Create a new Aria compiler instance
     pub fn new() -> Self {
-        let cm = Lrc::new(SourceMap::default());
         Self {
-            typescript_compiler: Arc::new(TypeScriptCompiler::new(cm)),
+            keep_going: false,
+            jobs: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            target_dir: None,
+            hash_algorithm: HashAlgorithm::default(),
+            source_encoding: SourceEncoding::default(),
+            dependencies: Vec::new(),
+            compress: true,
+            manifest_format: ManifestFormat::default(),
+            file_permissions: DEFAULT_FILE_PERMISSIONS,
+            transforms: Vec::new(),
+            emit_js_dir: None,
+            include_js: false,
+            emit_js_only: false,
+            strict: false,
+            validate_references: true,
+            check_packages: false,
+            only: Vec::new(),
+            features: Vec::new(),
+            exclude: Vec::new(),
+            dump_ast: false,
+            lint_unused_imports: false,
+            cache: None,
+            changed_files: None,
+            stdin_source: None,
+            metadata: BTreeMap::new(),
+            description_files: Mutex::new(BTreeSet::new()),
+        }
+    }
+    
+    /// Continue compiling remaining files after a file fails, reporting all
+    /// failures at the end instead of stopping at the first one
+    pub fn keep_going(mut self, keep_going: bool) -> Self {
+        self.keep_going = keep_going;
+        self
+    }
+    
+    /// Make `check_project` reject items without a description and agents
+    /// without tools
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    
+    /// When false, `compile_project` reports cross-reference failures as
+    /// warnings and still writes the bundle, which the runtime may reject
+    pub fn validate_references(mut self, validate: bool) -> Self {
+        self.validate_references = validate;
+        self
+    }
+    
+    /// Make `compile_project` fail if an imported npm package is not
+    /// installed, instead of producing a bundle that fails at runtime
+    pub fn check_packages(mut self, check: bool) -> Self {
+        self.check_packages = check;
+        self
+    }
+    
+    /// Keep only the named items and everything they reference in the
+    /// result, after validating cross-references against the whole project.
+    /// Empty keeps everything.
+    pub fn only(mut self, names: Vec<String>) -> Self {
+        self.only = names;
+        self
+    }
+    
+    /// Build features to enable. Tools and agents gated on a `feature` not in
+    /// this list are left out of the bundle, along with references to them.
+    pub fn features(mut self, features: Vec<String>) -> Self {
+        self.features = features;
+        self
+    }
+    
+    /// Glob patterns of files and directories to leave out of directory
+    /// inputs, relative to the input directory. A pattern without a `/`
+    /// matches a name at any depth; excluded directories are not descended into.
+    pub fn exclude(mut self, patterns: Vec<String>) -> Self {
+        self.exclude = patterns;
+        self
+    }
+    
+    /// Print the AST of every parsed TypeScript source to stderr, for
+    /// diagnosing why a decorator is not extracted
+    pub fn dump_ast(mut self, dump_ast: bool) -> Self {
+        self.dump_ast = dump_ast;
+        self
+    }
+    
+    /// Warn about imports each TypeScript source never uses, which can pull
+    /// needless packages into the bundle's `package.json`
+    pub fn lint_unused_imports(mut self, lint: bool) -> Self {
+        self.lint_unused_imports = lint;
+        self
+    }
+    
+    /// Build incrementally: sources not in `changed`, and importing nothing
+    /// in it, reuse their output cached under `cache_dir`. Every file that is
+    /// compiled is cached there for the next build.
+    pub fn incremental(mut self, cache_dir: PathBuf, changed: HashSet<PathBuf>) -> Self {
+        self.cache = Some(CompileCache::new(cache_dir));
+        self.changed_files = Some(changed);
+        self
+    }
+    
+    /// Compile `source` as a single TypeScript file named `<stdin>` instead
+    /// of discovering files under the input paths
+    pub fn stdin_source(mut self, source: Option<String>) -> Self {
+        self.stdin_source = source;
+        self
+    }
+    
+    /// Compile at most `jobs` files at once; 1 compiles sequentially
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+    
+    /// Write intermediate build artifacts under `target_dir` instead of
+    /// beside the output bundle
+    pub fn target_dir(mut self, target_dir: Option<PathBuf>) -> Self {
+        self.target_dir = target_dir;
+        self
+    }
+    
+    /// Algorithm used for the bundle's integrity hash
+    pub fn hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+    
+    /// Encoding applied to each transpiled source stored in the bundle
+    pub fn source_encoding(mut self, source_encoding: SourceEncoding) -> Self {
+        self.source_encoding = source_encoding;
+        self
+    }
+    
+    /// Bundles whose tools may be referenced without being defined here
+    pub fn dependencies(mut self, dependencies: Vec<PathBuf>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+    
+    /// Key-values written to the bundle's build metadata under `custom`
+    pub fn metadata(mut self, metadata: BTreeMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+    
+    /// Deflate bundle entries; when false they are stored as-is, trading
+    /// bundle size for a faster write
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+    
+    /// Layout of the bundle's manifest.json
+    pub fn manifest_format(mut self, manifest_format: ManifestFormat) -> Self {
+        self.manifest_format = manifest_format;
+        self
+    }
+    
+    /// Unix permissions of the bundle's file entries
+    pub fn file_permissions(mut self, file_permissions: u32) -> Self {
+        self.file_permissions = file_permissions;
+        self
+    }
+    
+    /// Also write each file's transpiled JavaScript under `emit_js_dir`,
+    /// mirroring the source tree
+    pub fn emit_js_dir(mut self, emit_js_dir: Option<PathBuf>) -> Self {
+        self.emit_js_dir = emit_js_dir;
+        self
+    }
+    
+    /// Also compile `.js`, `.jsx`, `.mjs` and `.cjs` files found under input
+    /// directories. JavaScript files given directly as inputs are always compiled.
+    pub fn include_js(mut self, include_js: bool) -> Self {
+        self.include_js = include_js;
+        self
+    }
+    
+    /// Skip writing the bundle, leaving only the JavaScript from `emit_js_dir`
+    pub fn emit_js_only(mut self, emit_js_only: bool) -> Self {
+        self.emit_js_only = emit_js_only;
+        self
+    }
+    
+    /// Add a custom SWC pass to every file's transpile step; see
+    /// `TypeScriptCompiler::with_transform` for ordering
+    pub fn with_transform<F, P>(mut self, make_pass: F) -> Self
+    where
+        F: Fn() -> P + Send + Sync + 'static,
+        P: Fold + 'static,
+    {
+        self.transforms.push(Arc::new(move || Box::new(make_pass()) as Box<dyn Fold>));
+        self
+    }
+    
+    /// Where the bundle for `output_path` is written before being moved into place
+    pub fn staging_path(&self, output_path: &Path) -> PathBuf {
+        crate::bundle::temp_path_for(output_path, self.target_dir.as_deref())
+    }
+    
+    /// Content hash of every source file under `input_paths` and of the
+    /// description files the last compile read, used to tell which files
+    /// changed between runs
+    pub async fn source_hashes(&self, input_paths: &[&str]) -> Result<BTreeMap<PathBuf, String>> {
+        let sources = self.discover_sources(input_paths).await?;
+        
+        let mut hashes: BTreeMap<PathBuf, String> = sources.into_iter()
+            .map(|source| {
+                let hash = blake3::hash(source.content.as_bytes()).to_hex().to_string();
+                (source.path, hash)
+            })
+            .collect();
+        // A missing description file hashes as empty, so creating it counts as a change
+        let description_files = self.description_files.lock().unwrap().clone();
+        for path in description_files {
+            let hash = tokio::fs::read(&path).await
+                .map(|content| blake3::hash(&content).to_hex().to_string())
+                .unwrap_or_default();
+            hashes.insert(path, hash);
         }
+        
+        Ok(hashes)
+    }
+    
+    /// Paths of the source files discovered under `input_paths`, in the
+    /// order they would be compiled, without parsing them
+    pub async fn source_paths(&self, input_paths: &[&str]) -> Result<Vec<PathBuf>> {
+        let sources = self.discover_sources(input_paths).await?;
+        Ok(sources.into_iter().map(|source| source.path).collect())
     }
     
     /// Compile a project from input path to output bundle
     pub async fn compile_project(
         &self,
-        input_path: &str,
-        output_path: &PathBuf,
-        verbose: bool,
+        input_paths: &[&str],
+        output_path: &Path,
+    ) -> Result<CompilationResult> {
+        self.compile_project_with(input_paths, output_path, &|_| {}, &CancellationToken::new()).await
+    }
+    
+    /// Compile a project like `compile_project`, reporting each phase and
+    /// compiled file to `progress`. Once `cancel` fires the compilation stops
+    /// at the next checkpoint with a [`Cancelled`] error. Checkpoints all come
+    /// before any JavaScript or bundle is written; once writing starts, it
+    /// finishes.
+    #[tracing::instrument(name = "compile", skip(self, output_path, progress, cancel))]
+    pub async fn compile_project_with(
+        &self,
+        input_paths: &[&str],
+        output_path: &Path,
+        progress: &(dyn Fn(CompileProgress) + Send + Sync),
+        cancel: &CancellationToken,
     ) -> Result<CompilationResult> {
         let start_time = std::time::Instant::now();
         
+        let mut phase_timings = Vec::new();
+        
         // 1. Discover source files
-        let sources = self.discover_sources(input_path).await?;
+        progress(CompileProgress::Phase("discovery"));
+        let phase_start = std::time::Instant::now();
+        let sources = self.discover_sources(input_paths).await?;
+        phase_timings.push(("discovery".to_string(), phase_start.elapsed().as_secs_f64()));
+        checkpoint(cancel)?;
         
-        if verbose {
-            println!("Found {} source files", sources.len());
-        }
+        tracing::debug!("Found {} source files", sources.len());
+        
+        let discovered_files_count = sources.len();
         
         // 2. Compile based on source language
         let mut compiled_files: Vec<CompiledFile> = Vec::new();
         let mut warnings = Vec::new();
+        let mut skipped_files_count = 0;
+        let mut file_errors = Vec::new();
+        let mut diagnostics = Vec::new();
+        
+        progress(CompileProgress::Phase("compile"));
+        let mut compiled_results = self.compile_typescript_sources_with(&sources, progress, cancel).await?.into_iter();
         
         for source in sources {
             match source.language {
-                SourceLanguage::TypeScript => {
-                    match self.typescript_compiler.compile_file(&source).await {
-                        Ok(compiled) => compiled_files.push(compiled),
-                        Err(e) => return Err(e),
+                SourceLanguage::TypeScript | SourceLanguage::JavaScript => {
+                    match compiled_results.next().expect("one result per TypeScript source") {
+                        Ok(compiled) => {
+                            warnings.extend(compiled.warnings.iter().cloned());
+                            compiled_files.push(compiled);
+                        }
+                        Err(e) if self.keep_going => {
+                            file_errors.push(format!("{}: {}", source.path.display(), e));
+                            diagnostics.extend(Diagnostic::for_file(&source.path, &e));
+                        }
+                        Err(e) => {
+                            let diagnostics = Diagnostic::for_file(&source.path, &e);
+                            return Err(CompileError::new(e.to_string(), diagnostics).into());
+                        }
                     }
                 }
                 SourceLanguage::AriaSDL => {
                     // Future: DSL compilation
                     // For now, skip DSL files
-                    warnings.push(format!("Skipping DSL file (not yet implemented): {}", source.path.display()));
+                    skipped_files_count += 1;
+                    warnings.push(Warning::new(
+                        WarningKind::SkippedFile,
+                        format!("Skipping DSL file (not yet implemented): {}", source.path.display()),
+                    ).in_file(&source.path));
                 }
             }
         }
         
+        if !file_errors.is_empty() {
+            let message = format!(
+                "{} file(s) failed to compile:\n - {}",
+                file_errors.len(),
+                file_errors.join("\n - ")
+            );
+            return Err(CompileError::new(message, diagnostics).into());
+        }
+        
+        // Local modules imported from outside the input paths are bundled too
+        for imported in self.compile_local_imports(&compiled_files).await? {
+            warnings.extend(imported.warnings.iter().cloned());
+            compiled_files.push(imported);
+        }
+        let source_files_count = compiled_files.len();
+        // With nothing transpiled, the previous bundle's sources can be reused
+        let all_cached = !compiled_files.is_empty() && compiled_files.iter().all(|f| f.cached);
+        checkpoint(cancel)?;
+        
+        if self.check_packages {
+            check_installed_packages(&compiled_files)?;
+        }
+        
+        phase_timings.push(("parse".to_string(), compiled_files.iter().map(|f| f.parse_time_secs).sum()));
+        phase_timings.push(("transpile".to_string(), compiled_files.iter().map(|f| f.transpile_time_secs).sum()));
+        
         if compiled_files.iter().all(|f| f.items.is_empty()) {
-            warnings.push("No decorated functions or classes found".to_string());
+            warnings.push(Warning::new(WarningKind::NoDecorators, "No decorated functions or classes found"));
         }
         
         // 3. Process compiled files into implementations and a code map
         let mut implementations = Vec::new();
         let mut compiled_code_map: HashMap<PathBuf, String> = HashMap::new();
+        let mut source_imports: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
 
         for file in compiled_files {
             let source_path = file.source.path.clone();
             compiled_code_map.insert(source_path.clone(), file.javascript_code);
+            source_imports.insert(source_path.clone(), file.imports);
 
             for item in file.items {
                 let (name, details) = match item {
@@ -82,66 +420,392 @@ impl AriaCompiler {
                     ExtractedItem::Agent { manifest } => (manifest.name.clone(), ImplementationDetails::Agent(manifest)),
                     ExtractedItem::Team { manifest } => (manifest.name.clone(), ImplementationDetails::Team(manifest)),
                     ExtractedItem::Pipeline { manifest } => (manifest.name.clone(), ImplementationDetails::Pipeline(manifest)),
+                    ExtractedItem::Workflow { manifest } => (manifest.name.clone(), ImplementationDetails::Workflow(manifest)),
                 };
                 implementations.push(Implementation {
                     name,
                     details,
                     source_file_path: source_path.clone(),
+                    source_language: file.source.language.clone(),
+                    compiler_version: env!("CARGO_PKG_VERSION").to_string(),
                 });
             }
         }
         
+        // Leave out items gated on features that are not enabled, and
+        // sources left with none of their items
+        let item_sources: HashSet<PathBuf> = implementations.iter().map(|i| i.source_file_path.clone()).collect();
+        dedup_references(&mut implementations, &mut warnings);
+        resolve_tool_references(&mut implementations);
+        let excluded_count = select_features(&self.features, &mut implementations);
+        if excluded_count > 0 {
+            drop_excluded_sources(&mut compiled_code_map, &source_imports, &item_sources, &implementations);
+        }
+        
         // 4. Generate manifest
-        let manifest = self.generate_manifest(&implementations)?;
+        progress(CompileProgress::Phase("manifest"));
+        let phase_start = std::time::Instant::now();
+        Self::validate_names(&implementations)?;
+        let mut manifest = self.generate_manifest(&implementations)?;
         
-        // 5. Validate cross-references
-        if let Err(e) = self.validate_cross_references(&manifest) {
-            return Err(e);
-        }
+        // 5. Validate cross-references, resolving unknown names against dependencies
+        let dependency_tools = self.load_dependency_tools().await?;
+        manifest.externals = if self.validate_references {
+            self.validate_cross_references(&manifest, &implementations, &dependency_tools)?
+        } else {
+            let (externals, errors) = Self::resolve_cross_references(&manifest, &implementations, &dependency_tools);
+            warnings.extend(errors.iter().map(|e| Warning::new(WarningKind::UnresolvedReference, e.to_string())));
+            externals
+        };
+        // Sources are all kept, since selected items may import from any of them
+        let implementations = select_only(&self.only, implementations, &mut manifest, &mut warnings)?;
+        phase_timings.push(("manifest".to_string(), phase_start.elapsed().as_secs_f64()));
+        // The last checkpoint: everything past it writes output, so a
+        // cancelled build leaves either none of it or all of it
+        checkpoint(cancel)?;
         
-        // 6. Get metrics before moving implementations
-        let source_files_count = compiled_code_map.len();
+        // Write transpiled JavaScript for inspection, if requested
+        if let Some(dir) = &self.emit_js_dir {
+            progress(CompileProgress::Phase("emit-js"));
+            let phase_start = std::time::Instant::now();
+            emit_javascript(dir, input_paths, &compiled_code_map).await?;
+            phase_timings.push(("emit-js".to_string(), phase_start.elapsed().as_secs_f64()));
+            
+            if self.emit_js_only {
+                return Ok(CompilationResult {
+                    bundle_size_kb: 0.0, // No bundle written
+                    uncompressed_size_kb: 0.0, // No bundle written
+                    tools_count: manifest.tools.len(),
+                    agents_count: manifest.agents.len(),
+                    teams_count: manifest.teams.len(),
+                    pipelines_count: manifest.pipelines.len(),
+                    workflows_count: manifest.workflows.len(),
+                    discovered_files_count,
+                    source_files_count,
+                    skipped_files_count,
+                    dependencies_count: 0,
+                    compilation_time_secs: start_time.elapsed().as_secs_f64(),
+                    compression_ratio: 0.0, // No bundle written
+                    manifest_bytes_saved: 0,
+                    excluded_count,
+                    phase_timings,
+                    warnings,
+                    manifest,
+                    output_path: None,
+                    manifest_only_rebuild: false,
+                });
+            }
+        }
         
-        // 7. Create bundle (this consumes implementations)
+        // 6. Create bundle (this consumes implementations)
+        progress(CompileProgress::Phase("bundle"));
+        let phase_start = std::time::Instant::now();
         let mut bundle = AriaBundle::create(
             manifest,
             implementations,
             compiled_code_map,
         )?;
         
-        // 8. Write to output
-        bundle.save_to_file(output_path).await?;
+        // 7. Write to output
+        bundle.metadata.hash_algorithm = self.hash_algorithm;
+        bundle.manifest.source_encoding = self.source_encoding;
+        bundle.metadata.custom.extend(self.metadata.clone());
+        bundle.store_uncompressed = !self.compress;
+        bundle.manifest_format = self.manifest_format;
+        bundle.file_permissions = self.file_permissions;
+        if all_cached && !output_path.to_string_lossy().contains(HASH_PLACEHOLDER) {
+            bundle.reuse_sources_from = Some(output_path.to_path_buf());
+        }
+        let output_path = bundle.save_to_file_staged(output_path, self.target_dir.as_deref()).await?;
+        phase_timings.push(("bundle".to_string(), phase_start.elapsed().as_secs_f64()));
         
-        // 9. Calculate metrics
+        // 8. Calculate metrics
         let compilation_time = start_time.elapsed();
-        let bundle_size = tokio::fs::metadata(output_path).await?.len();
+        let bundle_size = tokio::fs::metadata(&output_path).await?.len();
+        let uncompressed_size = AriaBundle::uncompressed_size(&output_path).await?;
+        let manifest_bytes_saved = match self.manifest_format {
+            ManifestFormat::Pretty => 0,
+            ManifestFormat::Compact => {
+                ManifestFormat::Pretty.to_json(&bundle.manifest)?.len() - ManifestFormat::Compact.to_json(&bundle.manifest)?.len()
+            }
+        };
         
         Ok(CompilationResult {
             bundle_size_kb: bundle_size as f64 / 1024.0,
+            uncompressed_size_kb: uncompressed_size as f64 / 1024.0,
             tools_count: bundle.manifest.tools.len(),
             agents_count: bundle.manifest.agents.len(),
             teams_count: bundle.manifest.teams.len(),
             pipelines_count: bundle.manifest.pipelines.len(),
+            workflows_count: bundle.manifest.workflows.len(),
+            discovered_files_count,
             source_files_count,
+            skipped_files_count,
             dependencies_count: 0, // TODO: Calculate actual dependencies
             compilation_time_secs: compilation_time.as_secs_f64(),
-            compression_ratio: 0.7, // TODO: Calculate actual compression
+            compression_ratio: if uncompressed_size > 0 {
+                bundle_size as f64 / uncompressed_size as f64
+            } else {
+                1.0
+            },
+            manifest_bytes_saved,
+            excluded_count,
+            phase_timings,
             warnings,
+            manifest: bundle.manifest.clone(),
+            output_path: Some(output_path),
+            manifest_only_rebuild: bundle.sources_reused,
         })
     }
     
-    /// Discover source files in the input path
-    async fn discover_sources(&self, input_path: &str) -> Result<Vec<SourceFile>> {
+    /// Compile the local modules imported by `compiled` that discovery did not
+    /// find, following their imports in turn. A failure is fatal, since the
+    /// importing file cannot run without the module.
+    async fn compile_local_imports(&self, compiled: &[CompiledFile]) -> Result<Vec<CompiledFile>> {
+        let mut known: HashSet<PathBuf> = compiled.iter().map(|f| f.source.path.clone()).collect();
+        let mut pending: Vec<PathBuf> = compiled.iter().flat_map(|f| f.imports.iter().cloned()).collect();
+        let mut imported = Vec::new();
+        
+        while !pending.is_empty() {
+            let mut sources = Vec::new();
+            for path in pending.drain(..) {
+                if known.insert(path.clone()) {
+                    tracing::debug!("Following local import: {}", path.display());
+                    sources.push(load_source_file(&path).await?);
+                }
+            }
+            
+            for (source, result) in sources.iter().zip(self.compile_typescript_sources(&sources).await?) {
+                match result {
+                    Ok(file) => {
+                        pending.extend(file.imports.iter().cloned());
+                        imported.push(file);
+                    }
+                    Err(e) => {
+                        let diagnostics = Diagnostic::for_file(&source.path, &e);
+                        return Err(CompileError::new(e.to_string(), diagnostics).into());
+                    }
+                }
+            }
+        }
+        
+        Ok(imported)
+    }
+    
+    /// Compile every TypeScript source on the blocking pool, at most `jobs`
+    /// at a time. Results are returned in source order.
+    async fn compile_typescript_sources(&self, sources: &[SourceFile]) -> Result<Vec<Result<CompiledFile>>> {
+        self.compile_typescript_sources_with(sources, &|_| {}, &CancellationToken::new()).await
+    }
+    
+    /// `compile_typescript_sources`, reporting each file to `progress` as its
+    /// result is collected and returning [`Cancelled`] once `cancel` fires.
+    /// Files already on the blocking pool finish, but their results are dropped.
+    async fn compile_typescript_sources_with(
+        &self,
+        sources: &[SourceFile],
+        progress: &(dyn Fn(CompileProgress) + Send + Sync),
+        cancel: &CancellationToken,
+    ) -> Result<Vec<Result<CompiledFile>>> {
+        tracing::debug!("Compiling with {} job(s)", self.jobs);
+        
+        let semaphore = Arc::new(Semaphore::new(self.jobs));
+        let mut pending = Vec::new();
+        let mut unchanged = self.load_unchanged(sources).await;
+        let cache_options = self.cache_options();
+        
+        for source in sources.iter().filter(|s| s.language.is_script()) {
+            if let Some(cached) = unchanged.remove(&source.path) {
+                pending.push((source.path.clone(), PendingFile::Cached(Box::new(cached))));
+                continue;
+            }
+            
+            let permit = tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit?,
+                _ = cancel.cancelled() => return Err(Cancelled.into()),
+            };
+            let path = source.path.clone();
+            let source = source.clone();
+            let transforms = self.transforms.clone();
+            let dump_ast = self.dump_ast;
+            let lint_unused_imports = self.lint_unused_imports;
+            let span = tracing::Span::current();
+            pending.push((path, PendingFile::Compiling(tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                let _span = span.enter();
+                // swc source maps are not shared across threads, so each file
+                // gets its own compiler
+                transforms.into_iter()
+                    .fold(TypeScriptCompiler::default(), TypeScriptCompiler::with_transform_factory)
+                    .dump_ast(dump_ast)
+                    .lint_unused_imports(lint_unused_imports)
+                    .compile_file(&source)
+            }))));
+        }
+        
+        let total = pending.len();
+        let mut results = Vec::with_capacity(total);
+        for (path, file) in pending {
+            let result = match file {
+                PendingFile::Cached(cached) => Ok(*cached),
+                PendingFile::Compiling(task) => tokio::select! {
+                    result = task => result?,
+                    _ = cancel.cancelled() => return Err(Cancelled.into()),
+                },
+            };
+            if let (Some(cache), Ok(compiled)) = (&self.cache, &result) {
+                // A failed write only costs a recompile next time
+                if let Err(e) = cache.store(compiled, &cache_options).await {
+                    tracing::debug!("Failed to cache {}: {}", path.display(), e);
+                }
+            }
+            // Descriptions are read on every build, so cached entries never hold stale ones
+            let result = match result {
+                Ok(compiled) => self.resolve_descriptions(compiled).await,
+                Err(e) => Err(e),
+            };
+            results.push(result);
+            progress(CompileProgress::File { path, done: results.len(), total });
+        }
+        
+        Ok(results)
+    }
+    
+    /// Fill in the descriptions of `file`'s items that name a
+    /// `descriptionFile`, read relative to the source's directory
+    async fn resolve_descriptions(&self, mut file: CompiledFile) -> Result<CompiledFile> {
+        let dir = file.source.path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let mut errors = Vec::new();
+        
+        for DescriptionFile { item, path } in &file.description_files {
+            let path = dir.join(path);
+            self.description_files.lock().unwrap().insert(path.clone());
+            match tokio::fs::read_to_string(&path).await {
+                Ok(content) => if let Some(item) = file.items.get_mut(*item) {
+                    item.set_description(content.trim().to_string());
+                },
+                Err(e) => errors.push(format!("Cannot read description file {}: {}", path.display(), e)),
+            }
+        }
+        
+        if !errors.is_empty() {
+            let diagnostics: Vec<Diagnostic> = errors.into_iter()
+                .map(|e| Diagnostic::message(e).with_code(DiagnosticCode::InvalidDecorator))
+                .collect();
+            let message = format!(
+                "Invalid decorator metadata in {}:\n - {}",
+                file.source.path.display(),
+                diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+            );
+            return Err(CompileError::new(message, diagnostics).into());
+        }
+        
+        Ok(file)
+    }
+    
+    /// When building incrementally, the cached output of every source that
+    /// can skip compiling, keyed by source path: the source is unchanged, is
+    /// cached for the current options, and imports nothing that is changed
+    /// or recompiled, directly or through other imports
+    async fn load_unchanged(&self, sources: &[SourceFile]) -> HashMap<PathBuf, CompiledFile> {
+        let (Some(cache), Some(changed)) = (&self.cache, &self.changed_files) else {
+            return HashMap::new();
+        };
+        // Transforms are closures, so a cached entry cannot tell which ones it was compiled with
+        if !self.transforms.is_empty() {
+            return HashMap::new();
+        }
+        
+        // git reports absolute paths while sources and their imports are
+        // relative to the input, so compare canonical paths throughout
+        let options = self.cache_options();
+        let mut stale: HashSet<PathBuf> = changed.iter().map(|path| canonical_path(path)).collect();
+        let mut unchanged = HashMap::new();
+        for source in sources.iter().filter(|s| s.language.is_script()) {
+            let path = canonical_path(&source.path);
+            match cache.load(source, &options).await {
+                Some(cached) if !stale.contains(&path) => {
+                    let imports: Vec<PathBuf> = cached.imports.iter().map(|import| canonical_path(import)).collect();
+                    unchanged.insert(path, (imports, cached));
+                }
+                _ => {
+                    stale.insert(path);
+                }
+            }
+        }
+        
+        // Importing a stale file makes a file stale, until no more go stale
+        loop {
+            let newly_stale: Vec<PathBuf> = unchanged.iter()
+                .filter(|(_, (imports, _))| imports.iter().any(|import| stale.contains(import)))
+                .map(|(path, _)| path.clone())
+                .collect();
+            if newly_stale.is_empty() {
+                break;
+            }
+            for path in newly_stale {
+                unchanged.remove(&path);
+                stale.insert(path);
+            }
+        }
+        
+        unchanged.into_values()
+            .map(|(_, cached)| (cached.source.path.clone(), cached))
+            .collect()
+    }
+    
+    /// The options a cache entry must have been compiled with to be reused
+    fn cache_options(&self) -> String {
+        let mut features = self.features.clone();
+        features.sort();
+        format!("lint_unused_imports={};features={}", self.lint_unused_imports, features.join(","))
+    }
+    
+    /// Discover source files across all input paths, skipping duplicates
+    #[tracing::instrument(name = "discovery", skip(self))]
+    async fn discover_sources(&self, input_paths: &[&str]) -> Result<Vec<SourceFile>> {
+        if let Some(content) = &self.stdin_source {
+            return Ok(vec![SourceFile {
+                path: PathBuf::from(STDIN_PATH),
+                content: content.clone(),
+                language: SourceLanguage::TypeScript,
+            }]);
+        }
+        
+        let exclude = exclude_matcher(&self.exclude)?;
+        // Output from an earlier `--emit-js-dir` run is not a source
+        let skip_dirs: Vec<PathBuf> = self.emit_js_dir.iter().filter_map(|dir| dir.canonicalize().ok()).collect();
+        let filter = DiscoveryFilter { exclude: &exclude, include_js: self.include_js, skip_dirs: &skip_dirs };
+        let mut sources = Vec::new();
+        let mut seen = HashSet::new();
+        
+        for input_path in input_paths {
+            for source in self.discover_sources_in(input_path, &filter).await? {
+                if seen.insert(source.path.clone()) {
+                    sources.push(source);
+                }
+            }
+        }
+        
+        Ok(sources)
+    }
+    
+    /// Discover source files in a single input path, applying `filter`
+    /// inside directories and archives
+    async fn discover_sources_in(&self, input_path: &str, filter: &DiscoveryFilter<'_>) -> Result<Vec<SourceFile>> {
         let mut sources = Vec::new();
         let path = Path::new(input_path);
         
-        if path.is_file() {
+        if path.is_file() && is_source_archive(path) {
+            // Archive - find TypeScript files in a temporary extraction
+            sources = discover_archive_sources(path, filter).await?;
+        } else if path.is_file() {
             // Single file
             let source = load_source_file(path).await?;
             sources.push(source);
         } else if path.is_dir() {
             // Directory - find all TypeScript files
-            sources = discover_typescript_files(path).await?;
+            sources = discover_typescript_files(path, path, filter).await?;
         } else {
             return Err(anyhow!("Input path does not exist: {}", input_path));
         }
@@ -155,6 +819,7 @@ impl AriaCompiler {
         let mut agents = Vec::new();
         let mut teams = Vec::new();
         let mut pipelines = Vec::new();
+        let mut workflows = Vec::new();
         
         for implementation in implementations {
             match &implementation.details {
@@ -162,54 +827,121 @@ impl AriaCompiler {
                 ImplementationDetails::Agent(agent_manifest) => agents.push(agent_manifest.clone()),
                 ImplementationDetails::Team(team_manifest) => teams.push(team_manifest.clone()),
                 ImplementationDetails::Pipeline(pipeline_manifest) => pipelines.push(pipeline_manifest.clone()),
+                ImplementationDetails::Workflow(workflow_manifest) => workflows.push(workflow_manifest.clone()),
             }
         }
         
+        // Implementations arrive in discovery order, which depends on the
+        // filesystem; sorting keeps manifest.json identical across machines
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        agents.sort_by(|a, b| a.name.cmp(&b.name));
+        teams.sort_by(|a, b| a.name.cmp(&b.name));
+        pipelines.sort_by(|a, b| a.name.cmp(&b.name));
+        workflows.sort_by(|a, b| a.name.cmp(&b.name));
+        
         Ok(AriaManifest {
+            manifest_version: MANIFEST_VERSION,
             name: "Generated Bundle".to_string(), // TODO: Get from config
             version: "0.1.0".to_string(),
             tools,
             agents,
             teams,
             pipelines,
+            workflows,
+            externals: Vec::new(),
+            implementations: Vec::new(),
+            source_encoding: SourceEncoding::default(),
         })
     }
 
     /// Run a compilation check without creating a bundle.
     pub async fn check_project(
         &self,
-        input_path: &str,
-        verbose: bool,
+        input_paths: &[&str],
+    ) -> Result<CompilationResult> {
+        self.check_project_with(input_paths, &mut |_| Ok(())).await
+    }
+    
+    /// `check_project`, passing the items extracted from each discovered
+    /// file to `on_items` once every file has compiled, before they are
+    /// validated. An error from `on_items` fails the check.
+    #[tracing::instrument(name = "check", skip(self, on_items))]
+    pub async fn check_project_with(
+        &self,
+        input_paths: &[&str],
+        on_items: &mut (dyn FnMut(&[CompiledFile]) -> Result<()> + Send),
     ) -> Result<CompilationResult> {
         let start_time = std::time::Instant::now();
         
+        let mut phase_timings = Vec::new();
+        
         // 1. Discover source files
-        let sources = self.discover_sources(input_path).await?;
+        let phase_start = std::time::Instant::now();
+        let sources = self.discover_sources(input_paths).await?;
+        phase_timings.push(("discovery".to_string(), phase_start.elapsed().as_secs_f64()));
         
-        if verbose {
-            println!("Found {} source files", sources.len());
-        }
+        tracing::debug!("Found {} source files", sources.len());
+        
+        let discovered_files_count = sources.len();
         
         // 2. Compile based on source language
         let mut compiled_files: Vec<CompiledFile> = Vec::new();
         let mut warnings = Vec::new();
+        let mut skipped_files_count = 0;
+        let mut file_errors = Vec::new();
+        let mut diagnostics = Vec::new();
+        
+        let mut compiled_results = self.compile_typescript_sources(&sources).await?.into_iter();
         
         for source in sources {
             match source.language {
-                SourceLanguage::TypeScript => {
-                    match self.typescript_compiler.compile_file(&source).await {
-                        Ok(compiled) => compiled_files.push(compiled),
-                        Err(e) => return Err(e),
+                SourceLanguage::TypeScript | SourceLanguage::JavaScript => {
+                    match compiled_results.next().expect("one result per TypeScript source") {
+                        Ok(compiled) => {
+                            warnings.extend(compiled.warnings.iter().cloned());
+                            compiled_files.push(compiled);
+                        }
+                        Err(e) if self.keep_going => {
+                            file_errors.push(format!("{}: {}", source.path.display(), e));
+                            diagnostics.extend(Diagnostic::for_file(&source.path, &e));
+                        }
+                        Err(e) => {
+                            let diagnostics = Diagnostic::for_file(&source.path, &e);
+                            return Err(CompileError::new(e.to_string(), diagnostics).into());
+                        }
                     }
                 }
                 SourceLanguage::AriaSDL => {
-                    warnings.push(format!("Skipping DSL file (not yet implemented): {}", source.path.display()));
+                    skipped_files_count += 1;
+                    warnings.push(Warning::new(
+                        WarningKind::SkippedFile,
+                        format!("Skipping DSL file (not yet implemented): {}", source.path.display()),
+                    ).in_file(&source.path));
                 }
             }
         }
         
+        if !file_errors.is_empty() {
+            let message = format!(
+                "{} file(s) failed to compile:\n - {}",
+                file_errors.len(),
+                file_errors.join("\n - ")
+            );
+            return Err(CompileError::new(message, diagnostics).into());
+        }
+        on_items(&compiled_files)?;
+        
+        // Local modules imported from outside the input paths are checked too
+        for imported in self.compile_local_imports(&compiled_files).await? {
+            warnings.extend(imported.warnings.iter().cloned());
+            compiled_files.push(imported);
+        }
+        
+        phase_timings.push(("parse".to_string(), compiled_files.iter().map(|f| f.parse_time_secs).sum()));
+        phase_timings.push(("transpile".to_string(), compiled_files.iter().map(|f| f.transpile_time_secs).sum()));
+        
         if compiled_files.iter().all(|f| f.items.is_empty()) {
-            warnings.push("No decorated functions or classes found".to_string());
+            warnings.push(Warning::new(WarningKind::NoDecorators, "No decorated functions or classes found"));
         }
         
         // 3. Process compiled files into implementations
@@ -221,76 +953,277 @@ impl AriaCompiler {
                     ExtractedItem::Agent { manifest } => (manifest.name.clone(), ImplementationDetails::Agent(manifest.clone())),
                     ExtractedItem::Team { manifest } => (manifest.name.clone(), ImplementationDetails::Team(manifest.clone())),
                     ExtractedItem::Pipeline { manifest } => (manifest.name.clone(), ImplementationDetails::Pipeline(manifest.clone())),
+                    ExtractedItem::Workflow { manifest } => (manifest.name.clone(), ImplementationDetails::Workflow(manifest.clone())),
                 };
                 implementations.push(Implementation {
                     name,
                     details,
                     source_file_path: file.source.path.clone(),
+                    source_language: file.source.language.clone(),
+                    compiler_version: env!("CARGO_PKG_VERSION").to_string(),
                 });
             }
         }
+        dedup_references(&mut implementations, &mut warnings);
+        resolve_tool_references(&mut implementations);
+        let excluded_count = select_features(&self.features, &mut implementations);
         
         // 4. Generate manifest
-        let manifest = self.generate_manifest(&implementations)?;
+        let phase_start = std::time::Instant::now();
+        Self::validate_names(&implementations)?;
+        let mut manifest = self.generate_manifest(&implementations)?;
         
-        // 5. Validate cross-references
-        if let Err(e) = self.validate_cross_references(&manifest) {
-            return Err(e);
+        // 5. Validate cross-references, resolving unknown names against dependencies
+        let dependency_tools = self.load_dependency_tools().await?;
+        manifest.externals = self.validate_cross_references(&manifest, &implementations, &dependency_tools)?;
+        let implementations = select_only(&self.only, implementations, &mut manifest, &mut warnings)?;
+        if self.strict {
+            Self::validate_strict(&implementations)?;
         }
+        phase_timings.push(("manifest".to_string(), phase_start.elapsed().as_secs_f64()));
         
         let compilation_time = start_time.elapsed();
         
         Ok(CompilationResult {
             bundle_size_kb: 0.0, // Not applicable
+            uncompressed_size_kb: 0.0, // Not applicable
             tools_count: manifest.tools.len(),
             agents_count: manifest.agents.len(),
             teams_count: manifest.teams.len(),
             pipelines_count: manifest.pipelines.len(),
+            workflows_count: manifest.workflows.len(),
+            discovered_files_count,
             source_files_count: compiled_files.len(),
+            skipped_files_count,
             dependencies_count: 0,
             compilation_time_secs: compilation_time.as_secs_f64(),
             compression_ratio: 0.0, // Not applicable
+            manifest_bytes_saved: 0,
+            excluded_count,
+            phase_timings,
             warnings,
+            manifest,
+            output_path: None,
+            manifest_only_rebuild: false,
         })
     }
 
+    /// Map each tool defined by a dependency bundle to the bundles defining it
+    async fn load_dependency_tools(&self) -> Result<HashMap<String, Vec<String>>> {
+        let mut tools: HashMap<String, Vec<String>> = HashMap::new();
+        
+        for path in &self.dependencies {
+            let bundle = AriaBundle::load_from_file(&path.to_string_lossy()).await
+                .map_err(|e| anyhow!("Failed to load dependency bundle {}: {}", path.display(), e))?;
+            for tool in &bundle.manifest.tools {
+                tools.entry(tool.name.clone()).or_default().push(bundle.manifest.name.clone());
+            }
+        }
+        
+        Ok(tools)
+    }
+
     /// Validates that all cross-references within the manifest are valid.
     /// For example, ensures that agents only reference tools that are actually defined.
-    fn validate_cross_references(&self, manifest: &AriaManifest) -> Result<()> {
-        let mut errors = Vec::new();
+    /// Tools found only in a dependency bundle are returned as external references.
+    fn validate_cross_references(
+        &self,
+        manifest: &AriaManifest,
+        implementations: &[Implementation],
+        dependency_tools: &HashMap<String, Vec<String>>,
+    ) -> Result<Vec<ExternalReference>> {
+        let (externals, errors) = Self::resolve_cross_references(manifest, implementations, dependency_tools);
+
+        if !errors.is_empty() {
+            let message = format!(
+                "Cross-reference validation failed:\n - {}",
+                errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+            );
+            return Err(CompileError::new(message, errors).into());
+        }
+
+        Ok(externals)
+    }
+
+    /// Resolve the manifest's cross-references, returning the external
+    /// references along with every reference that failed to resolve
+    pub(crate) fn resolve_cross_references(
+        manifest: &AriaManifest,
+        implementations: &[Implementation],
+        dependency_tools: &HashMap<String, Vec<String>>,
+    ) -> (Vec<ExternalReference>, Vec<Diagnostic>) {
+        let mut errors: Vec<Diagnostic> = Vec::new();
 
         // --- Tool validation ---
-        let defined_tools: HashSet<_> = manifest.tools.iter().map(|t| &t.name).collect();
+        let tools: Vec<(&ToolManifest, &PathBuf)> = implementations.iter()
+            .filter_map(|implementation| match &implementation.details {
+                ImplementationDetails::Tool(tool) => Some((tool, &implementation.source_file_path)),
+                _ => None,
+            })
+            .collect();
+
+        let mut externals: Vec<ExternalReference> = Vec::new();
 
         for agent in &manifest.agents {
             for tool_name in &agent.tools {
-                if !defined_tools.contains(tool_name) {
-                    errors.push(format!(
-                        "Agent '{}' references undefined tool: '{}'",
-                        agent.name, tool_name
-                    ));
+                match tool_candidates(&tools, &agent.name, tool_name).as_slice() {
+                    [] => match dependency_tools.get(tool_name).map(Vec::as_slice) {
+                        None | Some([]) => errors.push(Diagnostic::message(format!(
+                            "Agent '{}' references undefined tool: '{}'",
+                            agent.name, tool_name
+                        )).with_code(DiagnosticCode::UndefinedReference)),
+                        Some([bundle]) => {
+                            if !externals.iter().any(|e| &e.name == tool_name) {
+                                externals.push(ExternalReference {
+                                    kind: "tool".to_string(),
+                                    name: tool_name.clone(),
+                                    bundle: bundle.clone(),
+                                    external: true,
+                                });
+                            }
+                        }
+                        Some(bundles) => errors.push(Diagnostic::message(format!(
+                            "Agent '{}' references ambiguous tool '{}', provided by dependencies: {}",
+                            agent.name,
+                            tool_name,
+                            bundles.join(", ")
+                        )).with_code(DiagnosticCode::DuplicateName)),
+                    },
+                    [_] => {}
+                    candidates => errors.push(Diagnostic::message(format!(
+                        "Agent '{}' references ambiguous tool '{}', candidates: {}",
+                        agent.name,
+                        tool_name,
+                        candidates.iter()
+                            .map(|(tool, path)| format!("'{}' in {}", tool.name, path.display()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )).with_code(DiagnosticCode::DuplicateName)),
                 }
             }
         }
 
-        // --- (Future) Team validation ---
-        // let defined_agents: HashSet<_> = manifest.agents.iter().map(|a| &a.name).collect();
-        // for team in &manifest.teams {
-        //     for agent_name in &team.agents {
-        //         if !defined_agents.contains(agent_name) {
-        //             errors.push(format!(
-        //                 "Team '{}' references undefined agent: '{}'",
-        //                 team.name, agent_name
-        //             ));
-        //         }
-        //     }
-        // }
+        // --- Team validation ---
+        let defined_agents: HashSet<_> = manifest.agents.iter().map(|a| &a.name).collect();
+        for team in &manifest.teams {
+            for member in &team.members {
+                if !defined_agents.contains(&member.agent) {
+                    errors.push(Diagnostic::message(format!(
+                        "Team '{}' references undefined agent: '{}'",
+                        team.name, member.agent
+                    )).with_code(DiagnosticCode::UndefinedReference));
+                }
+            }
+        }
+
+        // --- Workflow validation ---
+        // A step may name anything the runtime can invoke, including a tool
+        // provided by a dependency bundle.
+        let defined_steps: HashSet<&String> = tools.iter().map(|(tool, _)| &tool.name)
+            .chain(manifest.agents.iter().map(|a| &a.name))
+            .chain(manifest.teams.iter().map(|t| &t.name))
+            .chain(manifest.pipelines.iter().map(|p| &p.name))
+            .collect();
+        for workflow in &manifest.workflows {
+            for step in &workflow.steps {
+                if defined_steps.contains(step) {
+                    continue;
+                }
+                match dependency_tools.get(step).map(Vec::as_slice) {
+                    None | Some([]) => errors.push(Diagnostic::message(format!(
+                        "Workflow '{}' step references undefined name: '{}'",
+                        workflow.name, step
+                    )).with_code(DiagnosticCode::UndefinedReference)),
+                    Some([bundle]) => {
+                        if !externals.iter().any(|e| &e.name == step) {
+                            externals.push(ExternalReference {
+                                kind: "tool".to_string(),
+                                name: step.clone(),
+                                bundle: bundle.clone(),
+                                external: true,
+                            });
+                        }
+                    }
+                    Some(bundles) => errors.push(Diagnostic::message(format!(
+                        "Workflow '{}' step references ambiguous tool '{}', provided by dependencies: {}",
+                        workflow.name,
+                        step,
+                        bundles.join(", ")
+                    )).with_code(DiagnosticCode::DuplicateName)),
+                }
+            }
+        }
+
+        (externals, errors)
+    }
+
+    /// Reject names that could escape the bundle's `implementations/`
+    /// directory or break the generated re-export stubs
+    fn validate_names(implementations: &[Implementation]) -> Result<()> {
+        let errors: Vec<Diagnostic> = implementations.iter()
+            .filter(|implementation| !is_valid_name(&implementation.name))
+            .map(|implementation| {
+                let kind = match &implementation.details {
+                    ImplementationDetails::Tool(_) => "Tool",
+                    ImplementationDetails::Agent(_) => "Agent",
+                    ImplementationDetails::Team(_) => "Team",
+                    ImplementationDetails::Pipeline(_) => "Pipeline",
+                    ImplementationDetails::Workflow(_) => "Workflow",
+                };
+                Diagnostic::message(format!(
+                    "{} name {:?} may only contain letters, digits, '_', '$', '-' and '.', and may not start with '.'",
+                    kind, implementation.name
+                ))
+                    .with_code(DiagnosticCode::InvalidName)
+                    .in_file(&implementation.source_file_path)
+            })
+            .collect();
 
         if !errors.is_empty() {
-            return Err(anyhow!(
-                "Cross-reference validation failed:\n - {}",
-                errors.join("\n - ")
-            ));
+            let message = format!(
+                "Invalid names:\n - {}",
+                errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+            );
+            return Err(CompileError::new(message, errors).into());
+        }
+
+        Ok(())
+    }
+
+    /// Strict mode checks for published bundles: every item needs a
+    /// description, and an agent with no tools is most likely a mistake.
+    fn validate_strict(implementations: &[Implementation]) -> Result<()> {
+        let mut errors: Vec<Diagnostic> = Vec::new();
+
+        for implementation in implementations {
+            let (kind, description) = match &implementation.details {
+                ImplementationDetails::Tool(tool) => ("Tool", &tool.description),
+                ImplementationDetails::Agent(agent) => ("Agent", &agent.description),
+                ImplementationDetails::Team(team) => ("Team", &team.description),
+                ImplementationDetails::Pipeline(pipeline) => ("Pipeline", &pipeline.description),
+                ImplementationDetails::Workflow(workflow) => ("Workflow", &workflow.description),
+            };
+            if description.trim().is_empty() {
+                errors.push(Diagnostic::message(format!("{} '{}' has no description", kind, implementation.name))
+                    .with_code(DiagnosticCode::MissingDescription)
+                    .in_file(&implementation.source_file_path));
+            }
+
+            if let ImplementationDetails::Agent(agent) = &implementation.details {
+                if agent.tools.is_empty() {
+                    errors.push(Diagnostic::message(format!("Agent '{}' has no tools", agent.name))
+                        .with_code(DiagnosticCode::AgentWithoutTools)
+                        .in_file(&implementation.source_file_path));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            let message = format!(
+                "Strict validation failed:\n - {}",
+                errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+            );
+            return Err(CompileError::new(message, errors).into());
         }
 
         Ok(())
@@ -318,21 +1251,46 @@ pub struct CompiledFile {
     pub source: SourceFile,
     pub javascript_code: String,
     pub items: Vec<ExtractedItem>,
+    /// Description files named by `items`, not yet read into them
+    pub description_files: Vec<DescriptionFile>,
+    /// Local sources this file imports through relative specifiers
+    pub imports: Vec<PathBuf>,
+    /// npm packages this file imports at runtime
+    pub packages: Vec<String>,
+    pub warnings: Vec<Warning>,
+    pub parse_time_secs: f64,
+    pub transpile_time_secs: f64,
+    /// Reused from the compile cache instead of compiled
+    pub cached: bool,
 }
 
 /// Supported source languages
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub enum SourceLanguage {
+    #[default]
     TypeScript,
+    /// Compiled through the TypeScript pipeline, parsed as ECMAScript
+    JavaScript,
     AriaSDL, // Future
 }
 
+impl SourceLanguage {
+    /// Whether sources in this language are compiled by the TypeScript compiler
+    pub fn is_script(&self) -> bool {
+        matches!(self, SourceLanguage::TypeScript | SourceLanguage::JavaScript)
+    }
+}
+
 /// Final, bundle-ready implementation data.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Implementation {
     pub name: String,
     pub details: ImplementationDetails,
     pub source_file_path: PathBuf,
+    /// Language the implementation was written in
+    pub source_language: SourceLanguage,
+    /// Version of arc that compiled it
+    pub compiler_version: String,
 }
 
 /// Enum to hold manifest details for different implementation types.
@@ -342,6 +1300,20 @@ pub enum ImplementationDetails {
     Agent(AgentManifest),
     Team(TeamManifest),
     Pipeline(PipelineManifest),
+    Workflow(WorkflowManifest),
+}
+
+impl ImplementationDetails {
+    /// Manifest kind name, as used in `implementations` records
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ImplementationDetails::Tool(_) => "tool",
+            ImplementationDetails::Agent(_) => "agent",
+            ImplementationDetails::Team(_) => "team",
+            ImplementationDetails::Pipeline(_) => "pipeline",
+            ImplementationDetails::Workflow(_) => "workflow",
+        }
+    }
 }
 
 /// Type of implementation
@@ -363,31 +1335,408 @@ pub struct DecoratorMetadata {
 #[derive(Debug)]
 pub struct CompilationResult {
     pub bundle_size_kb: f64,
+    /// Total size of the files in the bundle before compression
+    pub uncompressed_size_kb: f64,
     pub tools_count: usize,
     pub agents_count: usize,
     pub teams_count: usize,
     pub pipelines_count: usize,
+    pub workflows_count: usize,
+    /// Source files found during discovery
+    pub discovered_files_count: usize,
+    /// Source files that were compiled to JavaScript
     pub source_files_count: usize,
+    /// Source files that were discovered but not compiled (e.g. DSL files)
+    pub skipped_files_count: usize,
     pub dependencies_count: usize,
     pub compilation_time_secs: f64,
+    /// Bundle size on disk relative to its uncompressed contents
     pub compression_ratio: f64,
-    pub warnings: Vec<String>,
+    /// How much smaller a compact manifest.json is than a pretty one; 0 for
+    /// pretty manifests
+    pub manifest_bytes_saved: usize,
+    /// Elapsed seconds per compilation phase, in execution order
+    pub phase_timings: Vec<(String, f64)>,
+    pub warnings: Vec<Warning>,
+    /// The generated manifest, after cross-reference validation
+    pub manifest: AriaManifest,
+    /// Where the bundle was written, with any `{hash}` placeholder filled in
+    pub output_path: Option<PathBuf>,
+    /// Tools and agents left out because their feature was not enabled
+    pub excluded_count: usize,
+    /// Every source came from the compile cache and was copied from the
+    /// previous bundle, so only the manifest was regenerated
+    pub manifest_only_rebuild: bool,
+}
+
+/// A source file queued by `compile_typescript_sources_with`
+enum PendingFile {
+    /// Output reused from the compile cache
+    Cached(Box<CompiledFile>),
+    Compiling(tokio::task::JoinHandle<Result<CompiledFile>>),
+}
+
+/// Progress reported by `AriaCompiler::compile_project_with`
+#[derive(Debug, Clone)]
+pub enum CompileProgress {
+    /// A compilation phase is starting, named as in `phase_timings`
+    Phase(&'static str),
+    /// A source file finished compiling, the `done`th of `total`
+    File { path: PathBuf, done: usize, total: usize },
+}
+
+/// Keep the implementations named in `only` plus everything they reference,
+/// transitively: an agent's tools, a team's agents and a workflow's steps.
+/// The manifest, its externals and `warnings` are pruned to match; warnings
+/// not tied to a file are kept. Empty `only` keeps everything.
+fn select_only(
+    only: &[String],
+    mut implementations: Vec<Implementation>,
+    manifest: &mut AriaManifest,
+    warnings: &mut Vec<Warning>,
+) -> Result<Vec<Implementation>> {
+    if only.is_empty() {
+        return Ok(implementations);
+    }
+    
+    let unknown: Vec<&str> = only.iter()
+        .filter(|name| !implementations.iter().any(|i| &i.name == *name))
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(anyhow!("--only names undefined items: {}", unknown.join(", ")));
+    }
+    
+    let mut selected: HashSet<String> = HashSet::new();
+    let mut pending: Vec<String> = only.to_vec();
+    while let Some(name) = pending.pop() {
+        if !selected.insert(name.clone()) {
+            continue;
+        }
+        for implementation in implementations.iter().filter(|i| i.name == name) {
+            match &implementation.details {
+                ImplementationDetails::Agent(agent) => pending.extend(agent.tools.iter().cloned()),
+                ImplementationDetails::Team(team) => pending.extend(team.members.iter().map(|m| m.agent.clone())),
+                ImplementationDetails::Workflow(workflow) => pending.extend(workflow.steps.iter().cloned()),
+                ImplementationDetails::Tool(_) | ImplementationDetails::Pipeline(_) => {}
+            }
+        }
+    }
+    
+    implementations.retain(|i| selected.contains(&i.name));
+    manifest.tools.retain(|t| selected.contains(&t.name));
+    manifest.agents.retain(|a| selected.contains(&a.name));
+    manifest.teams.retain(|t| selected.contains(&t.name));
+    manifest.pipelines.retain(|p| selected.contains(&p.name));
+    manifest.workflows.retain(|w| selected.contains(&w.name));
+    manifest.externals.retain(|e| selected.contains(&e.name));
+    
+    let files: HashSet<&PathBuf> = implementations.iter().map(|i| &i.source_file_path).collect();
+    warnings.retain(|w| w.file.as_ref().is_none_or(|file| files.contains(file)));
+    
+    Ok(implementations)
+}
+
+/// Return [`Cancelled`] if `cancel` has fired
+fn checkpoint(cancel: &CancellationToken) -> Result<()> {
+    if cancel.is_cancelled() {
+        return Err(Cancelled.into());
+    }
+    Ok(())
+}
+
+/// Drop tools and agents gated on a feature not in `features`, and remove
+/// references to them from agents, teams and workflows. Returns how many
+/// items were dropped.
+fn select_features(features: &[String], implementations: &mut Vec<Implementation>) -> usize {
+    let gate = |details: &ImplementationDetails| match details {
+        ImplementationDetails::Tool(tool) => tool.feature.clone(),
+        ImplementationDetails::Agent(agent) => agent.feature.clone(),
+        _ => None,
+    };
+    let excluded: HashSet<String> = implementations.iter()
+        .filter(|i| gate(&i.details).is_some_and(|feature| !features.contains(&feature)))
+        .map(|i| i.name.clone())
+        .collect();
+    if excluded.is_empty() {
+        return 0;
+    }
+    
+    let before = implementations.len();
+    implementations.retain(|i| !excluded.contains(&i.name));
+    for implementation in implementations.iter_mut() {
+        match &mut implementation.details {
+            ImplementationDetails::Agent(agent) => agent.tools.retain(|t| !excluded.contains(t)),
+            ImplementationDetails::Team(team) => team.members.retain(|m| !excluded.contains(&m.agent)),
+            ImplementationDetails::Workflow(workflow) => workflow.steps.retain(|s| !excluded.contains(s)),
+            ImplementationDetails::Tool(_) | ImplementationDetails::Pipeline(_) => {}
+        }
+    }
+    
+    before - implementations.len()
+}
+
+/// Remove from `code` the sources that had items (`item_sources`) but keep
+/// none of them in `implementations`, unless a source that stays imports
+/// them, directly or through other imports
+fn drop_excluded_sources(
+    code: &mut HashMap<PathBuf, String>,
+    imports: &HashMap<PathBuf, Vec<PathBuf>>,
+    item_sources: &HashSet<PathBuf>,
+    implementations: &[Implementation],
+) {
+    let kept: HashSet<&PathBuf> = implementations.iter().map(|i| &i.source_file_path).collect();
+    let mut excluded: HashSet<PathBuf> = item_sources.iter()
+        .filter(|path| !kept.contains(path))
+        .map(|path| canonical_path(path))
+        .collect();
+    // Imports are resolved relative to the importing source, so compare canonical paths
+    let imports: HashMap<PathBuf, Vec<PathBuf>> = imports.iter()
+        .map(|(path, imported)| (canonical_path(path), imported.iter().map(|import| canonical_path(import)).collect()))
+        .collect();
+    
+    let mut pending: Vec<PathBuf> = code.keys()
+        .map(|path| canonical_path(path))
+        .filter(|path| !excluded.contains(path))
+        .collect();
+    while let Some(path) = pending.pop() {
+        for import in imports.get(&path).into_iter().flatten() {
+            if excluded.remove(import) {
+                pending.push(import.clone());
+            }
+        }
+    }
+    
+    code.retain(|path, _| !excluded.contains(&canonical_path(path)));
 }
 
-/// Discover TypeScript files in a directory
-fn discover_typescript_files(dir: &Path) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<SourceFile>>> + Send + '_>> {
+/// Name each `@tool` method `Agent.method` when another tool shares its
+/// name, then point agents' `tools` at the tools they resolve to. References
+/// matching no tool, or several, are left for cross-reference validation.
+fn resolve_tool_references(implementations: &mut [Implementation]) {
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+    for implementation in implementations.iter() {
+        if let ImplementationDetails::Tool(tool) = &implementation.details {
+            *name_counts.entry(tool.name.clone()).or_default() += 1;
+        }
+    }
+    for implementation in implementations.iter_mut() {
+        if let ImplementationDetails::Tool(tool) = &mut implementation.details {
+            if let Some(agent) = tool.agent.as_ref().filter(|_| name_counts[&tool.name] > 1) {
+                tool.name = format!("{}.{}", agent, tool.name);
+                implementation.name = tool.name.clone();
+            }
+        }
+    }
+
+    let tools: Vec<(ToolManifest, PathBuf)> = implementations.iter()
+        .filter_map(|implementation| match &implementation.details {
+            ImplementationDetails::Tool(tool) => Some((tool.clone(), implementation.source_file_path.clone())),
+            _ => None,
+        })
+        .collect();
+    let tools: Vec<(&ToolManifest, &PathBuf)> = tools.iter().map(|(tool, path)| (tool, path)).collect();
+    for implementation in implementations.iter_mut() {
+        if let ImplementationDetails::Agent(agent) = &mut implementation.details {
+            for reference in agent.tools.iter_mut() {
+                if let [(tool, _)] = tool_candidates(&tools, &agent.name, reference).as_slice() {
+                    *reference = tool.name.clone();
+                }
+            }
+            // A method listed in the decorator resolves to the entry added for it
+            retain_first(&mut agent.tools, |tool| tool);
+        }
+    }
+}
+
+/// The tools `agent`'s reference to `name` may resolve to: standalone tools
+/// named `name`, failing those the agent's own `@tool` methods, and failing
+/// those other agents' methods. A method matches its method name and its
+/// `Agent.method` name.
+fn tool_candidates<'a>(
+    tools: &[(&'a ToolManifest, &'a PathBuf)],
+    agent: &str,
+    name: &str,
+) -> Vec<(&'a ToolManifest, &'a PathBuf)> {
+    let mut standalone = Vec::new();
+    let mut own = Vec::new();
+    let mut other = Vec::new();
+    for &(tool, path) in tools {
+        match &tool.agent {
+            None if tool.name == name => standalone.push((tool, path)),
+            None => {}
+            Some(host) => {
+                let qualifier = format!("{}.", host);
+                let method = tool.name.strip_prefix(&qualifier).unwrap_or(&tool.name);
+                if name != method && name.strip_prefix(&qualifier) != Some(method) {
+                    continue;
+                }
+                if host == agent {
+                    own.push((tool, path));
+                } else {
+                    other.push((tool, path));
+                }
+            }
+        }
+    }
+    [standalone, own, other].into_iter().find(|candidates| !candidates.is_empty()).unwrap_or_default()
+}
+
+/// Drop repeated entries from agents' `tools` and teams' `members`,
+/// keeping the first, with a warning naming the item and the repeated name
+fn dedup_references(implementations: &mut [Implementation], warnings: &mut Vec<Warning>) {
+    for implementation in implementations.iter_mut() {
+        let messages: Vec<String> = match &mut implementation.details {
+            ImplementationDetails::Agent(agent) => retain_first(&mut agent.tools, |tool| tool).into_iter()
+                .map(|tool| format!("Agent '{}' lists tool '{}' more than once", agent.name, tool))
+                .collect(),
+            ImplementationDetails::Team(team) => retain_first(&mut team.members, |member| &member.agent).into_iter()
+                .map(|agent| format!("Team '{}' lists agent '{}' as a member more than once", team.name, agent))
+                .collect(),
+            _ => Vec::new(),
+        };
+        warnings.extend(messages.into_iter().map(|message| {
+            Warning::new(WarningKind::DuplicateReference, message).in_file(&implementation.source_file_path)
+        }));
+    }
+}
+
+/// Keep the first entry for each `key` in `entries`, returning the keys
+/// that were repeated
+fn retain_first<T>(entries: &mut Vec<T>, key: fn(&T) -> &String) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut repeated: Vec<String> = Vec::new();
+    entries.retain(|entry| {
+        let name = key(entry);
+        let first = seen.insert(name.clone());
+        if !first && !repeated.contains(name) {
+            repeated.push(name.clone());
+        }
+        first
+    });
+    repeated
+}
+
+/// Fail if a package imported by a compiled file is not installed in a
+/// `node_modules` directory above that file, where Bun would resolve it from
+fn check_installed_packages(compiled_files: &[CompiledFile]) -> Result<()> {
+    let mut missing: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    
+    for file in compiled_files {
+        for package in &file.packages {
+            let installed = file.source.path.ancestors().skip(1)
+                .any(|dir| dir.join("node_modules").join(package).is_dir());
+            if !installed {
+                missing.entry(package).or_default().push(file.source.path.display().to_string());
+            }
+        }
+    }
+    
+    if missing.is_empty() {
+        return Ok(());
+    }
+    
+    let packages: Vec<String> = missing.iter()
+        .map(|(package, files)| format!("{} (imported by {})", package, files.join(", ")))
+        .collect();
+    Err(anyhow!(
+        "{} imported package(s) are not installed, run `bun install`:\n - {}",
+        packages.len(),
+        packages.join("\n - ")
+    ))
+}
+
+/// Write each compiled file's JavaScript under `dir`, at its path relative
+/// to the input directory it was found in (or its file name for file inputs)
+async fn emit_javascript(dir: &Path, input_paths: &[&str], compiled_code: &HashMap<PathBuf, String>) -> Result<()> {
+    let roots: Vec<PathBuf> = input_paths.iter()
+        .filter_map(|path| std::fs::canonicalize(path).ok())
+        .filter(|path| path.is_dir() || is_source_archive(path))
+        .collect();
+    let mut emitted: HashMap<PathBuf, &Path> = HashMap::new();
+    
+    for (source_path, code) in compiled_code {
+        let relative = roots.iter()
+            .find_map(|root| source_path.strip_prefix(root).ok())
+            .map(Path::to_path_buf)
+            .or_else(|| source_path.file_name().map(PathBuf::from))
+            .ok_or_else(|| anyhow!("Cannot name emitted JavaScript for {}", source_path.display()))?;
+        
+        // Only plain components, so nothing can be written outside `dir`
+        if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(anyhow!("Refusing to emit JavaScript for {} outside {}", source_path.display(), dir.display()));
+        }
+        
+        let output = dir.join(relative).with_extension("js");
+        if let Some(other) = emitted.insert(output.clone(), source_path) {
+            return Err(anyhow!(
+                "{} and {} would both be emitted as {}",
+                other.display(), source_path.display(), output.display()
+            ));
+        }
+        
+        if let Some(parent) = output.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&output, code).await
+            .map_err(|e| anyhow!("Failed to write {}: {}", output.display(), e))?;
+    }
+    
+    Ok(())
+}
+
+/// Compile exclude patterns. A pattern without a `/` may match at any depth,
+/// and one ending in `/**` also matches the directory itself, so discovery
+/// can skip the directory instead of every file under it.
+fn exclude_matcher(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./");
+        let pattern = if pattern.contains('/') { pattern.to_string() } else { format!("**/{}", pattern) };
+        let glob = |p: &str| Glob::new(p).map_err(|e| anyhow!("Invalid exclude pattern '{}': {}", pattern, e));
+        builder.add(glob(&pattern)?);
+        if let Some(dir) = pattern.strip_suffix("/**") {
+            builder.add(glob(dir)?);
+        }
+    }
+    builder.build().map_err(|e| anyhow!("Invalid exclude patterns: {}", e))
+}
+
+/// Which files directory discovery picks up
+struct DiscoveryFilter<'a> {
+    /// Paths to leave out, matched relative to the input directory
+    exclude: &'a GlobSet,
+    /// Pick up JavaScript files as well as TypeScript
+    include_js: bool,
+    /// Canonical directories never descended into
+    skip_dirs: &'a [PathBuf],
+}
+
+/// Discover TypeScript files in a directory under `root`, skipping paths
+/// that `filter` leaves out
+fn discover_typescript_files<'a>(
+    dir: &'a Path,
+    root: &'a Path,
+    filter: &'a DiscoveryFilter<'a>,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Vec<SourceFile>>> + Send + 'a>> {
     Box::pin(async move {
         let mut sources = Vec::new();
         let mut entries = tokio::fs::read_dir(dir).await?;
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
+            if filter.exclude.is_match(path.strip_prefix(root).unwrap_or(&path)) {
+                continue;
+            }
 
             if path.is_dir() && !should_skip_directory(&path) {
+                if path.canonicalize().is_ok_and(|dir| filter.skip_dirs.contains(&dir)) {
+                    continue;
+                }
                 // Recursively search subdirectories
-                let mut sub_sources = discover_typescript_files(&path).await?;
+                let mut sub_sources = discover_typescript_files(&path, root, filter).await?;
                 sources.append(&mut sub_sources);
-            } else if path.is_file() && is_typescript_file(&path) {
+            } else if path.is_file() && is_source_file(&path) && (!is_javascript_file(&path) || (filter.include_js && !is_config_file(&path))) {
                 let source = load_source_file(&path).await?;
                 sources.push(source);
             }
@@ -397,6 +1746,25 @@ fn discover_typescript_files(dir: &Path) -> std::pin::Pin<Box<dyn std::future::F
     })
 }
 
+/// Discover TypeScript files inside a source archive. Each file is named by
+/// its path within the archive, under the archive's own path, since the
+/// extraction is removed once the sources are read.
+async fn discover_archive_sources(archive: &Path, filter: &DiscoveryFilter<'_>) -> Result<Vec<SourceFile>> {
+    let archive = std::fs::canonicalize(archive)?;
+    let extracted = {
+        let archive = archive.clone();
+        tokio::task::spawn_blocking(move || ExtractedArchive::extract(&archive)).await??
+    };
+    
+    let mut sources = discover_typescript_files(extracted.path(), extracted.path(), filter).await?;
+    for source in &mut sources {
+        let relative = source.path.strip_prefix(extracted.path())?.to_path_buf();
+        source.path = archive.join(relative);
+    }
+    
+    Ok(sources)
+}
+
 /// Load a single source file
 async fn load_source_file(path: &Path) -> Result<SourceFile> {
     let canonical_path = std::fs::canonicalize(path)?;
@@ -419,24 +1787,82 @@ fn should_skip_directory(path: &Path) -> bool {
     }
 }
 
-/// Check if file is a TypeScript file
-fn is_typescript_file(path: &Path) -> bool {
+/// Check if file is a TypeScript or JavaScript source file
+fn is_source_file(path: &Path) -> bool {
     if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-        matches!(ext, "ts" | "tsx")
+        matches!(ext, "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs")
     } else {
         false
     }
 }
 
+/// Check if file is a JavaScript source file
+fn is_javascript_file(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("js" | "jsx" | "mjs" | "cjs"))
+}
+
+/// Check if file is tooling configuration such as `eslint.config.js`,
+/// which is never an Aria source
+fn is_config_file(path: &Path) -> bool {
+    path.file_stem().and_then(|s| s.to_str()).is_some_and(|stem| stem.ends_with(".config"))
+}
+
 /// Detect source language from file path and content
 fn detect_language(path: &Path, _content: &str) -> SourceLanguage {
     if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
         match ext {
             "ts" | "tsx" => SourceLanguage::TypeScript,
+            "js" | "jsx" | "mjs" | "cjs" => SourceLanguage::JavaScript,
             "aria" => SourceLanguage::AriaSDL, // Future
             _ => SourceLanguage::TypeScript, // Default
         }
     } else {
         SourceLanguage::TypeScript
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// Compile a one-tool project with `emit_js_dir`, cancelling as `phase` starts
+    async fn compile_cancelled_at(phase: &'static str) -> (tempfile::TempDir, Result<CompilationResult>) {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/tools.ts"),
+            "import { tool } from \"@aria/sdk\";\n@tool({ description: \"echo\" })\nexport function echo(input: string): string { return input; }\n",
+        ).unwrap();
+        
+        let compiler = AriaCompiler::new().emit_js_dir(Some(dir.path().join("js")));
+        let cancel = CancellationToken::new();
+        let progress = |event: CompileProgress| {
+            if matches!(event, CompileProgress::Phase(name) if name == phase) {
+                cancel.cancel();
+            }
+        };
+        let input = dir.path().join("src");
+        let result = compiler.compile_project_with(&[input.to_str().unwrap()], &dir.path().join("out.aria"), &progress, &cancel).await;
+        (dir, result)
+    }
+    
+    #[tokio::test]
+    async fn cancelling_before_output_writes_nothing() {
+        for phase in ["discovery", "compile", "manifest"] {
+            let (dir, result) = compile_cancelled_at(phase).await;
+            assert!(result.unwrap_err().is::<Cancelled>(), "cancelled at {}", phase);
+            assert!(!dir.path().join("js").exists(), "JavaScript written after cancelling at {}", phase);
+            assert!(!dir.path().join("out.aria").exists(), "bundle written after cancelling at {}", phase);
+        }
+    }
+    
+    #[tokio::test]
+    async fn cancelling_while_writing_finishes_the_output() {
+        for phase in ["emit-js", "bundle"] {
+            let (dir, result) = compile_cancelled_at(phase).await;
+            result.unwrap();
+            assert!(dir.path().join("js").exists(), "no JavaScript after cancelling at {}", phase);
+            assert!(dir.path().join("out.aria").exists(), "no bundle after cancelling at {}", phase);
+        }
+    }
+}
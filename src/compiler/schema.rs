@@ -3,48 +3,433 @@
 //! These structs are serialized into the `manifest.json` file, which is the
 //! central contract between the compiler and the Aria Runtime.
 
+use anyhow::{Result, anyhow};
+use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use super::SourceLanguage;
+
+/// The manifest format version written by this compiler.
+/// Version 2 writes team members as objects instead of plain agent names.
+/// Version 3 writes tool inputs as objects instead of plain type names.
+pub const MANIFEST_VERSION: u32 = 3;
+
+/// The oldest manifest format version this compiler can read.
+/// Version 0 covers bundles written before the field existed.
+pub const MIN_SUPPORTED_MANIFEST_VERSION: u32 = 0;
 
 /// The root of the bundle manifest.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AriaManifest {
+    #[serde(default)]
+    pub manifest_version: u32,
     pub name: String,
     pub version: String,
     pub tools: Vec<ToolManifest>,
     pub agents: Vec<AgentManifest>,
     pub teams: Vec<TeamManifest>,
     pub pipelines: Vec<PipelineManifest>,
+    #[serde(default)]
+    pub workflows: Vec<WorkflowManifest>,
+    /// Names referenced by this bundle but defined in a dependency bundle
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub externals: Vec<ExternalReference>,
+    /// Hash and size of each implementation's bundled source, filled in
+    /// when the bundle is saved
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub implementations: Vec<ImplementationRecord>,
+    /// How `implementations/_sources/` entries are encoded
+    #[serde(default, skip_serializing_if = "SourceEncoding::is_identity")]
+    pub source_encoding: SourceEncoding,
+}
+
+impl AriaManifest {
+    /// Ensure the manifest format version is one this compiler understands.
+    pub fn check_version(&self) -> Result<()> {
+        if !(MIN_SUPPORTED_MANIFEST_VERSION..=MANIFEST_VERSION).contains(&self.manifest_version) {
+            return Err(anyhow!(
+                "Unsupported manifest version {} (supported: {}-{}); upgrade arc to read this bundle",
+                self.manifest_version, MIN_SUPPORTED_MANIFEST_VERSION, MANIFEST_VERSION
+            ));
+        }
+        Ok(())
+    }
+    
+    /// JSON Schema for the manifest format written by this compiler
+    pub fn json_schema() -> serde_json::Value {
+        let mut schema = serde_json::to_value(schemars::schema_for!(AriaManifest))
+            .expect("generated schema is valid JSON");
+        schema["title"] = serde_json::Value::String(format!("AriaManifest v{}", MANIFEST_VERSION));
+        schema
+    }
+    
+    /// Each tool, agent, team, pipeline and workflow mapped to the names it
+    /// references: an agent's tools, a team's members and a workflow's steps
+    pub fn dependency_graph(&self) -> BTreeMap<String, Vec<String>> {
+        let mut graph = BTreeMap::new();
+        for tool in &self.tools {
+            graph.insert(tool.name.clone(), Vec::new());
+        }
+        for agent in &self.agents {
+            graph.insert(agent.name.clone(), agent.tools.clone());
+        }
+        for team in &self.teams {
+            graph.insert(team.name.clone(), team.members.iter().map(|m| m.agent.clone()).collect());
+        }
+        for pipeline in &self.pipelines {
+            graph.insert(pipeline.name.clone(), Vec::new());
+        }
+        for workflow in &self.workflows {
+            graph.insert(workflow.name.clone(), workflow.steps.clone());
+        }
+        graph
+    }
+    
+    /// Check the serialized manifest against `json_schema`, so a shape the
+    /// runtime would reject is caught before it is written into a bundle
+    pub fn validate_schema(&self) -> Result<()> {
+        Self::validate_json(&serde_json::to_value(self)?)
+    }
+    
+    /// Check a manifest read from outside the compiler against `json_schema`,
+    /// listing every violation rather than stopping at the first one
+    pub fn validate_json(instance: &serde_json::Value) -> Result<()> {
+        let schema = Self::json_schema();
+        let validator = jsonschema::JSONSchema::compile(&schema)
+            .map_err(|e| anyhow!("Invalid manifest schema: {}", e))?;
+        
+        if let Err(errors) = validator.validate(instance) {
+            let details: Vec<String> = errors
+                .map(|e| format!("  - {}: {}", display_pointer(&e.instance_path.to_string()), e))
+                .collect();
+            return Err(anyhow!(
+                "Manifest does not match schema v{}:\n{}",
+                MANIFEST_VERSION,
+                details.join("\n")
+            ));
+        }
+        
+        Ok(())
+    }
+}
+
+/// Whether `name` is safe to use as an implementation name. Names become
+/// bundle stub file names and appear in generated JavaScript, so they are
+/// limited to identifier characters plus `-` and `.`, and may not start with `.`.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '$' | '-' | '.');
+    match chars.next() {
+        Some(first) => first != '.' && valid_char(first) && chars.all(valid_char),
+        None => false,
+    }
+}
+
+/// Render an empty JSON pointer as the document root
+fn display_pointer(pointer: &str) -> &str {
+    if pointer.is_empty() { "/" } else { pointer }
+}
+
+/// A tool defined in another bundle that this bundle references by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExternalReference {
+    pub kind: String,
+    pub name: String,
+    /// Name of the dependency bundle that defines it
+    pub bundle: String,
+    pub external: bool,
+}
+
+/// Encoding of the transpiled sources stored in a bundle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum SourceEncoding {
+    /// Plain `.js` files
+    #[default]
+    #[serde(rename = "identity")]
+    Identity,
+    /// Each source gzipped and stored as `.js.gz`; the runtime gunzips it
+    /// back to the `.js` path the stubs import
+    #[serde(rename = "gzip")]
+    Gzip,
+}
+
+impl SourceEncoding {
+    pub fn is_identity(&self) -> bool {
+        *self == SourceEncoding::Identity
+    }
+}
+
+/// Integrity record for one implementation's compiled source, so the
+/// runtime can verify it without rehashing the whole bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImplementationRecord {
+    pub name: String,
+    pub kind: String,
+    /// Digest of the compiled source before any source encoding, using the
+    /// bundle's hash algorithm
+    pub source_hash: String,
+    /// Size of the compiled source in bytes, before any source encoding
+    pub source_size: u64,
+    /// Language the implementation was written in; absent in older bundles
+    #[serde(default)]
+    pub source_language: SourceLanguage,
+    /// Version of arc that compiled the implementation; empty in older bundles
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub compiler_version: String,
 }
 
 /// Metadata for a decorated `@tool` function.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ToolManifest {
     pub name: String,
     pub description: String,
-    pub inputs: HashMap<String, String>, // Placeholder
+    pub inputs: BTreeMap<String, InputSpec>, // Parameter name -> spec
+    /// Execution timeout hint for the runtime, in milliseconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Rate-limit hint for the runtime
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Labels used to group tools
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Cost estimate the runtime aggregates for budgeting: a flat number or
+    /// an object such as `{ "tokens": 100 }`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<serde_json::Value>,
+    /// TypeScript type the tool resolves to, with `Promise<...>` unwrapped;
+    /// absent when the return type is not annotated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// Build feature that must be enabled for the tool to be compiled in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    /// Agent whose `@tool` method implements the tool; absent for standalone tools
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+}
+
+/// A tool input parameter. Its schema is that of [`InputSpecRepr`], the
+/// encodings it is read from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "InputSpecRepr")]
+pub struct InputSpec {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub required: bool,
+    /// Value the runtime uses when the input is omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl InputSpec {
+    /// A required input known only by its type name
+    pub fn required(type_: impl Into<String>) -> Self {
+        InputSpec { type_: type_.into(), required: true, default: None, description: None }
+    }
+}
+
+impl JsonSchema for InputSpec {
+    fn schema_name() -> String {
+        "InputSpec".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        InputSpecRepr::json_schema(generator)
+    }
+}
+
+/// Accepted encodings of a tool input: a bare type name (manifest version 2
+/// and earlier) or an object. `required` defaults to whether no default is given.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum InputSpecRepr {
+    Type(String),
+    Spec {
+        #[serde(rename = "type")]
+        type_: String,
+        #[serde(default)]
+        required: Option<bool>,
+        /// Value the runtime uses when the input is omitted
+        #[serde(default)]
+        default: Option<serde_json::Value>,
+        #[serde(default)]
+        description: Option<String>,
+    },
+}
+
+impl From<InputSpecRepr> for InputSpec {
+    fn from(repr: InputSpecRepr) -> Self {
+        match repr {
+            InputSpecRepr::Type(type_) => InputSpec::required(type_),
+            InputSpecRepr::Spec { type_, required, default, description } => InputSpec {
+                type_,
+                required: required.unwrap_or(default.is_none()),
+                default,
+                description,
+            },
+        }
+    }
 }
 
 /// Metadata for a decorated `@agent` class.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AgentManifest {
     pub name: String,
     pub description: String,
     pub tools: Vec<String>, // Names of tools used by this agent
+    /// Model the agent should run on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Whether the agent needs persistent memory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<bool>,
+    /// Whether the agent streams its responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub streaming: Option<bool>,
+    /// Labels used to group agents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Build feature that must be enabled for the agent to be compiled in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
 }
 
 /// Metadata for a decorated `@team` class.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TeamManifest {
     pub name: String,
     pub description: String,
-    pub members: Vec<String>, // Names of agents in this team
+    pub members: Vec<TeamMember>,
+}
+
+/// An agent in a team, optionally with the role it plays.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "TeamMemberRepr")]
+pub struct TeamMember {
+    pub agent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+}
+
+impl JsonSchema for TeamMember {
+    fn schema_name() -> String {
+        "TeamMember".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        TeamMemberRepr::json_schema(generator)
+    }
+}
+
+/// Accepted encodings of a team member: a bare agent name (manifest version 1)
+/// or an object with a role.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum TeamMemberRepr {
+    Name(String),
+    Member {
+        agent: String,
+        #[serde(default)]
+        role: Option<String>,
+    },
+}
+
+impl From<TeamMemberRepr> for TeamMember {
+    fn from(repr: TeamMemberRepr) -> Self {
+        match repr {
+            TeamMemberRepr::Name(agent) => TeamMember { agent, role: None },
+            TeamMemberRepr::Member { agent, role } => TeamMember { agent, role },
+        }
+    }
 }
 
 /// Metadata for a decorated `@pipeline` class.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PipelineManifest {
     pub name: String,
     pub description: String,
     // Add other pipeline-specific fields here later
-} 
\ No newline at end of file
+}
+
+/// Metadata for a decorated `@workflow` class.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WorkflowManifest {
+    pub name: String,
+    pub description: String,
+    /// Names of the tools, agents, teams or pipelines run in order
+    #[serde(default)]
+    pub steps: Vec<String>,
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn inputs_read_from_type_names_and_objects() {
+        let inputs: BTreeMap<String, InputSpec> = serde_json::from_value(serde_json::json!({
+            "query": "string",
+            "limit": { "type": "number", "default": 10 },
+            "strict": { "type": "boolean", "required": false, "description": "Exact matches only" },
+        })).unwrap();
+        
+        assert_eq!(inputs["query"], InputSpec::required("string"));
+        assert_eq!(inputs["limit"], InputSpec {
+            type_: "number".to_string(),
+            required: false,
+            default: Some(serde_json::json!(10)),
+            description: None,
+        });
+        assert_eq!(inputs["strict"], InputSpec {
+            type_: "boolean".to_string(),
+            required: false,
+            default: None,
+            description: Some("Exact matches only".to_string()),
+        });
+    }
+    
+    #[test]
+    fn input_schema_accepts_both_encodings() {
+        let schema = serde_json::to_value(schemars::schema_for!(InputSpec)).unwrap();
+        let validator = jsonschema::JSONSchema::compile(&schema).unwrap();
+        
+        assert!(validator.is_valid(&serde_json::json!("string")));
+        assert!(validator.is_valid(&serde_json::json!({ "type": "number", "default": 10 })));
+        assert!(validator.is_valid(&serde_json::to_value(InputSpec::required("string")).unwrap()));
+        assert!(!validator.is_valid(&serde_json::json!({ "required": true })));
+        assert!(!validator.is_valid(&serde_json::json!(42)));
+    }
+    
+    #[test]
+    fn legacy_manifests_match_the_schema() {
+        // Every encoding the serde reprs accept must pass the build-time schema check
+        let legacy = serde_json::json!({
+            "name": "app",
+            "version": "1.0.0",
+            "tools": [{ "name": "search", "description": "finds", "inputs": { "query": "string" } }],
+            "agents": [{ "name": "Finder", "description": "finds", "tools": ["search"] }],
+            "teams": [{ "name": "Crew", "description": "works", "members": ["Finder", { "agent": "Finder", "role": "lead" }] }],
+            "pipelines": [],
+        });
+        
+        let manifest: AriaManifest = serde_json::from_value(legacy.clone()).unwrap();
+        AriaManifest::validate_json(&legacy).unwrap();
+        manifest.validate_schema().unwrap();
+    }
+    
+    #[test]
+    fn team_member_schema_accepts_both_encodings() {
+        let schema = serde_json::to_value(schemars::schema_for!(TeamMember)).unwrap();
+        let validator = jsonschema::JSONSchema::compile(&schema).unwrap();
+        
+        assert!(validator.is_valid(&serde_json::json!("Researcher")));
+        assert!(validator.is_valid(&serde_json::json!({ "agent": "Researcher", "role": "lead" })));
+        assert!(validator.is_valid(&serde_json::json!({ "agent": "Researcher" })));
+        assert!(!validator.is_valid(&serde_json::json!({ "role": "lead" })));
+        assert!(!validator.is_valid(&serde_json::json!(42)));
+    }
+}
@@ -0,0 +1,179 @@
+//! Imports in project source files.
+//!
+//! Sources are bundled as `.js` files laid out like the source tree, so a
+//! relative import keeps working once its specifier names the `.js` file
+//! the imported module is transpiled to. Any other specifier that is not a
+//! builtin module names an npm package, which must be installed to run.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use swc_core::ecma::ast::{Ident, ImportSpecifier, MemberProp, Module, ModuleDecl, ModuleItem, NamedExport, PropName};
+use swc_core::ecma::visit::{Visit, VisitWith};
+
+/// Extensions tried, in order, for an import without one
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+/// Node and Bun modules that resolve without an installed package
+const BUILTIN_MODULES: &[&str] = &[
+    "assert", "async_hooks", "buffer", "bun", "child_process", "cluster", "console",
+    "constants", "crypto", "dgram", "diagnostics_channel", "dns", "domain", "events",
+    "fs", "http", "http2", "https", "inspector", "module", "net", "os", "path",
+    "perf_hooks", "process", "punycode", "querystring", "readline", "repl", "stream",
+    "string_decoder", "sys", "timers", "tls", "trace_events", "tty", "url", "util",
+    "v8", "vm", "wasi", "worker_threads", "zlib",
+];
+
+/// Point relative imports and re-exports in `module` at the bundled `.js`
+/// file of the local source they resolve to. Returns the resolved sources.
+/// Specifiers that do not resolve to a local source are left unchanged.
+pub fn rewrite_local_imports(module: &mut Module, from: &Path) -> Vec<PathBuf> {
+    let mut resolved = Vec::new();
+
+    for item in &mut module.body {
+        let src = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => &mut import.src,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => &mut export.src,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { src: Some(src), .. })) => src,
+            _ => continue,
+        };
+
+        if let Some((path, specifier)) = resolve_local_import(from, &src.value) {
+            src.value = specifier.into();
+            src.raw = None;
+            resolved.push(path);
+        }
+    }
+
+    resolved
+}
+
+/// Names of the npm packages `module` imports or re-exports from. Type-only
+/// imports are erased by transpilation, so they are not included.
+pub fn package_imports(module: &Module) -> Vec<String> {
+    let mut packages: Vec<String> = Vec::new();
+
+    for item in &module.body {
+        let src = match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) if !import.type_only => &import.src,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) if !export.type_only => &export.src,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { src: Some(src), type_only: false, .. })) => src,
+            _ => continue,
+        };
+
+        if let Some(package) = package_name(&src.value) {
+            if !packages.iter().any(|p| p == package) {
+                packages.push(package.to_string());
+            }
+        }
+    }
+
+    packages
+}
+
+/// Bindings introduced by `module`'s imports that the rest of the module
+/// never references, as `(local name, specifier)` in import order.
+/// References are matched by name, so a local variable that shadows an
+/// import counts as a use of it.
+pub fn unused_imports(module: &Module) -> Vec<(String, String)> {
+    let mut references = ReferencedNames::default();
+    for item in &module.body {
+        if !matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))) {
+            item.visit_with(&mut references);
+        }
+    }
+
+    let mut unused = Vec::new();
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else { continue };
+        for specifier in &import.specifiers {
+            let local = match specifier {
+                ImportSpecifier::Named(named) => &named.local,
+                ImportSpecifier::Default(default) => &default.local,
+                ImportSpecifier::Namespace(namespace) => &namespace.local,
+            };
+            if !references.names.contains(local.sym.as_ref()) {
+                unused.push((local.sym.to_string(), import.src.value.to_string()));
+            }
+        }
+    }
+
+    unused
+}
+
+/// Every identifier in the visited nodes, in values and types alike
+#[derive(Default)]
+struct ReferencedNames {
+    names: HashSet<String>,
+}
+
+impl Visit for ReferencedNames {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.to_string());
+    }
+
+    // `a.name` and `{ name: value }` name properties, not bindings
+    fn visit_member_prop(&mut self, prop: &MemberProp) {
+        if let MemberProp::Computed(computed) = prop {
+            computed.visit_with(self);
+        }
+    }
+
+    fn visit_prop_name(&mut self, name: &PropName) {
+        if let PropName::Computed(computed) = name {
+            computed.visit_with(self);
+        }
+    }
+}
+
+/// The package a bare specifier imports from: `lodash` for `lodash/fp`,
+/// `@scope/pkg` for `@scope/pkg/sub`. None for relative or absolute paths,
+/// URLs and `node:`-style specifiers, and builtin modules.
+fn package_name(specifier: &str) -> Option<&str> {
+    if specifier.starts_with('.') || specifier.starts_with('/') || specifier.contains(':') {
+        return None;
+    }
+
+    let mut segments = specifier.splitn(3, '/');
+    let first = segments.next()?;
+    let package = if first.starts_with('@') {
+        let name = segments.next()?;
+        &specifier[..first.len() + 1 + name.len()]
+    } else {
+        first
+    };
+
+    if BUILTIN_MODULES.contains(&package) {
+        None
+    } else {
+        Some(package)
+    }
+}
+
+/// Resolve a `./` or `../` specifier against the file `from`, returning the
+/// source it names and the specifier of that source's `.js` output
+fn resolve_local_import(from: &Path, specifier: &str) -> Option<(PathBuf, String)> {
+    if !specifier.starts_with("./") && !specifier.starts_with("../") {
+        return None;
+    }
+    let dir = from.parent()?;
+
+    // TypeScript ESM conventionally imports `utils.ts` as `./utils.js`
+    let stem = [".ts", ".tsx", ".js", ".jsx", ".mjs", ".cjs"].iter()
+        .find_map(|ext| specifier.strip_suffix(ext))
+        .unwrap_or(specifier);
+
+    let file_candidates = SOURCE_EXTENSIONS.iter()
+        .map(|ext| (format!("{}.{}", stem, ext), format!("{}.js", stem)));
+    let index_candidates = SOURCE_EXTENSIONS.iter()
+        .map(|ext| (format!("{}/index.{}", stem, ext), format!("{}/index.js", stem)));
+
+    file_candidates.chain(index_candidates)
+        .find_map(|(candidate, rewritten)| {
+            let path = dir.join(&candidate);
+            if path.is_file() {
+                std::fs::canonicalize(path).ok().map(|path| (path, rewritten))
+            } else {
+                None
+            }
+        })
+}
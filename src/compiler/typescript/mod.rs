@@ -1,61 +1,200 @@
+pub mod imports;
 pub mod visitor;
 
-use anyhow::{anyhow, Result};
-use swc_core::common::{sync::Lrc, Mark, SourceMap, GLOBALS, Globals};
+use anyhow::Result;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use swc_core::common::{sync::Lrc, Mark, SourceMap, Spanned, GLOBALS, Globals};
 use swc_core::ecma::ast::{Module, EsVersion, Program};
 use swc_core::ecma::codegen::{Emitter, Config, text_writer::JsWriter};
-use swc_core::ecma::parser::{lexer::Lexer, Parser, StringInput, Syntax, TsConfig};
+use swc_core::ecma::parser::{lexer::Lexer, EsConfig, Parser, StringInput, Syntax, TsConfig};
 use swc_core::ecma::transforms::{base::resolver, base::helpers, typescript};
 use swc_core::ecma::transforms::proposal::decorators;
-use swc_core::ecma::visit::FoldWith;
+use swc_core::ecma::visit::{Fold, FoldWith};
 
 use crate::compiler::SourceFile;
 use crate::compiler::CompiledFile;
+use crate::compiler::diagnostics::{CompileError, Diagnostic, DiagnosticCode, Warning, WarningKind};
 use self::visitor::AstVisitor;
 
+/// Creates a fresh instance of a custom SWC pass for each transpiled file
+pub type TransformFactory = Arc<dyn Fn() -> Box<dyn Fold> + Send + Sync>;
+
 /// TypeScript compiler using SWC for AST parsing
 pub struct TypeScriptCompiler {
     source_map: Lrc<SourceMap>,
+    transforms: Vec<TransformFactory>,
+    dump_ast: bool,
+    lint_unused_imports: bool,
 }
 
 impl TypeScriptCompiler {
     /// Create a new TypeScript compiler
     pub fn new(source_map: Lrc<SourceMap>) -> Self {
-        Self { source_map }
+        Self { source_map, transforms: Vec::new(), dump_ast: false, lint_unused_imports: false }
+    }
+    
+    /// Print each parsed module's AST to stderr before extracting from it
+    pub fn dump_ast(mut self, dump_ast: bool) -> Self {
+        self.dump_ast = dump_ast;
+        self
+    }
+    
+    /// Warn about imported bindings the module never references
+    pub fn lint_unused_imports(mut self, lint: bool) -> Self {
+        self.lint_unused_imports = lint;
+        self
+    }
+    
+    /// Add a custom pass to `transpile`. `make_pass` is called once per file.
+    ///
+    /// Custom passes run after the built-in resolver, decorators and
+    /// typescript passes, in the order they were added, so they see plain
+    /// JavaScript with resolved identifiers. Decorator metadata has already
+    /// been extracted from the original AST and is not affected by them.
+    pub fn with_transform<F, P>(self, make_pass: F) -> Self
+    where
+        F: Fn() -> P + Send + Sync + 'static,
+        P: Fold + 'static,
+    {
+        self.with_transform_factory(Arc::new(move || Box::new(make_pass()) as Box<dyn Fold>))
+    }
+    
+    /// Add a custom pass from an already boxed factory; see `with_transform`
+    pub fn with_transform_factory(mut self, factory: TransformFactory) -> Self {
+        self.transforms.push(factory);
+        self
     }
     
     /// Compile a single TypeScript file, returning all discovered implementations.
-    pub async fn compile_file(&self, source: &SourceFile) -> Result<CompiledFile> {
+    #[tracing::instrument(name = "compile_file", skip_all, fields(path = %source.path.display()))]
+    pub fn compile_file(&self, source: &SourceFile) -> Result<CompiledFile> {
         let globals = Globals::new();
         GLOBALS.set(&globals, || {
-            let module = self.parse(&source.content)?;
+            let parse_start = Instant::now();
+            let (mut module, recovered) = self.parse(&source.content, syntax_for(&source.path))?;
+            if self.dump_ast {
+                eprintln!("// AST of {}\n{:#?}", source.path.display(), module);
+            }
+            // Before rewriting, so warnings show the specifiers as written
+            let unused_imports = if self.lint_unused_imports {
+                imports::unused_imports(&module)
+            } else {
+                Vec::new()
+            };
+            let imports = imports::rewrite_local_imports(&mut module, &source.path);
+            let packages = imports::package_imports(&module);
             
             let mut visitor = AstVisitor::new();
             visitor.visit_module(&module);
+            
+            if !visitor.errors.is_empty() {
+                let diagnostics: Vec<Diagnostic> = visitor.errors.into_iter()
+                    .map(|e| Diagnostic::message(e).with_code(DiagnosticCode::InvalidDecorator))
+                    .collect();
+                let message = format!(
+                    "Invalid decorator metadata in {}:\n - {}",
+                    source.path.display(),
+                    diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n - ")
+                );
+                return Err(CompileError::new(message, diagnostics).into());
+            }
+            let parse_time_secs = parse_start.elapsed().as_secs_f64();
 
+            let transpile_start = Instant::now();
             let executable_code = self.transpile(&module)?;
+            let transpile_time_secs = transpile_start.elapsed().as_secs_f64();
+            
+            tracing::debug!("Compiled {}: {} items", source.path.display(), visitor.items.len());
+            
+            let mut warnings = visitor.warnings;
+            warnings.extend(recovered.into_iter().map(|d| Warning::new(
+                WarningKind::RecoveredSyntaxError,
+                format!("Syntax error in {} at line {}: {}", source.path.display(), d.line.unwrap_or_default(), d.message),
+            )));
+            warnings.extend(unused_imports.into_iter().map(|(name, specifier)| Warning::new(
+                WarningKind::UnusedImport,
+                format!("Unused import '{}' from '{}' in {}", name, specifier, source.path.display()),
+            )));
             
             Ok(CompiledFile {
                 source: source.clone(),
                 javascript_code: executable_code,
                 items: visitor.items,
+                description_files: visitor.description_files,
+                imports,
+                packages,
+                warnings: warnings.into_iter().map(|w| w.in_file(&source.path)).collect(),
+                parse_time_secs,
+                transpile_time_secs,
+                cached: false,
             })
         })
     }
 
-    fn parse(&self, source: &str) -> Result<Module> {
+    /// Parse `source`, returning the module along with the errors the parser
+    /// recovered from. If it could not recover, every error is reported.
+    #[tracing::instrument(name = "parse", skip_all)]
+    fn parse(&self, source: &str, syntax: Syntax) -> Result<(Module, Vec<Diagnostic>)> {
         let source_file = self.source_map.new_source_file(swc_core::common::FileName::Anon, source.into());
         let lexer = Lexer::new(
-            Syntax::Typescript(TsConfig { decorators: true, ..Default::default() }),
+            syntax,
             EsVersion::latest(),
             StringInput::from(&*source_file),
             None,
         );
         let mut parser = Parser::new_from(lexer);
-        parser.parse_module().map_err(|e| anyhow!("Failed to parse module: {:?}", e))
+        let result = parser.parse_module();
+        
+        // The parser recovers from some errors and keeps going. Those are
+        // returned with the module; if it then stopped at another, all of
+        // them are reported in source order.
+        let mut errors = parser.take_errors();
+        let fatal = match result {
+            Ok(module) => {
+                let recovered = errors.iter().map(|e| self.parse_diagnostic(e)).collect();
+                return Ok((module, recovered));
+            }
+            Err(e) => e,
+        };
+        errors.push(fatal);
+        errors.sort_by_key(|e| e.span().lo);
+        errors.dedup_by_key(|e| e.span().lo);
+        
+        let diagnostics: Vec<Diagnostic> = errors.iter().map(|e| self.parse_diagnostic(e)).collect();
+        let message = match errors.as_slice() {
+            [_] => format!(
+                "[{}] Failed to parse module: line {}: {}",
+                DiagnosticCode::ParseError,
+                diagnostics[0].line.unwrap_or_default(),
+                diagnostics[0].message
+            ),
+            _ => format!(
+                "[{}] Failed to parse module: {} syntax errors:\n - {}",
+                DiagnosticCode::ParseError,
+                diagnostics.len(),
+                diagnostics.iter()
+                    .map(|d| format!("line {}: {}", d.line.unwrap_or_default(), d.message))
+                    .collect::<Vec<_>>()
+                    .join("\n - ")
+            ),
+        };
+        Err(CompileError::new(message, diagnostics).into())
+    }
+
+    /// A diagnostic locating `error` in the parsed source
+    fn parse_diagnostic(&self, error: &swc_core::ecma::parser::error::Error) -> Diagnostic {
+        Diagnostic {
+            file: None,
+            line: Some(self.source_map.lookup_char_pos(error.span().lo).line),
+            code: Some(DiagnosticCode::ParseError),
+            message: error.kind().msg().to_string(),
+        }
     }
 
     /// Transpiles an entire module into a JavaScript code string.
+    #[tracing::instrument(name = "transpile", skip_all)]
     fn transpile(&self, module: &Module) -> Result<String> {
         let cm = self.source_map.clone();
         
@@ -65,18 +204,21 @@ impl TypeScriptCompiler {
         helpers::HELPERS.set(&helpers::Helpers::new(false), || {
             let mut program = Program::Module(module.clone());
             
-            let mut resolver_pass = resolver(unresolved_mark, top_level_mark, true);
-            program = program.fold_with(&mut resolver_pass);
-
-            let mut decorators_pass = decorators::decorators(decorators::Config{
-                legacy: true,
-                emit_metadata: false,
-                use_define_for_class_fields: false,
-            });
-            program = program.fold_with(&mut decorators_pass);
+            // Built-in passes first, then custom passes in the order added
+            let mut passes: Vec<Box<dyn Fold>> = vec![
+                Box::new(resolver(unresolved_mark, top_level_mark, true)),
+                Box::new(decorators::decorators(decorators::Config{
+                    legacy: true,
+                    emit_metadata: false,
+                    use_define_for_class_fields: false,
+                })),
+                Box::new(typescript::typescript(typescript::Config::default(), top_level_mark)),
+            ];
+            passes.extend(self.transforms.iter().map(|make_pass| make_pass()));
             
-            let mut ts_transform = typescript::typescript(typescript::Config::default(), top_level_mark);
-            program = program.fold_with(&mut ts_transform);
+            for pass in &mut passes {
+                program = program.fold_with(pass.as_mut());
+            }
     
             let mut buf = Vec::new();
             {
@@ -94,6 +236,16 @@ impl TypeScriptCompiler {
     }
 }
 
+/// Parser syntax for a source file: ECMAScript with decorators for
+/// JavaScript files, TypeScript for everything else
+fn syntax_for(path: &Path) -> Syntax {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("js" | "mjs" | "cjs") => Syntax::Es(EsConfig { decorators: true, decorators_before_export: true, ..Default::default() }),
+        Some("jsx") => Syntax::Es(EsConfig { decorators: true, decorators_before_export: true, jsx: true, ..Default::default() }),
+        _ => Syntax::Typescript(TsConfig { decorators: true, ..Default::default() }),
+    }
+}
+
 impl Default for TypeScriptCompiler {
     fn default() -> Self {
         Self::new(Lrc::new(SourceMap::default()))
@@ -3,14 +3,20 @@
 //! This module is responsible for identifying decorated entities (`@tool`, `@agent`)
 //! and extracting their metadata and implementation source code.
 
-use swc_ecma_ast::{Module, Expr, Lit, KeyValueProp, ClassDecl, FnDecl, ClassMethod};
+use swc_ecma_ast::{
+    Module, Expr, Lit, KeyValueProp, ObjectLit, Class, ClassDecl, ClassExpr, DefaultDecl, ExportDefaultDecl,
+    FnDecl, Function, ClassMethod, MethodKind, UnaryOp, TsEntityName, TsKeywordTypeKind, TsType, TsUnionOrIntersectionType,
+};
 use swc_ecma_visit::{Visit, VisitWith};
 
-use crate::compiler::schema::{ToolManifest, AgentManifest, TeamManifest, PipelineManifest};
-use std::collections::HashMap;
+use crate::compiler::diagnostics::{Warning, WarningKind};
+use crate::compiler::schema::{ToolManifest, InputSpec, AgentManifest, TeamManifest, TeamMember, PipelineManifest, WorkflowManifest};
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
 
 /// A temporary struct to hold data extracted by the visitor.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
 pub enum ExtractedItem {
     Tool {
         manifest: ToolManifest,
@@ -24,17 +30,88 @@ pub enum ExtractedItem {
     Pipeline {
         manifest: PipelineManifest,
     },
+    Workflow {
+        manifest: WorkflowManifest,
+    },
 }
 
+impl ExtractedItem {
+    /// Name the item will have in the manifest
+    pub fn name(&self) -> &str {
+        match self {
+            ExtractedItem::Tool { manifest } => &manifest.name,
+            ExtractedItem::Agent { manifest } => &manifest.name,
+            ExtractedItem::Team { manifest } => &manifest.name,
+            ExtractedItem::Pipeline { manifest } => &manifest.name,
+            ExtractedItem::Workflow { manifest } => &manifest.name,
+        }
+    }
+
+    /// Replace the item's description
+    pub fn set_description(&mut self, description: String) {
+        match self {
+            ExtractedItem::Tool { manifest } => manifest.description = description,
+            ExtractedItem::Agent { manifest } => manifest.description = description,
+            ExtractedItem::Team { manifest } => manifest.description = description,
+            ExtractedItem::Pipeline { manifest } => manifest.description = description,
+            ExtractedItem::Workflow { manifest } => manifest.description = description,
+        }
+    }
+}
+
+/// A `descriptionFile` named by an item's decorator. The visitor only
+/// records it; the compiler reads it and fills in the item's description.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescriptionFile {
+    /// Index of the item in the file's extracted items
+    pub item: usize,
+    /// Path as written, relative to the source file's directory
+    pub path: String,
+}
+
+/// The class currently being visited, used to validate decorated members.
+struct ClassContext {
+    name: String,
+    decorator: Option<String>,
+    /// Name of the agent an `@agent` class defines
+    agent: Option<String>,
+    /// Names of the `@tool` methods found in the class so far
+    method_tools: Vec<String>,
+}
+
+/// Class decorators that may host `@tool` methods. `@aria` is the application
+/// class generated by `arc new`.
+const TOOL_HOST_DECORATORS: &[&str] = &["agent", "aria"];
+
+/// Class decorators that each make a class a different kind of implementation
+const KIND_DECORATORS: &[&str] = &["agent", "team", "pipeline", "workflow"];
+
 /// An AST visitor that extracts Aria-specific implementations and their spans.
 pub struct AstVisitor {
     pub items: Vec<ExtractedItem>,
+    pub warnings: Vec<Warning>,
+    pub errors: Vec<String>,
+    /// Description files named by the extracted items
+    pub description_files: Vec<DescriptionFile>,
+    current_class: Option<ClassContext>,
+}
+
+impl Default for AstVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AstVisitor {
     /// Create a new visitor with the source code.
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self {
+            items: Vec::new(),
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            description_files: Vec::new(),
+            current_class: None,
+        }
     }
 
     /// Entrypoint to start visiting a module.
@@ -42,117 +119,219 @@ impl AstVisitor {
         module.visit_with(self);
     }
 
-    fn parse_tool_decorator(&mut self, name: String, decorator: &swc_ecma_ast::Decorator) {
+    fn parse_tool_decorator(&mut self, name: String, function: &Function, decorator: &swc_ecma_ast::Decorator) {
         let mut manifest = ToolManifest {
-            name: name,
+            name,
             description: String::new(),
-            inputs: HashMap::new(),
+            inputs: BTreeMap::new(),
+            timeout_ms: None,
+            rate_limit: None,
+            tags: Vec::new(),
+            cost: None,
+            output: function.return_type.as_ref().and_then(|ann| return_type_name(&ann.type_ann)),
+            feature: None,
+            agent: None,
         };
+        let mut timeout = None;
+        let mut rate_limit = None;
+        let mut inputs = None;
+        let mut tags = None;
+        let mut feature = None;
 
-        if let Some(call) = decorator.expr.as_call() {
-            if let Some(expr) = call.args.get(0) {
-                if let Expr::Object(obj) = &*expr.expr {
-                    for prop in &obj.props {
-                        if let Some(kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
-                            let key = self.get_prop_key(kv);
-                            match key.as_str() {
-                                "name" => manifest.name = self.get_prop_value(kv),
-                                "description" => manifest.description = self.get_prop_value(kv),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
+        let mut description_file = None;
+        for kv in decorator_props(decorator) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "name" => self.read_name(&mut manifest.name, kv),
+                "description" => manifest.description = self.get_prop_value(kv),
+                "descriptionFile" => description_file = Some(self.get_prop_value(kv)),
+                "timeout" => timeout = self.get_prop_json(kv),
+                "rateLimit" => rate_limit = self.get_prop_json(kv),
+                "inputs" => inputs = Some(&*kv.value),
+                "tags" => tags = Some(kv),
+                "cost" => manifest.cost = self.get_prop_json(kv),
+                "feature" => feature = Some(kv),
+                _ => {}
             }
         }
 
-        self.items.push(ExtractedItem::Tool {
-            manifest,
-        });
+        // Validate once the final tool name is known
+        manifest.timeout_ms = self.get_positive_int(&manifest.name, "timeout", timeout, u64::MAX);
+        manifest.rate_limit = self.get_positive_int(&manifest.name, "rateLimit", rate_limit, u32::MAX as u64)
+            .map(|v| v as u32);
+        if let Some(inputs) = inputs {
+            manifest.inputs = self.get_tool_inputs(&manifest.name, inputs);
+        }
+        if let Some(kv) = tags {
+            manifest.tags = self.get_tags(&manifest.name, kv);
+        }
+        if let Some(cost) = &manifest.cost {
+            self.check_cost(&manifest.name, cost);
+        }
+        if let Some(kv) = feature {
+            manifest.feature = self.get_feature("Tool", &manifest.name, kv);
+        }
+
+        self.push_item(ExtractedItem::Tool { manifest }, description_file);
     }
 
-    fn parse_agent_decorator(&mut self, class: &ClassDecl, decorator: &swc_ecma_ast::Decorator) {
+    fn parse_agent_decorator(&mut self, name: String, decorator: &swc_ecma_ast::Decorator) {
         let mut manifest = AgentManifest {
-            name: class.ident.sym.to_string(),
+            name,
             description: String::new(),
             tools: Vec::new(),
+            model: None,
+            memory: None,
+            streaming: None,
+            tags: Vec::new(),
+            feature: None,
         };
+        let mut model = None;
+        let mut memory = None;
+        let mut streaming = None;
+        let mut tags = None;
+        let mut feature = None;
 
-        if let Some(call) = decorator.expr.as_call() {
-            if let Some(expr) = call.args.get(0) {
-                if let Expr::Object(obj) = &*expr.expr {
-                    for prop in &obj.props {
-                        if let Some(kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
-                            let key = self.get_prop_key(kv);
-                            match key.as_str() {
-                                "name" => manifest.name = self.get_prop_value(kv),
-                                "description" => manifest.description = self.get_prop_value(kv),
-                                "tools" => manifest.tools = self.get_tools_list(kv),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
+        let mut description_file = None;
+        for kv in decorator_props(decorator) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "name" => self.read_name(&mut manifest.name, kv),
+                "description" => manifest.description = self.get_prop_value(kv),
+                "descriptionFile" => description_file = Some(self.get_prop_value(kv)),
+                "tools" => manifest.tools = self.get_string_list(kv),
+                "model" => model = self.get_prop_json(kv),
+                "memory" => memory = self.get_prop_json(kv),
+                "streaming" => streaming = self.get_prop_json(kv),
+                "feature" => feature = Some(kv),
+                "tags" => tags = Some(kv),
+                _ => {}
             }
         }
         
-        self.items.push(ExtractedItem::Agent {
-            manifest,
-        });
+        // Validate once the final agent name is known
+        manifest.model = self.get_agent_option(&manifest.name, "model", model, "a string", |v| v.as_str().map(String::from));
+        manifest.memory = self.get_agent_option(&manifest.name, "memory", memory, "a boolean", serde_json::Value::as_bool);
+        manifest.streaming = self.get_agent_option(&manifest.name, "streaming", streaming, "a boolean", serde_json::Value::as_bool);
+        if let Some(kv) = tags {
+            manifest.tags = self.get_tags(&manifest.name, kv);
+        }
+        if let Some(kv) = feature {
+            manifest.feature = self.get_feature("Agent", &manifest.name, kv);
+        }
+        
+        self.push_item(ExtractedItem::Agent { manifest }, description_file);
     }
 
-    fn parse_team_decorator(&mut self, class: &ClassDecl, decorator: &swc_ecma_ast::Decorator) {
+    fn parse_team_decorator(&mut self, name: String, decorator: &swc_ecma_ast::Decorator) {
         let mut manifest = TeamManifest {
-            name: class.ident.sym.to_string(),
+            name,
             description: String::new(),
             members: Vec::new(),
         };
 
-        if let Some(call) = decorator.expr.as_call() {
-            if let Some(expr) = call.args.get(0) {
-                if let Expr::Object(obj) = &*expr.expr {
-                    for prop in &obj.props {
-                        if let Some(kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
-                            let key = self.get_prop_key(kv);
-                            match key.as_str() {
-                                "name" => manifest.name = self.get_prop_value(kv),
-                                "description" => manifest.description = self.get_prop_value(kv),
-                                "members" => manifest.members = self.get_string_array(kv),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
+        let mut members = None;
+
+        let mut description_file = None;
+        for kv in decorator_props(decorator) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "name" => self.read_name(&mut manifest.name, kv),
+                "description" => manifest.description = self.get_prop_value(kv),
+                "descriptionFile" => description_file = Some(self.get_prop_value(kv)),
+                "members" => members = Some(kv),
+                _ => {}
             }
         }
         
-        self.items.push(ExtractedItem::Team { manifest });
+        // Parsed once the team name is known, so errors name the right team
+        if let Some(kv) = members {
+            manifest.members = self.get_team_members(&manifest.name, kv);
+        }
+        
+        self.push_item(ExtractedItem::Team { manifest }, description_file);
     }
 
-    fn parse_pipeline_decorator(&mut self, class: &ClassDecl, decorator: &swc_ecma_ast::Decorator) {
+    fn parse_pipeline_decorator(&mut self, name: String, decorator: &swc_ecma_ast::Decorator) {
         let mut manifest = PipelineManifest {
-            name: class.ident.sym.to_string(),
+            name,
             description: String::new(),
         };
 
-        if let Some(call) = decorator.expr.as_call() {
-            if let Some(expr) = call.args.get(0) {
-                if let Expr::Object(obj) = &*expr.expr {
-                    for prop in &obj.props {
-                        if let Some(kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
-                            let key = self.get_prop_key(kv);
-                            match key.as_str() {
-                                "name" => manifest.name = self.get_prop_value(kv),
-                                "description" => manifest.description = self.get_prop_value(kv),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
+        let mut description_file = None;
+        for kv in decorator_props(decorator) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "name" => self.read_name(&mut manifest.name, kv),
+                "description" => manifest.description = self.get_prop_value(kv),
+                "descriptionFile" => description_file = Some(self.get_prop_value(kv)),
+                _ => {}
+            }
+        }
+        
+        self.push_item(ExtractedItem::Pipeline { manifest }, description_file);
+    }
+
+    fn parse_workflow_decorator(&mut self, name: String, decorator: &swc_ecma_ast::Decorator) {
+        let mut manifest = WorkflowManifest {
+            name,
+            description: String::new(),
+            steps: Vec::new(),
+        };
+
+        let mut description_file = None;
+        for kv in decorator_props(decorator) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "name" => self.read_name(&mut manifest.name, kv),
+                "description" => manifest.description = self.get_prop_value(kv),
+                "descriptionFile" => description_file = Some(self.get_prop_value(kv)),
+                "steps" => manifest.steps = self.get_string_list(kv),
+                _ => {}
             }
         }
         
-        self.items.push(ExtractedItem::Pipeline { manifest });
+        self.push_item(ExtractedItem::Workflow { manifest }, description_file);
+    }
+
+    /// Add an extracted item, recording the `descriptionFile` it names
+    fn push_item(&mut self, item: ExtractedItem, description_file: Option<String>) {
+        if let Some(path) = description_file {
+            self.description_files.push(DescriptionFile { item: self.items.len(), path });
+        }
+        self.items.push(item);
+    }
+
+    /// Make an agent's own `@tool` methods part of its tools. Listing one in
+    /// the decorator as well is redundant, so it is kept once and warned about.
+    fn add_method_tools(&mut self, agent_index: usize, method_tools: Vec<String>) {
+        if let Some(ExtractedItem::Agent { manifest }) = self.items.get_mut(agent_index) {
+            for tool in method_tools {
+                if manifest.tools.contains(&tool) {
+                    self.warnings.push(Warning::new(WarningKind::RedundantTool, format!(
+                        "Agent '{}' lists its own tool method '{}' in `tools`; method tools are included automatically",
+                        manifest.name, tool
+                    )));
+                }
+                // Qualified so it cannot resolve to a standalone tool of the same name
+                manifest.tools.push(format!("{}.{}", manifest.name, tool));
+            }
+        }
+    }
+
+    /// Warn when a `@tool` method lives in a class that cannot host tools,
+    /// which usually means the `@agent` decorator was forgotten.
+    fn check_tool_host(&mut self, method_name: &str) {
+        if let Some(class) = &self.current_class {
+            let is_host = class.decorator.as_deref()
+                .is_some_and(|d| TOOL_HOST_DECORATORS.contains(&d));
+            if !is_host {
+                self.warnings.push(Warning::new(WarningKind::MisplacedTool, format!(
+                    "Tool method '{}' is declared in class '{}' which is not decorated with @agent",
+                    method_name, class.name
+                )));
+            }
+        }
     }
 
     fn get_prop_key(&self, kv: &KeyValueProp) -> String {
@@ -163,57 +342,328 @@ impl AstVisitor {
         }
     }
 
-    fn get_prop_value(&self, kv: &KeyValueProp) -> String {
-        match &*kv.value {
-            Expr::Lit(Lit::Str(s)) => s.value.to_string(),
-            _ => "".to_string(),
+    fn get_prop_value(&mut self, kv: &KeyValueProp) -> String {
+        let key = self.get_prop_key(kv);
+        self.get_static_string(&kv.value, &key).unwrap_or_default()
+    }
+
+    /// Read a `name` into `name`. A name that cannot be read statically
+    /// leaves the declared function or class name in place.
+    fn read_name(&mut self, name: &mut String, kv: &KeyValueProp) {
+        if let Some(static_name) = self.get_static_string(&kv.value, "name") {
+            *name = static_name;
+        }
+    }
+
+    /// Read a `feature` gate, which must be a static string: any other value
+    /// is an error rather than a gate no `--features` list can enable
+    fn get_feature(&mut self, kind: &str, item_name: &str, kv: &KeyValueProp) -> Option<String> {
+        let feature = self.get_static_string(&kv.value, "feature");
+        if feature.is_none() {
+            self.errors.push(format!("{} '{}' has invalid feature: must be a string literal", kind, item_name));
+        }
+        feature
+    }
+
+    /// Read a string from a string literal or a template literal without
+    /// substitutions. Templates with substitutions produce a warning since
+    /// their value is only known at runtime.
+    fn get_static_string(&mut self, expr: &Expr, key: &str) -> Option<String> {
+        match expr {
+            Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+            Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+                tpl.quasis.first().and_then(|q| q.cooked.as_ref()).map(|c| c.to_string())
+            }
+            Expr::Tpl(_) => {
+                self.warnings.push(Warning::new(WarningKind::DynamicValue, format!(
+                    "Template literal with substitutions in '{}' cannot be statically extracted",
+                    key
+                )));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn get_prop_json(&self, kv: &KeyValueProp) -> Option<serde_json::Value> {
+        self.expr_to_json(&kv.value)
+    }
+
+    /// Convert a literal expression (including nested arrays and objects) to JSON.
+    /// Returns `None` for anything that cannot be evaluated statically.
+    fn expr_to_json(&self, expr: &Expr) -> Option<serde_json::Value> {
+        match expr {
+            Expr::Lit(Lit::Str(s)) => Some(serde_json::Value::String(s.value.to_string())),
+            Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl.quasis.first()
+                .and_then(|q| q.cooked.as_ref())
+                .map(|c| serde_json::Value::String(c.to_string())),
+            Expr::Lit(Lit::Bool(b)) => Some(serde_json::Value::Bool(b.value)),
+            Expr::Lit(Lit::Null(_)) => Some(serde_json::Value::Null),
+            Expr::Lit(Lit::Num(n)) => number_to_json(n.value),
+            Expr::Unary(unary) if unary.op == UnaryOp::Minus => {
+                self.expr_to_json(&unary.arg)?.as_f64().and_then(|n| number_to_json(-n))
+            }
+            Expr::Array(array_lit) => Some(serde_json::Value::Array(
+                array_lit.elems.iter()
+                    .flatten()
+                    .filter_map(|elem| self.expr_to_json(&elem.expr))
+                    .collect(),
+            )),
+            Expr::Object(obj) => {
+                let mut map = serde_json::Map::new();
+                for prop in &obj.props {
+                    if let Some(kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
+                        if let Some(value) = self.expr_to_json(&kv.value) {
+                            map.insert(self.get_prop_key(kv), value);
+                        }
+                    }
+                }
+                Some(serde_json::Value::Object(map))
+            }
+            Expr::Paren(paren) => self.expr_to_json(&paren.expr),
+            Expr::TsAs(ts_as) => self.expr_to_json(&ts_as.expr),
+            Expr::TsConstAssertion(assertion) => self.expr_to_json(&assertion.expr),
+            _ => None,
+        }
+    }
+
+    /// Read an optional positive integer setting, recording an error for
+    /// zero, negative, fractional, out-of-range or non-numeric values.
+    fn get_positive_int(&mut self, tool_name: &str, key: &str, value: Option<serde_json::Value>, max: u64) -> Option<u64> {
+        let value = value?;
+        match value.as_u64() {
+            Some(n) if n > 0 && n <= max => Some(n),
+            _ => {
+                self.errors.push(format!(
+                    "Tool '{}' has invalid {} {}: must be a positive integer",
+                    tool_name, key, value
+                ));
+                None
+            }
+        }
+    }
+
+    /// Read an agent option of one type with `convert`, recording an error
+    /// naming the `expected` type when the value is of another
+    fn get_agent_option<T>(
+        &mut self,
+        agent_name: &str,
+        key: &str,
+        value: Option<serde_json::Value>,
+        expected: &str,
+        convert: fn(&serde_json::Value) -> Option<T>,
+    ) -> Option<T> {
+        let value = value?;
+        let converted = convert(&value);
+        if converted.is_none() {
+            self.errors.push(format!(
+                "Agent '{}' has invalid {} {}: must be {}",
+                agent_name, key, value, expected
+            ));
+        }
+        converted
+    }
+
+    /// Warn about negative numbers in a tool's `cost`, whether it is a flat
+    /// number or an object of numbers. Any other shape is left to the runtime.
+    fn check_cost(&mut self, tool_name: &str, cost: &serde_json::Value) {
+        let negative: Vec<String> = match cost {
+            serde_json::Value::Number(n) if n.as_f64().is_some_and(|n| n < 0.0) => vec![n.to_string()],
+            serde_json::Value::Object(map) => map.iter()
+                .filter(|(_, v)| v.as_f64().is_some_and(|n| n < 0.0))
+                .map(|(key, v)| format!("{}: {}", key, v))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        for value in negative {
+            self.warnings.push(Warning::new(WarningKind::NegativeCost, format!(
+                "Tool '{}' has a negative cost ({}); costs should not be negative",
+                tool_name, value
+            )));
+        }
+    }
+
+    /// Read an explicit `inputs` object whose entries are type names
+    /// (`query: "string"`) or input specs, recording an error for anything
+    /// that cannot be evaluated statically.
+    fn get_tool_inputs(&mut self, tool_name: &str, expr: &Expr) -> BTreeMap<String, InputSpec> {
+        let mut inputs = BTreeMap::new();
+        let obj = match expr {
+            Expr::Object(obj) => obj,
+            _ => {
+                self.errors.push(format!("Tool '{}' has invalid inputs: must be an object literal", tool_name));
+                return inputs;
+            }
+        };
+
+        for prop in &obj.props {
+            let kv = match prop.as_prop().and_then(|p| p.as_key_value()) {
+                Some(kv) => kv,
+                None => {
+                    self.errors.push(format!("Tool '{}' has invalid inputs: entries must be `name: \"type\"` or `name: {{ type: ... }}` pairs", tool_name));
+                    continue;
+                }
+            };
+            let key = self.get_prop_key(kv);
+            let spec = match &*kv.value {
+                Expr::Object(spec) => self.get_input_spec(tool_name, &key, spec),
+                value => match self.get_static_string(value, &key) {
+                    Some(type_name) => Some(InputSpec::required(type_name)),
+                    None => {
+                        self.errors.push(format!(
+                            "Tool '{}' input '{}' must be a type name string or an object with a `type`",
+                            tool_name, key
+                        ));
+                        None
+                    }
+                },
+            };
+            if let Some(spec) = spec {
+                inputs.insert(key, spec);
+            }
+        }
+
+        inputs
+    }
+
+    /// Read an input spec such as
+    /// `{ type: "string", required: false, default: "", description: "..." }`.
+    /// `required` defaults to whether no default is given.
+    fn get_input_spec(&mut self, tool_name: &str, input: &str, obj: &ObjectLit) -> Option<InputSpec> {
+        let mut type_ = None;
+        let mut required = None;
+        let mut default = None;
+        let mut description = None;
+
+        for kv in obj.props.iter().filter_map(|p| p.as_prop().and_then(|p| p.as_key_value())) {
+            let key = self.get_prop_key(kv);
+            match key.as_str() {
+                "type" => type_ = self.get_static_string(&kv.value, &key),
+                "required" => match self.expr_to_json(&kv.value) {
+                    Some(serde_json::Value::Bool(value)) => required = Some(value),
+                    _ => self.errors.push(format!(
+                        "Tool '{}' input '{}' has invalid required: must be true or false",
+                        tool_name, input
+                    )),
+                },
+                "default" => match self.expr_to_json(&kv.value) {
+                    Some(value) => default = Some(value),
+                    None => self.errors.push(format!(
+                        "Tool '{}' input '{}' has invalid default: must be a literal value",
+                        tool_name, input
+                    )),
+                },
+                "description" => description = self.get_static_string(&kv.value, &key),
+                _ => {}
+            }
         }
+
+        let Some(type_) = type_ else {
+            self.errors.push(format!("Tool '{}' input '{}' must set `type` to a type name string", tool_name, input));
+            return None;
+        };
+        Some(InputSpec {
+            type_,
+            required: required.unwrap_or(default.is_none()),
+            default,
+            description,
+        })
     }
 
+    /// Static name of a method: `search() {}` or `"web-search"() {}`.
+    /// Computed and numeric names give `None`.
     fn get_method_name(&self, method: &ClassMethod) -> Option<String> {
         match &method.key {
             swc_ecma_ast::PropName::Ident(ident) => Some(ident.sym.to_string()),
+            swc_ecma_ast::PropName::Str(s) => Some(s.value.to_string()),
             _ => None,
         }
     }
 
-    fn get_string_array(&self, kv: &KeyValueProp) -> Vec<String> {
-        let mut items = Vec::new();
-        if let Expr::Array(array_lit) = &*kv.value {
-            for elem in &array_lit.elems {
-                if let Some(expr) = elem {
-                    if let Expr::Lit(Lit::Str(s)) = &*expr.expr {
-                        items.push(s.value.to_string());
+    /// Read team members given either as agent names or as `{ agent, role }`
+    /// objects. Members without a static agent name are recorded as errors.
+    fn get_team_members(&mut self, team_name: &str, kv: &KeyValueProp) -> Vec<TeamMember> {
+        let mut members = Vec::new();
+        let array_lit = match &*kv.value {
+            Expr::Array(array_lit) => array_lit,
+            _ => return members,
+        };
+
+        for expr in array_lit.elems.iter().flatten() {
+            match &*expr.expr {
+                Expr::Object(obj) => {
+                    let mut agent = None;
+                    let mut role = None;
+                    for prop in &obj.props {
+                        if let Some(member_kv) = prop.as_prop().and_then(|p| p.as_key_value()) {
+                            match self.get_prop_key(member_kv).as_str() {
+                                "agent" => agent = self.get_static_string(&member_kv.value, "agent"),
+                                "role" => role = self.get_static_string(&member_kv.value, "role"),
+                                _ => {}
+                            }
+                        }
+                    }
+                    match agent {
+                        Some(agent) => members.push(TeamMember { agent, role }),
+                        None => self.errors.push(format!(
+                            "Team '{}' has a member without a static 'agent' name",
+                            team_name
+                        )),
+                    }
+                }
+                other => {
+                    if let Some(agent) = self.get_static_string(other, "members") {
+                        members.push(TeamMember { agent, role: None });
                     }
                 }
             }
         }
-        items
+
+        members
     }
 
-    fn get_tools_list(&self, kv: &KeyValueProp) -> Vec<String> {
-        let mut tools = Vec::new();
+    fn get_string_list(&mut self, kv: &KeyValueProp) -> Vec<String> {
+        let key = self.get_prop_key(kv);
+        let mut values = Vec::new();
         if let Expr::Array(array_lit) = &*kv.value {
-            for elem in &array_lit.elems {
-                if let Some(expr) = elem {
-                    if let Expr::Lit(Lit::Str(s)) = &*expr.expr {
-                        tools.push(s.value.to_string());
-                    }
+            for expr in array_lit.elems.iter().flatten() {
+                if let Some(value) = self.get_static_string(&expr.expr, &key) {
+                    values.push(value);
+                }
+            }
+        }
+        values
+    }
+
+    /// Read a `tags` array, warning about entries that are not strings
+    fn get_tags(&mut self, owner: &str, kv: &KeyValueProp) -> Vec<String> {
+        let mut tags = Vec::new();
+        if let Expr::Array(array_lit) = &*kv.value {
+            for expr in array_lit.elems.iter().flatten() {
+                match self.get_static_string(&expr.expr, "tags") {
+                    Some(tag) => tags.push(tag),
+                    // Template literals already warn about their substitutions
+                    None if matches!(&*expr.expr, Expr::Tpl(_)) => {}
+                    None => self.warnings.push(Warning::new(WarningKind::InvalidTag, format!(
+                        "Ignoring non-string tag on '{}'",
+                        owner
+                    ))),
                 }
             }
         }
-        tools
+        tags
     }
 }
 
 /// Implement the `Visit` trait to hook into the AST traversal process.
-impl<'ast> Visit for AstVisitor {
+impl Visit for AstVisitor {
     fn visit_fn_decl(&mut self, func: &FnDecl) {
         for decorator in &func.function.decorators {
             if let Some(call) = decorator.expr.as_call() {
                 if let Some(ident) = call.callee.as_expr().and_then(|e| e.as_ident()) {
                     if ident.sym.as_ref() == "tool" {
-                        self.parse_tool_decorator(func.ident.sym.to_string(), decorator);
+                        self.parse_tool_decorator(func.ident.sym.to_string(), &func.function, decorator);
                         return;
                     }
                 }
@@ -227,8 +677,30 @@ impl<'ast> Visit for AstVisitor {
             if let Some(call) = decorator.expr.as_call() {
                 if let Some(ident) = call.callee.as_expr().and_then(|e| e.as_ident()) {
                     if ident.sym.as_ref() == "tool" {
-                        if let Some(tool_name) = self.get_method_name(method) {
-                            self.parse_tool_decorator(tool_name, decorator);
+                        // Async and generator methods are ordinary methods here
+                        if method.kind != MethodKind::Method {
+                            let accessor = if method.kind == MethodKind::Getter { "getter" } else { "setter" };
+                            self.warnings.push(Warning::new(WarningKind::UnsupportedTool, format!(
+                                "@tool on {} '{}' is ignored: only methods can be tools",
+                                accessor,
+                                self.get_method_name(method).unwrap_or_default()
+                            )));
+                            return;
+                        }
+                        let Some(tool_name) = self.get_method_name(method) else {
+                            self.warnings.push(Warning::new(
+                                WarningKind::UnsupportedTool,
+                                "@tool on a method with a computed name is ignored: give the method a static name",
+                            ));
+                            return;
+                        };
+                        self.check_tool_host(&tool_name);
+                        self.parse_tool_decorator(tool_name, &method.function, decorator);
+                        
+                        // The decorator may rename the tool, so take the final name
+                        if let (Some(ExtractedItem::Tool { manifest }), Some(class)) = (self.items.last_mut(), &mut self.current_class) {
+                            manifest.agent = class.agent.clone();
+                            class.method_tools.push(manifest.name.clone());
                         }
                         return; 
                     }
@@ -239,28 +711,189 @@ impl<'ast> Visit for AstVisitor {
     }
 
     fn visit_class_decl(&mut self, class: &ClassDecl) {
-        for decorator in &class.class.decorators {
+        self.visit_decorated_class(class.ident.sym.to_string(), &class.class);
+    }
+
+    /// `export class` is an ordinary class declaration, but `export default class`
+    /// is a class expression and may be anonymous
+    fn visit_export_default_decl(&mut self, export: &ExportDefaultDecl) {
+        match &export.decl {
+            DefaultDecl::Class(ClassExpr { ident, class }) => {
+                let name = ident.as_ref().map(|ident| ident.sym.to_string()).unwrap_or_default();
+                self.visit_decorated_class(name, class);
+            }
+            _ => export.visit_children_with(self),
+        }
+    }
+}
+
+impl AstVisitor {
+    /// Extract a class's decorator, then visit its members. `class_name` is
+    /// empty for an anonymous default-exported class.
+    fn visit_decorated_class(&mut self, class_name: String, class: &Class) {
+        // A class can only be one kind; only its first kind decorator is extracted
+        let kinds: Vec<String> = class.decorators.iter()
+            .filter_map(|decorator| decorator.expr.as_call()?.callee.as_expr()?.as_ident())
+            .map(|ident| ident.sym.to_string())
+            .filter(|name| KIND_DECORATORS.contains(&name.as_str()))
+            .collect();
+        if kinds.len() > 1 {
+            self.errors.push(format!(
+                "Class '{}' has conflicting decorators {}: a class can only be one of @agent, @team, @pipeline or @workflow",
+                if class_name.is_empty() { "default" } else { &class_name },
+                kinds.iter().map(|kind| format!("@{}", kind)).collect::<Vec<_>>().join(" and ")
+            ));
+        }
+
+        let mut class_decorator = None;
+        for decorator in &class.decorators {
             if let Some(call) = decorator.expr.as_call() {
                 if let Some(ident) = call.callee.as_expr().and_then(|e| e.as_ident()) {
                     match ident.sym.as_ref() {
-                        "agent" => {
-                            self.parse_agent_decorator(class, decorator);
-                            return; 
-                        }
-                        "team" => {
-                            self.parse_team_decorator(class, decorator);
-                            return;
-                        }
-                        "pipeline" => {
-                            self.parse_pipeline_decorator(class, decorator);
-                            return;
-                        }
-                        _ => {}
+                        "agent" => self.parse_agent_decorator(class_name.clone(), decorator),
+                        "team" => self.parse_team_decorator(class_name.clone(), decorator),
+                        "pipeline" => self.parse_pipeline_decorator(class_name.clone(), decorator),
+                        "workflow" => self.parse_workflow_decorator(class_name.clone(), decorator),
+                        "aria" => {}
+                        _ => continue,
                     }
+                    class_decorator = Some(ident.sym.to_string());
+                    break;
                 }
             }
         }
-        // If it's not a decorated class we care about, visit its children
+
+        // An anonymous class can only be named by its decorator
+        if let Some(decorator) = class_decorator.as_deref().filter(|d| *d != "aria") {
+            if self.items.last().is_some_and(|item| item.name().is_empty()) {
+                self.errors.push(format!(
+                    "Anonymous default-exported @{} class needs a name: name the class or set `name` in the decorator",
+                    decorator
+                ));
+            }
+        }
+
+        // The agent's manifest was just pushed; its method tools are added once the body is visited
+        let agent_index = (class_decorator.as_deref() == Some("agent")).then(|| self.items.len() - 1);
+
+        // Visit members with the class context so decorated methods can be validated
+        let parent = self.current_class.replace(ClassContext {
+            name: class_name,
+            decorator: class_decorator,
+            agent: agent_index.map(|index| self.items[index].name().to_string()),
+            method_tools: Vec::new(),
+        });
         class.visit_children_with(self);
+        let context = std::mem::replace(&mut self.current_class, parent);
+
+        if let (Some(index), Some(context)) = (agent_index, context) {
+            self.add_method_tools(index, context.method_tools);
+        }
+    }
+}
+
+/// Key-value properties of a decorator's object arguments, in order. The
+/// config object comes first and may be followed by an options object, as in
+/// `@tool({ name: "search" }, { timeout: 5000 })`. Properties are applied in
+/// order, so the second argument overrides the first on conflict.
+fn decorator_props(decorator: &swc_ecma_ast::Decorator) -> Vec<&KeyValueProp> {
+    match decorator.expr.as_call() {
+        Some(call) => call.args.iter()
+            .take(2)
+            .filter(|arg| arg.spread.is_none())
+            .filter_map(|arg| match &*arg.expr {
+                Expr::Object(obj) => Some(obj),
+                _ => None,
+            })
+            .flat_map(|obj| obj.props.iter())
+            .filter_map(|prop| prop.as_prop().and_then(|p| p.as_key_value()))
+            .collect(),
+        None => Vec::new(),
     }
-} 
\ No newline at end of file
+}
+
+/// Name of the value a tool resolves to: its return type with any
+/// `Promise<...>` unwrapped
+fn return_type_name(ty: &TsType) -> Option<String> {
+    if let TsType::TsTypeRef(type_ref) = ty {
+        let is_promise = matches!(&type_ref.type_name, TsEntityName::Ident(ident) if ident.sym.as_ref() == "Promise");
+        if let (true, Some(params)) = (is_promise, &type_ref.type_params) {
+            if let [inner] = params.params.as_slice() {
+                return type_name(inner);
+            }
+        }
+    }
+    type_name(ty)
+}
+
+/// Render a type annotation as TypeScript source. Covers keywords, arrays,
+/// named types with their arguments, and unions; anything else (object
+/// literals, function types, ...) gives `None`.
+fn type_name(ty: &TsType) -> Option<String> {
+    match ty {
+        TsType::TsKeywordType(keyword) => Some(match keyword.kind {
+            TsKeywordTypeKind::TsAnyKeyword => "any",
+            TsKeywordTypeKind::TsUnknownKeyword => "unknown",
+            TsKeywordTypeKind::TsNumberKeyword => "number",
+            TsKeywordTypeKind::TsObjectKeyword => "object",
+            TsKeywordTypeKind::TsBooleanKeyword => "boolean",
+            TsKeywordTypeKind::TsBigIntKeyword => "bigint",
+            TsKeywordTypeKind::TsStringKeyword => "string",
+            TsKeywordTypeKind::TsSymbolKeyword => "symbol",
+            TsKeywordTypeKind::TsVoidKeyword => "void",
+            TsKeywordTypeKind::TsUndefinedKeyword => "undefined",
+            TsKeywordTypeKind::TsNullKeyword => "null",
+            TsKeywordTypeKind::TsNeverKeyword => "never",
+            _ => return None,
+        }.to_string()),
+        TsType::TsArrayType(array) => {
+            let elem = type_name(&array.elem_type)?;
+            // Parenthesize unions so `(A | B)[]` keeps its meaning
+            match without_parens(&array.elem_type) {
+                TsType::TsUnionOrIntersectionType(_) => Some(format!("({})[]", elem)),
+                _ => Some(format!("{}[]", elem)),
+            }
+        }
+        TsType::TsTypeRef(type_ref) => {
+            let name = entity_name(&type_ref.type_name);
+            match &type_ref.type_params {
+                Some(params) => {
+                    let args = params.params.iter().map(|p| type_name(p)).collect::<Option<Vec<_>>>()?;
+                    Some(format!("{}<{}>", name, args.join(", ")))
+                }
+                None => Some(name),
+            }
+        }
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            let types = union.types.iter().map(|t| type_name(t)).collect::<Option<Vec<_>>>()?;
+            Some(types.join(" | "))
+        }
+        TsType::TsParenthesizedType(paren) => type_name(&paren.type_ann),
+        _ => None,
+    }
+}
+
+/// `ty` with any enclosing parentheses removed
+fn without_parens(ty: &TsType) -> &TsType {
+    match ty {
+        TsType::TsParenthesizedType(paren) => without_parens(&paren.type_ann),
+        _ => ty,
+    }
+}
+
+/// Render a possibly qualified type name such as `Api.SearchResult`
+fn entity_name(name: &TsEntityName) -> String {
+    match name {
+        TsEntityName::Ident(ident) => ident.sym.to_string(),
+        TsEntityName::TsQualifiedName(qualified) => format!("{}.{}", entity_name(&qualified.left), qualified.right.sym),
+    }
+}
+
+/// Convert a JS number to JSON, keeping whole numbers as integers
+fn number_to_json(value: f64) -> Option<serde_json::Value> {
+    if value.fract() == 0.0 && value.abs() < i64::MAX as f64 {
+        Some(serde_json::Value::from(value as i64))
+    } else {
+        serde_json::Number::from_f64(value).map(serde_json::Value::Number)
+    }
+}
@@ -1,22 +1,55 @@
 use anyhow::{Result, anyhow};
 use serde::{Serialize, Deserialize};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
+use crate::bundle::{ManifestFormat, HASH_PLACEHOLDER};
+use crate::bundle::hash::HashAlgorithm;
+use crate::compiler::schema::SourceEncoding;
+
+/// Fields that `--frozen` requires aria.toml, or a file it extends, to set
+/// explicitly rather than fall back to a default
+pub const REQUIRED_FIELDS: &[&str] = &[
+    "project.name",
+    "project.version",
+    "project.description",
+    "build.target",
+    "build.source_dirs",
+    "runtime.bun_version",
+];
+
+/// Fields renamed since earlier aria.toml formats, as `(old, new)` pairs of
+/// `section.key` paths. `arc upgrade` moves values from old keys to new ones.
+pub const RENAMED_FIELDS: &[(&str, &str)] = &[];
+
 /// Project configuration loaded from aria.toml
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProjectConfig {
     pub project: ProjectInfo,
     pub build: BuildConfig,
     pub runtime: RuntimeConfig,
+    #[serde(default, skip_serializing_if = "DeployConfig::is_empty")]
+    pub deploy: DeployConfig,
+    /// Named `[profile.<name>]` tables that override top-level fields
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profile: BTreeMap<String, toml::Value>,
 }
 
 impl ProjectConfig {
-    /// Load configuration from aria.toml file
+    /// Load configuration from aria.toml file, following any `extends` chain
     pub async fn load_from_file(path: &Path) -> Result<Self> {
-        let content = fs::read_to_string(path).await?;
-        let config: ProjectConfig = toml::from_str(&content)
-            .map_err(|e| anyhow!("Failed to parse aria.toml: {}", e))?;
+        let mut chain = Self::read_extends_chain(path).await?;
+        
+        // The root base must be a complete config; each extending file is merged over it
+        let (root_path, root_value) = chain.pop().expect("config chain is never empty");
+        let mut config: ProjectConfig = root_value.try_into()
+            .map_err(|e| anyhow!("Failed to parse {}: {}", root_path.display(), e))?;
+        
+        while let Some((child_path, child_value)) = chain.pop() {
+            let overlay = ConfigOverlay::from_value(&child_path.display().to_string(), child_value)?;
+            config = ConfigUtils::apply(config, overlay);
+        }
         
         // Validate configuration
         config.validate()?;
@@ -24,6 +57,118 @@ impl ProjectConfig {
         Ok(config)
     }
     
+    /// Read the requested file and every file it `extends`, in order from the
+    /// requested file to its root base, with `extends` keys removed
+    async fn read_extends_chain(path: &Path) -> Result<Vec<(PathBuf, toml::Value)>> {
+        // Walk the `extends` chain from the requested file to its root base
+        let mut chain: Vec<(PathBuf, toml::Value)> = Vec::new();
+        let mut current = path.to_path_buf();
+        
+        loop {
+            let canonical = current.canonicalize()
+                .map_err(|e| anyhow!("Failed to read config {}: {}", current.display(), e))?;
+            
+            if chain.iter().any(|(p, _)| p == &canonical) {
+                let cycle: Vec<String> = chain.iter()
+                    .map(|(p, _)| p.display().to_string())
+                    .chain(std::iter::once(canonical.display().to_string()))
+                    .collect();
+                return Err(anyhow!("Cycle detected in config extends: {}", cycle.join(" -> ")));
+            }
+            
+            let content = fs::read_to_string(&canonical).await?;
+            let mut value: toml::Value = toml::from_str(&content)
+                .map_err(|e| anyhow!("Failed to parse {}: {}", canonical.display(), e))?;
+            
+            let extends = match value.as_table_mut().and_then(|t| t.remove("extends")) {
+                Some(toml::Value::String(extends)) => Some(extends),
+                Some(_) => return Err(anyhow!("'extends' in {} must be a path string", canonical.display())),
+                None => None,
+            };
+            
+            let parent_dir = canonical.parent().map(Path::to_path_buf).unwrap_or_default();
+            
+            // Dependency bundles are relative to the file that lists them
+            let dependencies = value.get_mut("build")
+                .and_then(|build| build.get_mut("dependencies"))
+                .and_then(|deps| deps.as_array_mut());
+            for dependency in dependencies.into_iter().flatten() {
+                if let Some(dependency_path) = dependency.as_str() {
+                    *dependency = toml::Value::String(parent_dir.join(dependency_path).display().to_string());
+                }
+            }
+            
+            chain.push((canonical, value));
+            
+            match extends {
+                Some(extends) => current = parent_dir.join(extends),
+                None => break,
+            }
+        }
+        
+        Ok(chain)
+    }
+    
+    /// List the `REQUIRED_FIELDS` that neither `path` nor any file it extends sets
+    pub async fn missing_required_fields(path: &Path) -> Result<Vec<&'static str>> {
+        let chain = Self::read_extends_chain(path).await?;
+        
+        Ok(REQUIRED_FIELDS.iter()
+            .copied()
+            .filter(|field| {
+                let (section, key) = field.split_once('.').expect("required fields are section.key");
+                !chain.iter().any(|(_, value)| value.get(section).and_then(|t| t.get(key)).is_some())
+            })
+            .collect())
+    }
+    
+    /// Read a single aria.toml permissively and migrate it to the current
+    /// schema: renamed keys are moved, missing required fields get their
+    /// defaults and unknown keys are dropped.
+    ///
+    /// Files that `extends` another are partial configs and are rejected.
+    pub async fn upgrade_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).await
+            .map_err(|e| anyhow!("Failed to read config {}: {}", path.display(), e))?;
+        let mut value: toml::Value = toml::from_str(&content)
+            .map_err(|e| anyhow!("Failed to parse {}: {}", path.display(), e))?;
+        
+        if value.get("extends").is_some() {
+            return Err(anyhow!(
+                "{} extends another config; only standalone files can be upgraded",
+                path.display()
+            ));
+        }
+        
+        rename_fields(&mut value, RENAMED_FIELDS);
+        if let Some(profiles) = value.get_mut("profile").and_then(|p| p.as_table_mut()) {
+            for (_, profile) in profiles.iter_mut() {
+                rename_fields(profile, RENAMED_FIELDS);
+            }
+        }
+        
+        let defaults = toml::Value::try_from(ProjectConfig::default())
+            .map_err(|e| anyhow!("Failed to serialize default config: {}", e))?;
+        if let Some(table) = value.as_table_mut() {
+            for field in REQUIRED_FIELDS {
+                let (section, key) = field.split_once('.').expect("required fields are section.key");
+                if let Some(default) = defaults.get(section).and_then(|t| t.get(key)) {
+                    let section_table = table.entry(section)
+                        .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+                    if let Some(section_table) = section_table.as_table_mut() {
+                        section_table.entry(key).or_insert_with(|| default.clone());
+                    }
+                }
+            }
+        }
+        
+        let config: ProjectConfig = value.try_into()
+            .map_err(|e| anyhow!("Failed to migrate {}: {}", path.display(), e))?;
+        config.validate()?;
+        
+        Ok(config)
+    }
+    
     /// Save configuration to aria.toml file
     pub async fn save_to_file(&self, path: &Path) -> Result<()> {
         let content = toml::to_string_pretty(self)
@@ -49,6 +194,24 @@ impl ProjectConfig {
             _ => return Err(anyhow!("Invalid build target: {}", self.build.target)),
         }
         
+        if let Some(permissions) = self.build.file_permissions {
+            if permissions > 0o777 {
+                return Err(anyhow!("Invalid build.file_permissions {:#o}: must be at most 0o777", permissions));
+            }
+        }
+        
+        if self.deploy.socket.is_some() && self.deploy.endpoint.is_some() {
+            return Err(anyhow!("deploy.socket and deploy.endpoint cannot both be set"));
+        }
+        if self.deploy.socket.as_deref() == Some("") {
+            return Err(anyhow!("deploy.socket cannot be empty"));
+        }
+        if let Some(endpoint) = &self.deploy.endpoint {
+            if !endpoint.starts_with("http://") {
+                return Err(anyhow!("Invalid deploy.endpoint '{}': must be an http:// URL", endpoint));
+            }
+        }
+        
         Ok(())
     }
     
@@ -57,6 +220,44 @@ impl ProjectConfig {
         self.build.output.as_deref()
     }
     
+    /// Interpolate `{name}` and `{version}` placeholders in an output path template.
+    /// `{hash}` is left in place for the bundle writer to fill in once the
+    /// content hash is known, and may only appear in the file name.
+    pub fn render_output_template(&self, template: &str) -> Result<String> {
+        let mut rendered = String::new();
+        let mut rest = template;
+        
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let end = rest[start..].find('}')
+                .ok_or_else(|| anyhow!("Unclosed placeholder in output template '{}'", template))?;
+            let placeholder = &rest[start + 1..start + end];
+            
+            match placeholder {
+                "name" => rendered.push_str(&self.project.name),
+                "version" => rendered.push_str(&self.project.version),
+                "hash" => {
+                    if rest[start..].contains(['/', '\\']) {
+                        return Err(anyhow!(
+                            "Placeholder '{{hash}}' may only appear in the file name of output template '{}'",
+                            template
+                        ));
+                    }
+                    rendered.push_str(HASH_PLACEHOLDER);
+                }
+                _ => return Err(anyhow!(
+                    "Unknown placeholder '{{{}}}' in output template '{}'. Supported placeholders: {{name}}, {{version}}, {{hash}}",
+                    placeholder, template
+                )),
+            }
+            
+            rest = &rest[start + end + 1..];
+        }
+        rendered.push_str(rest);
+        
+        Ok(rendered)
+    }
+    
     /// Get the source directories
     pub fn get_source_dirs(&self) -> Vec<&str> {
         self.build.source_dirs.iter().map(|s| s.as_str()).collect()
@@ -67,10 +268,46 @@ impl ProjectConfig {
         self.build.watch.unwrap_or(false)
     }
     
+    /// Layout of manifest.json: `build.manifest_format` when set, otherwise
+    /// compact for size-optimized builds and pretty for the rest
+    pub fn manifest_format(&self) -> ManifestFormat {
+        match (self.build.manifest_format, &self.build.optimization) {
+            (Some(format), _) => format,
+            (None, Some(OptimizationLevel::Size)) => ManifestFormat::Compact,
+            (None, _) => ManifestFormat::Pretty,
+        }
+    }
+    
     /// Get exclude patterns for file discovery
     pub fn get_exclude_patterns(&self) -> Vec<&str> {
         self.build.exclude.iter().map(|s| s.as_str()).collect()
     }
+    
+    /// Get the names of all profiles defined in this configuration
+    pub fn available_profiles(&self) -> Vec<&str> {
+        self.profile.keys().map(|s| s.as_str()).collect()
+    }
+    
+    /// Apply a named profile on top of this configuration
+    pub fn with_profile(self, name: &str) -> Result<Self> {
+        let profile = match self.profile.get(name) {
+            Some(profile) => profile.clone(),
+            None => {
+                let available = self.available_profiles();
+                return Err(anyhow!(
+                    "Unknown profile '{}'. Available profiles: {}",
+                    name,
+                    if available.is_empty() { "(none)".to_string() } else { available.join(", ") }
+                ));
+            }
+        };
+        
+        let overlay = ConfigOverlay::from_value(&format!("profile '{}'", name), profile)?;
+        let merged = ConfigUtils::apply(self, overlay);
+        merged.validate()?;
+        
+        Ok(merged)
+    }
 }
 
 impl Default for ProjectConfig {
@@ -96,12 +333,21 @@ impl Default for ProjectConfig {
                 ],
                 watch: Some(false),
                 optimization: Some(OptimizationLevel::Release),
+                hash_algorithm: None,
+                source_encoding: None,
+                manifest_format: None,
+                file_permissions: None,
+                dependencies: vec![],
+                metadata: BTreeMap::new(),
             },
             runtime: RuntimeConfig {
                 bun_version: "latest".to_string(),
                 node_version: None,
                 environment: vec![],
+                quilt_socket: None,
             },
+            deploy: DeployConfig::default(),
+            profile: BTreeMap::new(),
         }
     }
 }
@@ -129,6 +375,27 @@ pub struct BuildConfig {
     pub exclude: Vec<String>,
     pub watch: Option<bool>,
     pub optimization: Option<OptimizationLevel>,
+    /// Algorithm for the bundle hash (default: blake3). Uploads are always
+    /// verified with blake3 and also carry this digest when it differs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_algorithm: Option<HashAlgorithm>,
+    /// Encoding of transpiled sources in the bundle: "identity" (default) or
+    /// "gzip" for runtimes that decompress sources on load
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_encoding: Option<SourceEncoding>,
+    /// Layout of manifest.json: "pretty" or "compact" (default: compact for
+    /// size-optimized builds, pretty otherwise)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_format: Option<ManifestFormat>,
+    /// Unix permissions of files in the bundle, e.g. `0o640` (default: 0o644)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_permissions: Option<u32>,
+    /// Paths of other .aria bundles whose tools agents may reference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<String>,
+    /// Key-values recorded in the bundle's build metadata, e.g. CI run ids
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
 }
 
 /// Runtime configuration section
@@ -138,6 +405,29 @@ pub struct RuntimeConfig {
     pub node_version: Option<String>,
     #[serde(default)]
     pub environment: Vec<EnvironmentVariable>,
+    /// Unix socket of the Quilt daemon used by `arc upload`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quilt_socket: Option<String>,
+}
+
+/// Deploy configuration section, used by `arc upload` and auto-upload
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeployConfig {
+    /// Unix socket of the Quilt daemon; takes precedence over `runtime.quilt_socket`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+    /// `http://` address of a Quilt daemon listening on TCP, instead of `socket`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Upload the bundle after every successful `arc build`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_upload: Option<bool>,
+}
+
+impl DeployConfig {
+    pub fn is_empty(&self) -> bool {
+        self.socket.is_none() && self.endpoint.is_none() && self.auto_upload.is_none()
+    }
 }
 
 /// Environment variable configuration
@@ -159,6 +449,91 @@ pub enum OptimizationLevel {
     Size,
 }
 
+/// A partial configuration: a `[profile.<name>]` table or a file that
+/// `extends` another. Only the fields it sets replace the base config's,
+/// so it can also set a field back to its default value.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigOverlay {
+    #[serde(default)]
+    pub project: ProjectOverlay,
+    #[serde(default)]
+    pub build: BuildOverlay,
+    #[serde(default)]
+    pub runtime: RuntimeOverlay,
+    #[serde(default)]
+    pub deploy: DeployConfig,
+    #[serde(default)]
+    pub profile: BTreeMap<String, toml::Value>,
+}
+
+impl ConfigOverlay {
+    /// Parse a partial config table; `label` names it in errors
+    pub fn from_value(label: &str, value: toml::Value) -> Result<Self> {
+        value.try_into()
+            .map_err(|e| anyhow!("Failed to parse {}: {}", label, e))
+    }
+}
+
+/// Project information fields a `ConfigOverlay` sets
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectOverlay {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub description: Option<String>,
+    pub authors: Option<Vec<String>>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+}
+
+/// Build fields a `ConfigOverlay` sets
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BuildOverlay {
+    pub target: Option<String>,
+    pub output: Option<String>,
+    pub source_dirs: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub watch: Option<bool>,
+    pub optimization: Option<OptimizationLevel>,
+    pub hash_algorithm: Option<HashAlgorithm>,
+    pub source_encoding: Option<SourceEncoding>,
+    pub manifest_format: Option<ManifestFormat>,
+    pub file_permissions: Option<u32>,
+    pub dependencies: Option<Vec<String>>,
+    /// Merged key by key into the base metadata
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Runtime fields a `ConfigOverlay` sets
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RuntimeOverlay {
+    pub bun_version: Option<String>,
+    pub node_version: Option<String>,
+    pub environment: Option<Vec<EnvironmentVariable>>,
+    pub quilt_socket: Option<String>,
+}
+
+/// Move values from the old keys in `renames` to their new names, unless
+/// the new key is already set
+fn rename_fields(value: &mut toml::Value, renames: &[(&str, &str)]) {
+    for (old, new) in renames {
+        let (old_section, old_key) = old.split_once('.').expect("renamed fields are section.key");
+        let (new_section, new_key) = new.split_once('.').expect("renamed fields are section.key");
+        
+        let moved = value.get_mut(old_section)
+            .and_then(|section| section.as_table_mut())
+            .and_then(|section| section.remove(old_key));
+        if let (Some(moved), Some(table)) = (moved, value.as_table_mut()) {
+            let section = table.entry(new_section)
+                .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+            if let Some(section) = section.as_table_mut() {
+                section.entry(new_key).or_insert(moved);
+            }
+        }
+    }
+}
+
 /// Default source directories
 fn default_source_dirs() -> Vec<String> {
     vec!["src".to_string()]
@@ -300,65 +675,59 @@ impl ConfigTemplates {
 pub struct ConfigUtils;
 
 impl ConfigUtils {
-    /// Merge two configurations (right takes precedence)
-    pub fn merge(base: ProjectConfig, override_config: ProjectConfig) -> ProjectConfig {
+    /// Apply a partial config over `base`; fields the overlay leaves unset
+    /// keep their base values
+    pub fn apply(base: ProjectConfig, overlay: ConfigOverlay) -> ProjectConfig {
         ProjectConfig {
             project: ProjectInfo {
-                name: if override_config.project.name != "aria-project" {
-                    override_config.project.name
-                } else {
-                    base.project.name
-                },
-                version: if override_config.project.version != "0.1.0" {
-                    override_config.project.version
-                } else {
-                    base.project.version
-                },
-                description: if override_config.project.description != "An Aria agentic application" {
-                    override_config.project.description
-                } else {
-                    base.project.description
-                },
-                authors: if !override_config.project.authors.is_empty() {
-                    override_config.project.authors
-                } else {
-                    base.project.authors
-                },
-                license: override_config.project.license.or(base.project.license),
-                repository: override_config.project.repository.or(base.project.repository),
+                name: overlay.project.name.unwrap_or(base.project.name),
+                version: overlay.project.version.unwrap_or(base.project.version),
+                description: overlay.project.description.unwrap_or(base.project.description),
+                authors: overlay.project.authors.unwrap_or(base.project.authors),
+                license: overlay.project.license.or(base.project.license),
+                repository: overlay.project.repository.or(base.project.repository),
             },
             build: BuildConfig {
-                target: if override_config.build.target != "typescript" {
-                    override_config.build.target
-                } else {
-                    base.build.target
+                target: overlay.build.target.unwrap_or(base.build.target),
+                output: overlay.build.output.or(base.build.output),
+                source_dirs: overlay.build.source_dirs.unwrap_or(base.build.source_dirs),
+                exclude: overlay.build.exclude.unwrap_or(base.build.exclude),
+                watch: overlay.build.watch.or(base.build.watch),
+                optimization: overlay.build.optimization.or(base.build.optimization),
+                hash_algorithm: overlay.build.hash_algorithm.or(base.build.hash_algorithm),
+                source_encoding: overlay.build.source_encoding.or(base.build.source_encoding),
+                manifest_format: overlay.build.manifest_format.or(base.build.manifest_format),
+                file_permissions: overlay.build.file_permissions.or(base.build.file_permissions),
+                dependencies: overlay.build.dependencies.unwrap_or(base.build.dependencies),
+                metadata: {
+                    let mut metadata = base.build.metadata;
+                    metadata.extend(overlay.build.metadata);
+                    metadata
                 },
-                output: override_config.build.output.or(base.build.output),
-                source_dirs: if !override_config.build.source_dirs.is_empty() {
-                    override_config.build.source_dirs
-                } else {
-                    base.build.source_dirs
-                },
-                exclude: if !override_config.build.exclude.is_empty() {
-                    override_config.build.exclude
-                } else {
-                    base.build.exclude
-                },
-                watch: override_config.build.watch.or(base.build.watch),
-                optimization: override_config.build.optimization.or(base.build.optimization),
             },
             runtime: RuntimeConfig {
-                bun_version: if override_config.runtime.bun_version != "latest" {
-                    override_config.runtime.bun_version
-                } else {
-                    base.runtime.bun_version
-                },
-                node_version: override_config.runtime.node_version.or(base.runtime.node_version),
-                environment: if !override_config.runtime.environment.is_empty() {
-                    override_config.runtime.environment
+                bun_version: overlay.runtime.bun_version.unwrap_or(base.runtime.bun_version),
+                node_version: overlay.runtime.node_version.or(base.runtime.node_version),
+                environment: overlay.runtime.environment.unwrap_or(base.runtime.environment),
+                quilt_socket: overlay.runtime.quilt_socket.or(base.runtime.quilt_socket),
+            },
+            deploy: {
+                // Socket and endpoint are one daemon address; setting either replaces both
+                let address = if overlay.deploy.socket.is_some() || overlay.deploy.endpoint.is_some() {
+                    &overlay.deploy
                 } else {
-                    base.runtime.environment
-                },
+                    &base.deploy
+                };
+                DeployConfig {
+                    socket: address.socket.clone(),
+                    endpoint: address.endpoint.clone(),
+                    auto_upload: overlay.deploy.auto_upload.or(base.deploy.auto_upload),
+                }
+            },
+            profile: {
+                let mut profile = base.profile;
+                profile.extend(overlay.profile);
+                profile
             },
         }
     }
@@ -373,11 +742,39 @@ impl ConfigUtils {
             return Err(anyhow!("Configuration path is not a file: {}", path.display()));
         }
         
-        // Try to read and parse the file
-        let content = fs::read_to_string(path).await?;
-        toml::from_str::<ProjectConfig>(&content)
+        // Try to read and parse the file, including any base it extends
+        ProjectConfig::load_from_file(path).await
             .map_err(|e| anyhow!("Invalid configuration file: {}", e))?;
         
         Ok(())
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENAMES: &[(&str, &str)] = &[("build.out", "build.output"), ("runtime.socket", "deploy.socket")];
+
+    #[test]
+    fn renamed_keys_move_to_their_new_names() {
+        let mut value: toml::Value = toml::from_str("[build]\nout = \"dist/app.aria\"\n\n[runtime]\nsocket = \"/tmp/quilt.sock\"\n").unwrap();
+
+        rename_fields(&mut value, RENAMES);
+
+        assert_eq!(value["build"].get("out"), None);
+        assert_eq!(value["build"]["output"].as_str(), Some("dist/app.aria"));
+        assert_eq!(value["runtime"].get("socket"), None);
+        assert_eq!(value["deploy"]["socket"].as_str(), Some("/tmp/quilt.sock"));
+    }
+
+    #[test]
+    fn renamed_keys_do_not_replace_the_new_key() {
+        let mut value: toml::Value = toml::from_str("[build]\nout = \"old.aria\"\noutput = \"new.aria\"\n").unwrap();
+
+        rename_fields(&mut value, RENAMES);
+
+        assert_eq!(value["build"].get("out"), None);
+        assert_eq!(value["build"]["output"].as_str(), Some("new.aria"));
+    }
+}
@@ -1,11 +1,15 @@
 use anyhow::{Result, anyhow};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio::sync::mpsc;
+use tonic::codec::CompressionEncoding;
 use tonic::transport::{Endpoint, Channel, Uri};
 use tower::service_fn;
 
+use crate::bundle::hash::HashAlgorithm;
+
 
 // Include the generated gRPC code
 pub mod quilt {
@@ -19,9 +23,9 @@ use quilt::{
     ValidateBundleRequest,
 };
 
-use crate::cli::{print_status, print_info, print_error};
 
-const DEFAULT_QUILT_SOCKET: &str = "/run/quilt/api.sock";
+/// Socket the Quilt daemon listens on when none is configured
+pub const DEFAULT_QUILT_SOCKET: &str = "/run/quilt/api.sock";
 const CHUNK_SIZE: usize = 64 * 1024; // 64KB chunks
 
 /// Progress information for bundle uploads
@@ -30,6 +34,8 @@ pub struct UploadProgress {
     pub bytes_uploaded: u64,
     pub total_bytes: u64,
     pub percent: f64,
+    /// Average transfer rate since the upload started, in bytes per second
+    pub bytes_per_second: f64,
 }
 
 /// Result of a bundle upload operation
@@ -54,26 +60,59 @@ impl QuiltClient {
     }
     
     /// Create a new QuiltClient connected to a specific Unix socket path
+    #[tracing::instrument(name = "connect")]
     pub async fn connect_to_socket(socket_path: &str) -> Result<Self> {
-        print_info(&format!("Connecting to Quilt daemon at: {}", socket_path));
+        tracing::info!("Connecting to Quilt daemon at: {}", socket_path);
         
-        // Check if socket exists
-        if !Path::new(socket_path).exists() {
-            return Err(anyhow!("Quilt daemon socket not found: {}", socket_path));
-        }
+        // Check the path exists, is really a socket, and that we may connect
+        let socket_path = resolve_socket_path(socket_path)?;
+        probe_socket(&socket_path).await?;
         
         // Create Unix socket connection
-        let channel = Self::create_unix_channel(socket_path).await?;
+        let channel = Self::create_unix_channel(&socket_path).await?;
         let client = QuiltServiceClient::new(channel);
         
-        print_status("Connected", "Successfully connected to Quilt daemon");
+        tracing::info!(status = "Connected", "Successfully connected to Quilt daemon");
         
         Ok(Self { client })
     }
     
+    /// Connect to a daemon address: an `http://` endpoint, or otherwise a Unix
+    /// socket path
+    pub async fn connect_to(address: &str) -> Result<Self> {
+        if address.starts_with("http://") {
+            Self::connect_to_endpoint(address).await
+        } else {
+            Self::connect_to_socket(address).await
+        }
+    }
+    
+    /// Create a new QuiltClient connected to a daemon listening on TCP at `endpoint`
+    #[tracing::instrument(name = "connect")]
+    pub async fn connect_to_endpoint(endpoint: &str) -> Result<Self> {
+        tracing::info!("Connecting to Quilt daemon at: {}", endpoint);
+        
+        let channel = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| anyhow!("Invalid Quilt daemon endpoint {}: {}", endpoint, e))?
+            .connect()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Quilt daemon at {}: {}", endpoint, e))?;
+        
+        tracing::info!(status = "Connected", "Successfully connected to Quilt daemon");
+        
+        Ok(Self { client: QuiltServiceClient::new(channel) })
+    }
+    
+    /// Compress requests with `encoding` and accept responses compressed the
+    /// same way. This is transport compression, independent of the bundle's own.
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.client = self.client.send_compressed(encoding).accept_compressed(encoding);
+        self
+    }
+    
     /// Create a channel connected to a Unix socket
-    async fn create_unix_channel(socket_path: &str) -> Result<Channel> {
-        let path = Path::new(socket_path).to_path_buf();
+    async fn create_unix_channel(socket_path: &Path) -> Result<Channel> {
+        let path = socket_path.to_path_buf();
 
         // The URI here is ignored because we are using a custom connector,
         // but it's a required part of the Endpoint builder.
@@ -88,35 +127,47 @@ impl QuiltClient {
         Ok(channel)
     }
     
-    /// Test connection to Quilt daemon
+    /// Test connection to Quilt daemon. A daemon without the `list_containers`
+    /// RPC counts as reachable; only transport and other RPC errors fail.
     pub async fn test_connection(&mut self) -> Result<()> {
-        print_info("Testing connection to Quilt daemon...");
+        tracing::info!("Testing connection to Quilt daemon...");
         
         // Try to list containers as a connectivity test
         match self.client.list_containers(quilt::ListContainersRequest {
             state_filter: 0, // Unspecified - list all
         }).await {
             Ok(_response) => {
-                print_status("Connected", "Quilt daemon is responding");
+                tracing::info!(status = "Connected", "Quilt daemon is responding");
+                Ok(())
+            }
+            // The daemon answered, it just doesn't offer this RPC; uploads may still work
+            Err(e) if e.code() == tonic::Code::Unimplemented => {
+                tracing::warn!("Quilt daemon does not support the list_containers health check; continuing without it");
                 Ok(())
             }
             Err(e) => {
-                print_error(&format!("Connection test failed: {}", e));
-                Err(anyhow!("Failed to communicate with Quilt daemon: {}", e))
+                tracing::error!("Connection test failed: {}", e);
+                Err(rpc_error("Failed to communicate with Quilt daemon", e))
             }
         }
     }
     
-    /// Upload a bundle to the Quilt daemon with progress reporting
+    /// Upload a bundle to the Quilt daemon with progress reporting. With
+    /// `max_rate` (bytes per second), chunks are paced to hold that average.
+    /// `custom_metadata` is sent in the request's `metadata_fields`.
+    #[tracing::instrument(name = "upload", skip(self, custom_metadata, progress_callback))]
     pub async fn upload_bundle<F>(
         &mut self,
         bundle_path: &str,
+        hash_algorithm: HashAlgorithm,
+        custom_metadata: &BTreeMap<String, String>,
+        max_rate: Option<f64>,
         progress_callback: F,
     ) -> Result<UploadResult>
     where
         F: Fn(UploadProgress) + Send + 'static,
     {
-        print_status("Uploading", &format!("bundle via gRPC: {}", bundle_path));
+        tracing::info!(status = "Uploading", "bundle via gRPC: {}", bundle_path);
         
         // Validate bundle exists
         let path = Path::new(bundle_path);
@@ -128,10 +179,13 @@ impl QuiltClient {
         let bundle_data = fs::read(path).await?;
         let total_size = bundle_data.len() as u64;
         
-        print_info(&format!("Bundle size: {:.2} MB", total_size as f64 / (1024.0 * 1024.0)));
+        tracing::info!("Bundle size: {:.2} MB", total_size as f64 / (1024.0 * 1024.0));
         
-        // Calculate blake3 hash for integrity verification
-        let blake3_hash = calculate_blake3_hash(&bundle_data)?;
+        // The daemon verifies the upload against a blake3 hash. A bundle built
+        // with another algorithm also carries that digest, named for it.
+        let checksum = HashAlgorithm::Blake3.digest(&bundle_data);
+        let mut metadata_fields: std::collections::HashMap<String, String> = custom_metadata.clone().into_iter().collect();
+        metadata_fields.extend(algorithm_digest_field(hash_algorithm, &bundle_data));
         
         // Create metadata message. The name and version can be derived from the path
         // or set to a default if not easily available without full parsing.
@@ -142,10 +196,10 @@ impl QuiltClient {
             description: "".to_string(),
             total_size_bytes: total_size,
             chunk_size_bytes: CHUNK_SIZE as u32,
-            blake3_hash: blake3_hash.clone(),
+            blake3_hash: checksum.clone(),
             signature: String::new(), // TODO: Add signature support for AUTH.MD
             uploader_identity: String::new(), // TODO: Add identity support for AUTH.MD
-            metadata_fields: std::collections::HashMap::new(),
+            metadata_fields,
         };
         
         // Create upload stream
@@ -160,57 +214,70 @@ impl QuiltClient {
             return Err(anyhow!("Failed to send metadata"));
         }
         
-        // Send bundle data in chunks
-        let mut bytes_sent = 0u64;
+        // Send bundle data in chunks while the request streams, so the pace
+        // chunks are queued at is the pace they go out on the socket
         let start_time = std::time::Instant::now();
-        
-        for chunk in bundle_data.chunks(CHUNK_SIZE) {
-            let chunk_request = UploadBundleRequest {
-                payload: Some(quilt::upload_bundle_request::Payload::Chunk(chunk.to_vec())),
-            };
+        let sender = tokio::spawn(async move {
+            let mut bytes_sent = 0u64;
             
-            if tx.send(chunk_request).await.is_err() {
-                return Err(anyhow!("Failed to send chunk"));
+            for chunk in bundle_data.chunks(CHUNK_SIZE) {
+                let chunk_request = UploadBundleRequest {
+                    payload: Some(quilt::upload_bundle_request::Payload::Chunk(chunk.to_vec())),
+                };
+                
+                // The request has ended; its response carries the error
+                if tx.send(chunk_request).await.is_err() {
+                    return;
+                }
+                
+                bytes_sent += chunk.len() as u64;
+                tracing::trace!("Sent chunk: {}/{} bytes", bytes_sent, total_size);
+                
+                // Sleep until the elapsed time matches what the bytes sent so far
+                // should have taken at the configured rate
+                if let Some(rate) = max_rate {
+                    let target = std::time::Duration::from_secs_f64(bytes_sent as f64 / rate);
+                    if let Some(ahead) = target.checked_sub(start_time.elapsed()) {
+                        tokio::time::sleep(ahead).await;
+                    }
+                }
+                
+                let progress = UploadProgress {
+                    bytes_uploaded: bytes_sent,
+                    total_bytes: total_size,
+                    percent: (bytes_sent as f64 / total_size as f64) * 100.0,
+                    bytes_per_second: bytes_sent as f64 / start_time.elapsed().as_secs_f64(),
+                };
+                
+                progress_callback(progress);
             }
             
-            bytes_sent += chunk.len() as u64;
-            let progress = UploadProgress {
-                bytes_uploaded: bytes_sent,
-                total_bytes: total_size,
-                percent: (bytes_sent as f64 / total_size as f64) * 100.0,
+            // Send final checksum; dropping the sender then closes the stream
+            let checksum_request = UploadBundleRequest {
+                payload: Some(quilt::upload_bundle_request::Payload::Checksum(checksum)),
             };
-            
-            progress_callback(progress);
-        }
-        
-        // Send final checksum
-        let checksum_request = UploadBundleRequest {
-            payload: Some(quilt::upload_bundle_request::Payload::Checksum(blake3_hash)),
-        };
-        
-        if tx.send(checksum_request).await.is_err() {
-            return Err(anyhow!("Failed to send checksum"));
-        }
-        
-        // Close the sender
-        drop(tx);
+            let _ = tx.send(checksum_request).await;
+        });
         
         // Create the stream and make the request
         let request_stream = ReceiverStream::new(rx);
         let request = tonic::Request::new(request_stream);
         
         // Send the upload request
-        match self.client.upload_bundle(request).await {
+        let response = self.client.upload_bundle(request).await;
+        sender.abort();
+        
+        match response {
             Ok(response) => {
                 let upload_response = response.into_inner();
                 let upload_time = start_time.elapsed().as_secs_f64();
                 
                 if upload_response.success {
-                    print_status("Success", "Bundle uploaded via gRPC");
-                    print_info(&format!("Bundle ID: {}", upload_response.bundle_id));
-                    print_info(&format!("Upload time: {:.2}s", upload_time));
-                    print_info(&format!("Transfer rate: {:.2} MB/s", 
-                        (total_size as f64 / (1024.0 * 1024.0)) / upload_time));
+                    tracing::info!(status = "Success", "Bundle uploaded via gRPC");
+                    tracing::info!("Bundle ID: {}", upload_response.bundle_id);
+                    tracing::info!("Upload time: {:.2}s", upload_time);
+                    tracing::info!("Transfer rate: {:.2} MB/s", 
+                        (total_size as f64 / (1024.0 * 1024.0)) / upload_time);
                     
                     Ok(UploadResult {
                         bundle_id: upload_response.bundle_id,
@@ -226,7 +293,7 @@ impl QuiltClient {
                         upload_response.error_message
                     };
                     
-                    print_error(&format!("Upload failed: {}", error_msg));
+                    tracing::error!("Upload failed: {}", error_msg);
                     
                     Ok(UploadResult {
                         bundle_id: upload_response.bundle_id,
@@ -238,10 +305,8 @@ impl QuiltClient {
                 }
             }
             Err(e) => {
-                let error_msg = format!("gRPC upload failed: {}", e);
-                print_error(&error_msg);
-                
-                Err(anyhow!(error_msg))
+                tracing::error!("gRPC upload failed: {}", e);
+                Err(rpc_error("gRPC upload failed", e))
             }
         }
     }
@@ -252,7 +317,8 @@ impl QuiltClient {
             bundle_id: bundle_id.to_string(),
         };
         
-        let response = self.client.get_bundle_info(request).await?;
+        let response = self.client.get_bundle_info(request).await
+            .map_err(|e| rpc_error("Failed to get bundle info", e))?;
         let bundle_response = response.into_inner();
         
         if bundle_response.success {
@@ -272,7 +338,8 @@ impl QuiltClient {
             offset: 0,
         };
         
-        let response = self.client.list_bundles(request).await?;
+        let response = self.client.list_bundles(request).await
+            .map_err(|e| rpc_error("Failed to list bundles", e))?;
         let list_response = response.into_inner();
         
         Ok(list_response.bundles)
@@ -285,11 +352,12 @@ impl QuiltClient {
             force,
         };
         
-        let response = self.client.delete_bundle(request).await?;
+        let response = self.client.delete_bundle(request).await
+            .map_err(|e| rpc_error("Failed to delete bundle", e))?;
         let delete_response = response.into_inner();
         
         if delete_response.success {
-            print_status("Deleted", &format!("Bundle {} removed", bundle_id));
+            tracing::info!(status = "Deleted", "Bundle {} removed", bundle_id);
             Ok(())
         } else {
             Err(anyhow!("Failed to delete bundle: {}", delete_response.error_message))
@@ -307,7 +375,8 @@ impl QuiltClient {
             check_dependencies: true,
         };
         
-        let response = self.client.validate_bundle(request).await?;
+        let response = self.client.validate_bundle(request).await
+            .map_err(|e| rpc_error("Failed to validate bundle", e))?;
         let validate_response = response.into_inner();
         
         if validate_response.success {
@@ -319,8 +388,192 @@ impl QuiltClient {
     }
 }
 
-/// Calculate blake3 hash of data
-fn calculate_blake3_hash(data: &[u8]) -> Result<String> {
-    let hash = blake3::hash(data);
-    Ok(hash.to_hex().to_string())
-} 
\ No newline at end of file
+/// Metadata field carrying the upload's digest under a non-blake3 `hash_algorithm`,
+/// e.g. `sha256_hash`. Blake3 already travels in `blake3_hash`.
+fn algorithm_digest_field(hash_algorithm: HashAlgorithm, data: &[u8]) -> Option<(String, String)> {
+    match hash_algorithm {
+        HashAlgorithm::Blake3 => None,
+        _ => Some((format!("{}_hash", hash_algorithm.as_str()), hash_algorithm.digest(data))),
+    }
+}
+
+/// Error for a failed RPC. A response that does not decode, or an RPC the
+/// daemon does not know, means its `quilt.proto` differs from the one this
+/// arc was built against, so that is reported instead of the raw status.
+fn rpc_error(context: &str, status: tonic::Status) -> anyhow::Error {
+    let protocol_mismatch = match status.code() {
+        // tonic reports prost decode failures as Internal
+        tonic::Code::Internal => status.message().contains("failed to decode Protobuf message"),
+        tonic::Code::Unimplemented => true,
+        _ => false,
+    };
+    
+    if protocol_mismatch {
+        anyhow!(
+            "{}: Quilt daemon protocol version appears incompatible with this arc build ({}). \
+             Upgrade arc or the daemon so both use the same quilt.proto.",
+            context, status.message()
+        )
+    } else {
+        anyhow!("{}: {}", context, status)
+    }
+}
+
+/// Expand `~`, resolve relative paths, and verify the result is a Unix socket
+pub fn resolve_socket_path(socket_path: &str) -> Result<PathBuf> {
+    let expanded = match socket_path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = std::env::var("HOME")
+                .map_err(|_| anyhow!("Cannot expand '~' in socket path: HOME is not set"))?;
+            PathBuf::from(home).join(rest)
+        }
+        None => PathBuf::from(socket_path),
+    };
+    
+    let resolved = if expanded.is_relative() {
+        std::env::current_dir()?.join(expanded)
+    } else {
+        expanded
+    };
+    
+    let metadata = std::fs::metadata(&resolved).map_err(|e| match e.kind() {
+        std::io::ErrorKind::PermissionDenied => anyhow!(
+            "Permission denied reading Quilt daemon socket {}: check that your user can access its directory",
+            resolved.display()
+        ),
+        _ => anyhow!("Quilt daemon socket not found: {}", resolved.display()),
+    })?;
+    
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if !metadata.file_type().is_socket() {
+            return Err(anyhow!("Path exists but is not a Unix socket: {}", resolved.display()));
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = metadata;
+    
+    Ok(resolved)
+}
+
+/// Open and immediately close a connection to the socket, so a permission
+/// problem is reported with advice instead of as a raw transport error
+async fn probe_socket(socket_path: &Path) -> Result<()> {
+    match tokio::net::UnixStream::connect(socket_path).await {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            let (owner, group) = socket_owner(socket_path);
+            Err(anyhow!(
+                "Permission denied connecting to Quilt daemon socket {} ({}). \
+                 Join the '{}' group (e.g. `sudo usermod -aG {} $USER`, then log in again) \
+                 or ask an administrator to check the socket permissions.",
+                socket_path.display(),
+                owner,
+                group,
+                group
+            ))
+        }
+        Err(e) => Err(anyhow!("Failed to connect to Quilt daemon at {}: {}", socket_path.display(), e)),
+    }
+}
+
+/// Describe the socket's owner and mode, and name the group that grants
+/// access to it (`quilt` when it cannot be determined or is root)
+fn socket_owner(socket_path: &Path) -> (String, String) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(metadata) = std::fs::metadata(socket_path) {
+            let user = lookup_id_name("/etc/passwd", metadata.uid()).unwrap_or_else(|| metadata.uid().to_string());
+            let group = lookup_id_name("/etc/group", metadata.gid());
+            let description = format!(
+                "owner {}:{}, mode {:o}",
+                user,
+                group.clone().unwrap_or_else(|| metadata.gid().to_string()),
+                metadata.mode() & 0o777
+            );
+            // Joining root is never the right advice
+            let access_group = group.filter(|g| g != "root").unwrap_or_else(|| "quilt".to_string());
+            return (description, access_group);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = socket_path;
+    
+    ("owner unknown".to_string(), "quilt".to_string())
+}
+
+/// Find the name for a numeric id in a passwd- or group-style file
+#[cfg(unix)]
+fn lookup_id_name(file: &str, id: u32) -> Option<String> {
+    let content = std::fs::read_to_string(file).ok()?;
+    content.lines().find_map(|line| {
+        let mut fields = line.split(':');
+        let name = fields.next()?;
+        let entry_id = fields.nth(1)?.parse::<u32>().ok()?;
+        (entry_id == id).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_uploads_carry_a_named_sha256_digest() {
+        let (name, digest) = algorithm_digest_field(HashAlgorithm::Sha256, b"abc").unwrap();
+
+        assert_eq!(name, "sha256_hash");
+        assert_eq!(digest, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(algorithm_digest_field(HashAlgorithm::Blake3, b"abc"), None);
+    }
+
+    #[test]
+    fn sockets_resolve_to_their_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("quilt.sock");
+        let _listener = std::os::unix::net::UnixListener::bind(&socket).unwrap();
+
+        assert_eq!(resolve_socket_path(&socket.display().to_string()).unwrap(), socket);
+    }
+
+    #[test]
+    fn tilde_expands_to_the_home_directory() {
+        let home = std::env::var("HOME").unwrap();
+
+        let error = resolve_socket_path("~/.arc-test-missing/quilt.sock").unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Quilt daemon socket not found: {}/.arc-test-missing/quilt.sock", home));
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_the_current_directory() {
+        let expected = std::env::current_dir().unwrap().join("arc-test-missing/quilt.sock");
+
+        let error = resolve_socket_path("arc-test-missing/quilt.sock").unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Quilt daemon socket not found: {}", expected.display()));
+    }
+
+    #[test]
+    fn missing_sockets_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("quilt.sock");
+
+        let error = resolve_socket_path(&socket.display().to_string()).unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Quilt daemon socket not found: {}", socket.display()));
+    }
+
+    #[test]
+    fn regular_files_are_not_sockets() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("quilt.sock");
+        std::fs::write(&file, "").unwrap();
+
+        let error = resolve_socket_path(&file.display().to_string()).unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Path exists but is not a Unix socket: {}", file.display()));
+    }
+}
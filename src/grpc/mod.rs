@@ -1,4 +1,4 @@
 pub mod client;
  
 // Re-export for easier usage
-pub use client::{QuiltClient, UploadProgress, UploadResult}; 
\ No newline at end of file
+pub use client::{QuiltClient, UploadProgress, UploadResult, DEFAULT_QUILT_SOCKET}; 
\ No newline at end of file
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use clap::{Arg, ArgAction, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::Path;
 
 pub mod bundle;
 pub mod cli;
@@ -7,7 +8,8 @@ pub mod compiler;
 pub mod config;
 pub mod grpc;
 
-use crate::cli::{handle_build_command, handle_check_command, handle_new_command, handle_upload_command};
+use crate::cli::{handle_build_command, handle_check_command, handle_config_command, handle_diff_command, handle_doctor_command, handle_explain_command, handle_info_command, handle_manifest_command, handle_merge_command, handle_new_command, handle_package_command, handle_upgrade_command, handle_upload_command, handle_verify_command, init_logging, ErrorFormat};
+use crate::grpc::DEFAULT_QUILT_SOCKET;
 
 fn cli() -> Command {
     Command::new("arc")
@@ -15,6 +17,7 @@ fn cli() -> Command {
         .version("0.1.0")
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .arg(Arg::new("log-file").long("log-file").value_name("PATH").global(true).help("Also write all log output to PATH, without colors"))
         .subcommand(
             Command::new("new")
                 .about("Create a new Aria project")
@@ -24,34 +27,182 @@ fn cli() -> Command {
         .subcommand(
             Command::new("build")
                 .about("Build an Aria project into a .aria bundle")
-                .arg(Arg::new("input").default_value(".").help("Input directory or file"))
+                .arg(Arg::new("input").num_args(1..).default_value(".").help("Input directories or files"))
                 .arg(Arg::new("output").short('o').long("output").help("Output file path"))
+                .arg(Arg::new("output-template").long("output-template").value_name("TEMPLATE").conflicts_with("output").help("Output path template with {name}, {version} and {hash} placeholders; {hash} names the bundle after its content hash"))
                 .arg(Arg::new("watch").short('w').long("watch").action(ArgAction::SetTrue).help("Watch for file changes"))
-                .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::SetTrue).help("Enable verbose output"))
+                .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::Count).help("Enable verbose output (repeat for more detail)"))
+                .arg(Arg::new("keep-going").long("keep-going").action(ArgAction::SetTrue).help("Keep compiling after a file fails and report all failures"))
+                .arg(Arg::new("profile").long("profile").value_name("NAME").help("Apply a [profile.<name>] section from aria.toml"))
+                .arg(Arg::new("config").long("config").value_name("PATH").help("Load this config file instead of searching upward from the first input for aria.toml"))
+                .arg(Arg::new("target-dir").long("target-dir").value_name("DIR").help("Directory for intermediate build artifacts (env: ARC_TARGET_DIR)"))
+                .arg(Arg::new("frozen").long("frozen").visible_alias("locked").action(ArgAction::SetTrue).help("Require aria.toml to exist and set project.name, project.version, project.description, build.target, build.source_dirs and runtime.bun_version"))
+                .arg(Arg::new("error-format").long("error-format").value_name("FORMAT").value_parser(["human", "github", "json"]).default_value("human").help("How to report compilation errors"))
+                .arg(Arg::new("jobs").short('j').long("jobs").value_name("N").value_parser(clap::value_parser!(u64).range(1..)).help("Number of files to compile in parallel (default: number of CPUs)"))
+                .arg(Arg::new("no-compress").long("no-compress").action(ArgAction::SetTrue).help("Store bundle entries uncompressed for faster local builds"))
+                .arg(Arg::new("emit-js-dir").long("emit-js-dir").value_name("DIR").help("Also write each file's transpiled JavaScript under DIR, mirroring the source tree"))
+                .arg(Arg::new("emit-js-only").long("emit-js-only").action(ArgAction::SetTrue).requires("emit-js-dir").help("Write only the JavaScript from --emit-js-dir, not the bundle"))
+                .arg(Arg::new("include-js").long("include-js").action(ArgAction::SetTrue).help("Also compile .js, .jsx, .mjs and .cjs files found in input directories"))
+                .arg(Arg::new("metadata").long("metadata").value_name("KEY=VALUE").action(ArgAction::Append).help("Record a key-value in the bundle's build metadata; repeatable, overrides build.metadata"))
+                .arg(Arg::new("stdin").long("stdin").action(ArgAction::SetTrue).conflicts_with_all(["watch", "emit-js-dir"]).help("Build a single TypeScript source read from stdin; the input path is only used to find aria.toml"))
+                .arg(Arg::new("lint-unused-imports").long("lint-unused-imports").action(ArgAction::SetTrue).help("Warn about imports a source file never uses"))
+                .arg(Arg::new("check-deps").long("check-deps").action(ArgAction::SetTrue).help("Fail if an imported npm package is not installed under node_modules"))
+                .arg(Arg::new("no-validate").long("no-validate").action(ArgAction::SetTrue).help("Report undefined or ambiguous cross-references as warnings and build anyway; the runtime may reject the bundle"))
+                .arg(Arg::new("no-lock").long("no-lock").action(ArgAction::SetTrue).help("Don't lock the output directory; concurrent builds of the same output may then corrupt it"))
+                .arg(Arg::new("no-upload").long("no-upload").action(ArgAction::SetTrue).help("Don't upload the bundle even if deploy.auto_upload is set"))
+                .arg(Arg::new("only").long("only").value_name("NAMES").value_delimiter(',').action(ArgAction::Append).help("Keep only these comma-separated items and the tools, agents and steps they reference"))
+                .arg(Arg::new("compare-manifest").long("compare-manifest").value_name("BUNDLE").conflicts_with("watch").help("After building, compare the manifest with this bundle's and exit 1 if any item differs"))
+                .arg(Arg::new("since").long("since").value_name("REF").conflicts_with_all(["watch", "stdin"]).help("Only recompile files changed since this git ref and files importing them, reusing cached output for the rest"))
+                .arg(Arg::new("features").long("features").value_name("LIST").value_delimiter(',').action(ArgAction::Append).help("Enable these comma-separated features; tools and agents gated on other features are left out"))
         )
         .subcommand(
             Command::new("check")
                 .about("Check an Aria project for errors")
-                .arg(Arg::new("input").default_value(".").help("Input directory or file"))
-                .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::SetTrue).help("Enable verbose output"))
+                .arg(Arg::new("input").num_args(1..).default_value(".").help("Input directories or files"))
+                .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::Count).help("Enable verbose output (repeat for more detail)"))
+                .arg(Arg::new("keep-going").long("keep-going").action(ArgAction::SetTrue).help("Keep compiling after a file fails and report all failures"))
+                .arg(Arg::new("profile").long("profile").value_name("NAME").help("Apply a [profile.<name>] section from aria.toml"))
+                .arg(Arg::new("config").long("config").value_name("PATH").help("Load this config file instead of searching upward from the first input for aria.toml"))
+                .arg(Arg::new("frozen").long("frozen").visible_alias("locked").action(ArgAction::SetTrue).help("Require aria.toml to exist and set project.name, project.version, project.description, build.target, build.source_dirs and runtime.bun_version"))
+                .arg(Arg::new("error-format").long("error-format").value_name("FORMAT").value_parser(["human", "github", "json"]).default_value("human").help("How to report compilation errors"))
+                .arg(Arg::new("jobs").short('j').long("jobs").value_name("N").value_parser(clap::value_parser!(u64).range(1..)).help("Number of files to compile in parallel (default: number of CPUs)"))
+                .arg(Arg::new("only").long("only").value_name("NAMES").value_delimiter(',').action(ArgAction::Append).help("Keep only these comma-separated items and the tools, agents and steps they reference"))
+                .arg(Arg::new("since").long("since").value_name("REF").conflicts_with_all(["watch", "stdin"]).help("Only recompile files changed since this git ref and files importing them, reusing cached output for the rest"))
+                .arg(Arg::new("features").long("features").value_name("LIST").value_delimiter(',').action(ArgAction::Append).help("Enable these comma-separated features; tools and agents gated on other features are left out"))
+                .arg(Arg::new("lint-unused-imports").long("lint-unused-imports").action(ArgAction::SetTrue).help("Warn about imports a source file never uses"))
+                .arg(Arg::new("include-js").long("include-js").action(ArgAction::SetTrue).help("Also compile .js, .jsx, .mjs and .cjs files found in input directories"))
+                .arg(Arg::new("dump-ast").long("dump-ast").action(ArgAction::SetTrue).hide(true).help("Print each source file's parsed AST to stderr before extracting decorators"))
+                .arg(Arg::new("max-warnings").long("max-warnings").value_name("N").value_parser(clap::value_parser!(u64)).conflicts_with("watch").help("Fail if more than N warnings are reported"))
+                .arg(Arg::new("watch").short('w').long("watch").action(ArgAction::SetTrue).help("Re-check whenever a source file changes"))
+                .arg(Arg::new("all").long("all").action(ArgAction::SetTrue).requires("watch").help("In watch mode, re-print warnings for unchanged files too"))
+                .arg(Arg::new("filter-tag").long("filter-tag").value_name("TAG").help("List the tools and agents tagged with TAG"))
+                .arg(Arg::new("emit-items").long("emit-items").action(ArgAction::SetTrue).conflicts_with("watch").help("Print the items extracted from each source file as JSON before checking"))
+                .arg(Arg::new("stdin").long("stdin").action(ArgAction::SetTrue).conflicts_with("watch").help("Check a single TypeScript source read from stdin; the input path is only used to find aria.toml"))
+                .arg(Arg::new("list-files").long("list-files").action(ArgAction::SetTrue).conflicts_with_all(["watch", "emit-items"]).help("Print the source files that would be checked, then exit without parsing them"))
+                .arg(Arg::new("json").long("json").action(ArgAction::SetTrue).requires("list-files").help("With --list-files, print the paths as a JSON array"))
+                .arg(Arg::new("strict").long("strict").action(ArgAction::SetTrue).help("Fail on items without a description and agents without tools"))
+        )
+        .subcommand(
+            Command::new("manifest")
+                .about("Compile a project and print its validated manifest JSON to stdout")
+                .arg(Arg::new("input").num_args(1..).default_value(".").help("Input directories or files"))
+                .arg(Arg::new("verbose").short('v').long("verbose").action(ArgAction::Count).help("Enable verbose output (repeat for more detail)"))
+                .arg(Arg::new("keep-going").long("keep-going").action(ArgAction::SetTrue).help("Keep compiling after a file fails and report all failures"))
+                .arg(Arg::new("profile").long("profile").value_name("NAME").help("Apply a [profile.<name>] section from aria.toml"))
         )
         .subcommand(
             Command::new("upload")
                 .about("Upload an Aria bundle to Quilt daemon via gRPC")
                 .arg(Arg::new("bundle").required(true).help("Path to .aria bundle file"))
-                .arg(Arg::new("socket").short('s').long("socket").help("Unix socket path to Quilt daemon (default: /run/quilt/api.sock)"))
+                .arg(Arg::new("socket").short('s').long("socket").help(format!("Unix socket path or http:// endpoint of the Quilt daemon (env: ARC_QUILT_SOCKET, config: deploy.socket, deploy.endpoint or runtime.quilt_socket, default: {})", DEFAULT_QUILT_SOCKET)))
+                .arg(Arg::new("no-test").long("no-test").action(ArgAction::SetTrue).help("Skip the connection test before uploading"))
+                .arg(Arg::new("grpc-compression").long("grpc-compression").value_name("ENCODING").value_parser(["none", "gzip"]).default_value("none").help("Compress the upload stream on the wire (zstd is not offered: tonic 0.10 only supports gzip)"))
+                .arg(Arg::new("max-rate").long("max-rate").value_name("MB/s").value_parser(clap::value_parser!(f64)).help("Limit the average upload rate, in megabytes per second"))
+                .arg(Arg::new("receipt").long("receipt").value_name("PATH").help("Where to write the upload receipt (default: <BUNDLE>.receipt.json beside the bundle)"))
+                .arg(Arg::new("skip-existing").long("skip-existing").action(ArgAction::SetTrue).help("Skip the upload if the receipt shows this bundle was already uploaded to the same daemon"))
+                .arg(Arg::new("profile").long("profile").value_name("NAME").help("Apply a [profile.<name>] section from aria.toml"))
+        )
+        .subcommand(
+            Command::new("package")
+                .about("Package a manifest and pre-transpiled JavaScript into a .aria bundle")
+                .arg(Arg::new("manifest").short('m').long("manifest").required(true).help("Path to manifest.json"))
+                .arg(Arg::new("impl-dir").long("impl-dir").required(true).help("Directory of compiled .js implementations"))
+                .arg(Arg::new("output").short('o').long("output").help("Output file path (default: dist/<name>.aria)"))
+        )
+        .subcommand(
+            Command::new("merge")
+                .about("Combine several .aria bundles into one")
+                .arg(Arg::new("bundles").required(true).num_args(2..).help("Paths to the .aria bundles; the first supplies the merged name and version"))
+                .arg(Arg::new("output").short('o').long("output").help("Output file path (default: dist/<name>.aria)"))
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare two .aria bundles (exits nonzero if they differ)")
+                .arg(Arg::new("old").required(true).help("Path to the baseline .aria bundle"))
+                .arg(Arg::new("new").required(true).help("Path to the new .aria bundle"))
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check a bundle's build hash and ed25519 signature (exits nonzero on failure)")
+                .arg(Arg::new("bundle").required(true).help("Path to the .aria bundle"))
+                .arg(Arg::new("public-key").long("public-key").value_name("PATH").required(true).help("Signer's ed25519 public key: 32 raw bytes or 64 hex digits"))
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Inspect project configuration")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("show")
+                        .about("Print the effective configuration after extends and profiles are applied")
+                        .arg(Arg::new("input").default_value(".").help("Project directory"))
+                        .arg(Arg::new("profile").long("profile").value_name("NAME").help("Apply a [profile.<name>] section from aria.toml"))
+                        .arg(Arg::new("json").long("json").action(ArgAction::SetTrue).help("Print as JSON instead of TOML"))
+                )
+        )
+        .subcommand(
+            Command::new("upgrade")
+                .about("Migrate aria.toml to the current configuration schema")
+                .arg(Arg::new("input").default_value(".").help("Project directory"))
+                .arg(Arg::new("dry-run").long("dry-run").action(ArgAction::SetTrue).help("Print the changes without writing the file"))
         )
+        .subcommand(
+            Command::new("explain")
+                .about("Describe a diagnostic code such as ARC0001")
+                .arg(Arg::new("code").required(true).help("Diagnostic code"))
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Print compiler and build environment details")
+                .arg(Arg::new("json").long("json").action(ArgAction::SetTrue).help("Emit the details as JSON"))
+        )
+        .subcommand(
+            Command::new("doctor")
+                .about("Check that the tools and services arc relies on are available")
+                .arg(Arg::new("input").default_value(".").help("Project directory"))
+                .arg(Arg::new("socket").short('s').long("socket").help("Unix socket path or http:// endpoint of the Quilt daemon (env: ARC_QUILT_SOCKET, config: deploy.socket, deploy.endpoint or runtime.quilt_socket)"))
+        )
+}
+
+/// Whether `--error-format` asks for errors in a machine-readable form on stdout
+fn machine_error_format(matches: &ArgMatches) -> bool {
+    ErrorFormat::from_arg(matches.get_one::<String>("error-format")) != ErrorFormat::Human
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let matches = cli().get_matches();
+    
+    // Install the CLI log renderer before running any command. `manifest`,
+    // `check --list-files`, `check --emit-items` and a non-human
+    // `--error-format` reserve stdout for their output, and `--stdin` runs
+    // sit in pipelines, so their logs go to stderr. `--log-file`
+    // additionally copies them to a file.
+    let verbosity = matches.subcommand()
+        .and_then(|(_, sub_matches)| sub_matches.try_get_one::<u8>("verbose").ok().flatten().copied())
+        .unwrap_or(0);
+    let logs_to_stderr = match matches.subcommand() {
+        Some(("manifest", _)) => true,
+        Some(("check", sub_matches)) => sub_matches.get_flag("list-files") || sub_matches.get_flag("emit-items") || sub_matches.get_flag("stdin") || machine_error_format(sub_matches),
+        Some(("build", sub_matches)) => sub_matches.get_flag("stdin") || machine_error_format(sub_matches),
+        _ => false,
+    };
+    init_logging(verbosity, logs_to_stderr, matches.get_one::<String>("log-file").map(Path::new))?;
 
     match matches.subcommand() {
         Some(("new", sub_matches)) => handle_new_command(sub_matches).await?,
         Some(("build", sub_matches)) => handle_build_command(sub_matches).await?,
         Some(("check", sub_matches)) => handle_check_command(sub_matches).await?,
+        Some(("manifest", sub_matches)) => handle_manifest_command(sub_matches).await?,
         Some(("upload", sub_matches)) => handle_upload_command(sub_matches).await?,
+        Some(("package", sub_matches)) => handle_package_command(sub_matches).await?,
+        Some(("merge", sub_matches)) => handle_merge_command(sub_matches).await?,
+        Some(("diff", sub_matches)) => handle_diff_command(sub_matches).await?,
+        Some(("verify", sub_matches)) => handle_verify_command(sub_matches).await?,
+        Some(("config", sub_matches)) => handle_config_command(sub_matches).await?,
+        Some(("upgrade", sub_matches)) => handle_upgrade_command(sub_matches).await?,
+        Some(("explain", sub_matches)) => handle_explain_command(sub_matches).await?,
+        Some(("info", sub_matches)) => handle_info_command(sub_matches).await?,
+        Some(("doctor", sub_matches)) => handle_doctor_command(sub_matches).await?,
         _ => unreachable!(),
     }
 
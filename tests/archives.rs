@@ -0,0 +1,92 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+use std::io::Write;
+use std::path::Path;
+
+fn write_zip(path: &Path, files: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    for (name, content) in files {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+fn write_tar_gz(path: &Path, files: &[(&str, &str)]) {
+    let encoder = flate2::write::GzEncoder::new(std::fs::File::create(path).unwrap(), flate2::Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append_data(&mut header, name, content.as_bytes()).unwrap();
+    }
+    tar.into_inner().unwrap().finish().unwrap();
+}
+
+fn tool_names(project: &Project, input: &str) -> Vec<String> {
+    let output = project.arc().args(["manifest", input]).assert().success().get_output().stdout.clone();
+    let manifest: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    manifest["tools"].as_array().unwrap().iter().map(|tool| tool["name"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn compiles_sources_from_a_zip() {
+    let project = Project::new();
+    write_zip(&project.join("src.zip"), &[
+        ("src/greet.ts", &common::tool_source("greet")),
+        ("src/nested/wave.ts", &common::tool_source("wave")),
+    ]);
+
+    let mut names = tool_names(&project, "src.zip");
+    names.sort();
+    assert_eq!(names, ["greet", "wave"]);
+}
+
+#[test]
+fn compiles_sources_from_a_tarball() {
+    let project = Project::new();
+    write_tar_gz(&project.join("src.tgz"), &[("src/greet.ts", &common::tool_source("greet"))]);
+
+    project.arc().args(["build", "src.tgz", "-o", "out.aria"]).assert().success();
+
+    assert_eq!(common::bundle_manifest(&project.join("out.aria"))["tools"][0]["name"], "greet");
+}
+
+#[test]
+fn diagnostics_name_sources_inside_the_archive() {
+    let project = Project::new();
+    write_zip(&project.join("src.zip"), &[("src/broken.ts", "export function a() {\n  return 1 +;\n}\n")]);
+
+    let output = project.arc().args(["check", "src.zip", "--error-format", "json"])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output).expect("stdout is JSON");
+    assert!(diagnostics[0]["file"].as_str().unwrap().ends_with("src.zip/src/broken.ts"), "{diagnostics}");
+    assert_eq!(diagnostics[0]["line"], 2);
+}
+
+#[test]
+fn zip_entries_outside_the_archive_are_rejected() {
+    let project = Project::new();
+    std::fs::create_dir(project.join("work")).unwrap();
+    write_zip(&project.join("work/src.zip"), &[
+        ("src/greet.ts", &common::tool_source("greet")),
+        ("../../escaped.ts", &common::tool_source("escaped")),
+    ]);
+
+    project.arc().current_dir(project.join("work")).args(["check", "src.zip"])
+        .assert()
+        .failure()
+        .stderr(contains("entry '../../escaped.ts' escapes the archive").and(contains("src.zip")));
+
+    assert!(!project.join("escaped.ts").exists());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::{tool_source, Project};
+use fs4::FileExt;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+fn arc_build(project: &Project) -> Command {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin("arc"));
+    cmd.current_dir(project.path())
+        .args(["build", ".", "-o", "dist/app.aria"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
+
+#[test]
+fn build_waits_for_the_output_lock() {
+    let project = Project::new().file("src/tools.ts", &tool_source("greet"));
+    std::fs::create_dir_all(project.join("dist")).unwrap();
+    let held = std::fs::File::create(project.join("dist/.arc-build.lock")).unwrap();
+    held.lock_exclusive().unwrap();
+
+    let mut build = arc_build(&project).spawn().unwrap();
+    std::thread::sleep(Duration::from_secs(2));
+    assert!(build.try_wait().unwrap().is_none(), "build finished while another held the lock");
+    assert!(!project.join("dist/app.aria").exists());
+
+    held.unlock().unwrap();
+    let output = build.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Another build is in progress")
+        || String::from_utf8_lossy(&output.stdout).contains("Another build is in progress"));
+    assert!(project.join("dist/app.aria").exists());
+}
+
+#[test]
+fn concurrent_builds_of_one_output_both_succeed() {
+    let project = Project::new().file("src/tools.ts", &tool_source("greet"));
+
+    let first = arc_build(&project).spawn().unwrap();
+    let second = arc_build(&project).spawn().unwrap();
+    assert!(first.wait_with_output().unwrap().status.success());
+    assert!(second.wait_with_output().unwrap().status.success());
+
+    // The bundle left behind is whole: it loads and matches itself
+    project.arc().args(["diff", "dist/app.aria", "dist/app.aria"]).assert().success();
+}
+
+#[test]
+fn watch_rebuilds_take_the_output_lock() {
+    let project = Project::new().file("src/tools.ts", &tool_source("greet"));
+    std::fs::create_dir_all(project.join("dist")).unwrap();
+    let held = std::fs::File::create(project.join("dist/.arc-build.lock")).unwrap();
+    held.lock_exclusive().unwrap();
+
+    let mut watch = arc_build(&project).arg("--watch").spawn().unwrap();
+    std::thread::sleep(Duration::from_secs(2));
+    assert!(!project.join("dist/app.aria").exists(), "watch rebuilt while another build held the lock");
+
+    held.unlock().unwrap();
+    let built = (0..50).any(|_| {
+        std::thread::sleep(Duration::from_millis(200));
+        project.join("dist/app.aria").exists()
+    });
+    watch.kill().unwrap();
+    watch.wait().unwrap();
+    assert!(built, "watch did not build once the lock was released");
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const TOOL: &str = r#"
+import { tool } from "@aria/sdk";
+import { readFile } from "fs";
+import { join } from "node:path";
+import type { Options } from "typings-only";
+import chunk from "lodash/chunk";
+
+@tool({ description: "chunks" })
+export function split(input: string): string { return join(input, String(chunk)); }
+"#;
+
+#[test]
+fn missing_packages_fail_the_build() {
+    let project = Project::new()
+        .file("src/tools.ts", TOOL)
+        .file("node_modules/@aria/sdk/package.json", "{}");
+
+    project.arc().args(["build", ".", "-o", "out.aria", "--check-deps"])
+        .assert()
+        .failure()
+        .stderr(contains("1 imported package(s) are not installed, run `bun install`")
+            .and(contains("lodash (imported by "))
+            .and(contains("src/tools.ts"))
+            .and(contains("typings-only").not())
+            .and(contains("node:path").not()));
+
+    assert!(!project.join("out.aria").exists());
+}
+
+#[test]
+fn installed_packages_pass() {
+    let project = Project::new()
+        .file("src/tools.ts", TOOL)
+        .file("node_modules/@aria/sdk/package.json", "{}")
+        .file("node_modules/lodash/package.json", "{}");
+
+    project.arc().args(["build", ".", "-o", "out.aria", "--check-deps"]).assert().success();
+}
+
+#[test]
+fn packages_are_not_checked_by_default() {
+    let project = Project::new().file("src/tools.ts", TOOL);
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+}
@@ -0,0 +1,107 @@
+//! Helpers shared by the integration tests: a throwaway project directory
+//! and a command running the `arc` binary inside it.
+
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// A project in a temporary directory, removed when dropped
+pub struct Project {
+    dir: TempDir,
+}
+
+impl Project {
+    pub fn new() -> Self {
+        Self { dir: TempDir::new().expect("create temp dir") }
+    }
+
+    /// Write `content` to `path` relative to the project, creating parent directories
+    pub fn file(self, path: &str, content: &str) -> Self {
+        self.write(path, content);
+        self
+    }
+
+    /// Like `file`, for a project that is already set up
+    pub fn write(&self, path: &str, content: &str) {
+        let path = self.path().join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("create parent dir");
+        std::fs::write(path, content).expect("write project file");
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    pub fn join(&self, path: &str) -> PathBuf {
+        self.path().join(path)
+    }
+
+    /// `arc` with the project as its working directory
+    pub fn arc(&self) -> Command {
+        let mut cmd = Command::cargo_bin("arc").expect("arc binary");
+        cmd.current_dir(self.path());
+        cmd
+    }
+
+    /// Run `arc manifest` on the project and parse its output
+    pub fn manifest(&self) -> serde_json::Value {
+        let output = self.arc().arg("manifest").arg(".").assert().success().get_output().stdout.clone();
+        serde_json::from_slice(&output).expect("manifest output is JSON")
+    }
+}
+
+/// Run git in the project, failing the test if it fails
+pub fn git(project: &Project, args: &[&str]) {
+    let status = std::process::Command::new("git").args(args).current_dir(project.path()).status().expect("run git");
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// A `@tool` function named `name`
+pub fn tool_source(name: &str) -> String {
+    format!(
+        "import {{ tool }} from \"@aria/sdk\";\n\n\
+         @tool({{ description: \"{name} tool\" }})\n\
+         export function {name}(input: string): string {{\n    return input;\n}}\n"
+    )
+}
+
+/// The `bundle.zip` packed inside the `.aria` file at `bundle`
+fn inner_archive(bundle: &Path) -> zip::ZipArchive<Cursor<Vec<u8>>> {
+    let mut outer = zip::ZipArchive::new(std::fs::File::open(bundle).expect("open bundle")).expect("bundle is a zip");
+    let mut inner_data = Vec::new();
+    outer.by_name("bundle.zip").expect("bundle.zip entry").read_to_end(&mut inner_data).expect("read bundle.zip");
+    zip::ZipArchive::new(Cursor::new(inner_data)).expect("bundle.zip is a zip")
+}
+
+/// Names of the entries in the `bundle.zip` inside the `.aria` file at `bundle`
+pub fn bundle_entries(bundle: &Path) -> Vec<String> {
+    inner_archive(bundle).file_names().map(String::from).collect()
+}
+
+/// Read `name` from the `bundle.zip` packed inside the `.aria` file at `bundle`
+pub fn bundle_file(bundle: &Path, name: &str) -> String {
+    String::from_utf8(bundle_bytes(bundle, name)).expect("bundle entry is UTF-8")
+}
+
+/// Like `bundle_file`, for entries that are not text
+pub fn bundle_bytes(bundle: &Path, name: &str) -> Vec<u8> {
+    let mut content = Vec::new();
+    inner_archive(bundle).by_name(name).expect("bundle entry").read_to_end(&mut content).expect("read bundle entry");
+    content
+}
+
+/// Read `name` from the outer `.aria` archive at `bundle`, such as `metadata/build.json`
+pub fn outer_file(bundle: &Path, name: &str) -> String {
+    let mut outer = zip::ZipArchive::new(std::fs::File::open(bundle).expect("open bundle")).expect("bundle is a zip");
+    let mut content = String::new();
+    outer.by_name(name).expect("outer entry").read_to_string(&mut content).expect("read outer entry");
+    content
+}
+
+/// The manifest of the `.aria` file at `bundle`
+pub fn bundle_manifest(bundle: &Path) -> serde_json::Value {
+    serde_json::from_str(&bundle_file(bundle, "manifest.json")).expect("manifest is JSON")
+}
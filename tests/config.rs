@@ -0,0 +1,192 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const BASE_CONFIG: &str = r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+exclude = ["legacy.ts"]
+
+[runtime]
+bun_version = "1.1"
+"#;
+
+fn show_config(project: &Project, profile: Option<&str>) -> serde_json::Value {
+    let mut cmd = project.arc();
+    cmd.args(["config", "show", ".", "--json"]);
+    if let Some(profile) = profile {
+        cmd.args(["--profile", profile]);
+    }
+    let output = cmd.assert().success().get_output().stdout.clone();
+    serde_json::from_slice(&output).expect("config is JSON")
+}
+
+#[test]
+fn profiles_can_set_fields_back_to_their_defaults() {
+    let project = Project::new().file("aria.toml", &format!("{}{}", BASE_CONFIG, r#"
+[profile.ci.build]
+exclude = []
+
+[profile.ci.runtime]
+bun_version = "latest"
+"#));
+
+    let config = show_config(&project, Some("ci"));
+
+    assert_eq!(config["build"]["exclude"], serde_json::json!([]));
+    assert_eq!(config["runtime"]["bun_version"], "latest");
+    assert_eq!(config["project"]["name"], "app");
+}
+
+#[test]
+fn extending_files_can_set_fields_back_to_their_defaults() {
+    let project = Project::new()
+        .file("base.toml", BASE_CONFIG)
+        .file("aria.toml", "extends = \"base.toml\"\n\n[project]\nname = \"aria-project\"\n");
+
+    let config = show_config(&project, None);
+
+    assert_eq!(config["project"]["name"], "aria-project");
+    assert_eq!(config["project"]["version"], "1.0.0");
+}
+
+#[test]
+fn extending_files_inherit_the_fields_they_do_not_set() {
+    let project = Project::new()
+        .file("base.toml", BASE_CONFIG)
+        .file("aria.toml", "extends = \"base.toml\"\n\n[runtime]\nbun_version = \"1.2\"\n");
+
+    let config = show_config(&project, None);
+
+    assert_eq!(config["runtime"]["bun_version"], "1.2");
+    assert_eq!(config["project"]["name"], "app");
+    assert_eq!(config["build"]["exclude"], serde_json::json!(["legacy.ts"]));
+}
+
+#[test]
+fn extends_chains_apply_every_file_in_order() {
+    let project = Project::new()
+        .file("configs/root.toml", BASE_CONFIG)
+        .file("configs/shared.toml", "extends = \"root.toml\"\n\n[project]\nversion = \"2.0.0\"\n\n[runtime]\nbun_version = \"1.2\"\n")
+        .file("aria.toml", "extends = \"configs/shared.toml\"\n\n[runtime]\nbun_version = \"1.3\"\n");
+
+    let config = show_config(&project, None);
+
+    assert_eq!(config["project"]["name"], "app");
+    assert_eq!(config["project"]["version"], "2.0.0");
+    assert_eq!(config["runtime"]["bun_version"], "1.3");
+}
+
+#[test]
+fn extends_cycles_are_reported() {
+    let project = Project::new()
+        .file("a.toml", "extends = \"b.toml\"\n")
+        .file("b.toml", "extends = \"aria.toml\"\n")
+        .file("aria.toml", &format!("extends = \"a.toml\"\n{}", BASE_CONFIG));
+
+    project.arc().args(["config", "show", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Cycle detected in config extends:")
+            .and(contains("aria.toml -> "))
+            .and(contains("/a.toml -> "))
+            .and(contains("/b.toml -> ")));
+}
+
+#[test]
+fn unknown_profiles_list_the_available_ones() {
+    let project = Project::new().file("aria.toml", &format!("{}{}", BASE_CONFIG, r#"
+[profile.ci.build]
+exclude = []
+
+[profile.staging.runtime]
+bun_version = "latest"
+"#));
+
+    project.arc().args(["config", "show", ".", "--profile", "prod"])
+        .assert()
+        .failure()
+        .stderr(contains("Unknown profile 'prod'. Available profiles:")
+            .and(contains("ci"))
+            .and(contains("staging")));
+}
+
+#[test]
+fn unknown_sections_in_profiles_are_rejected() {
+    let project = Project::new().file("aria.toml", &format!("{}{}", BASE_CONFIG, "\n[profile.ci.bulid]\nwatch = true\n"));
+
+    project.arc().args(["config", "show", ".", "--profile", "ci"])
+        .assert()
+        .failure()
+        .stderr(contains("profile 'ci'").and(contains("bulid")));
+}
+
+#[test]
+fn check_applies_the_profile() {
+    let project = Project::new()
+        .file("aria.toml", &format!("{}{}", BASE_CONFIG, "\n[profile.all.build]\nexclude = []\n"))
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/legacy.ts", &common::tool_source("legacy"));
+
+    project.arc().args(["check", "."]).assert().success().stdout(contains("Tools: 1"));
+    project.arc().args(["check", ".", "--profile", "all"]).assert().success().stdout(contains("Tools: 2"));
+}
+
+#[test]
+fn upload_applies_the_profile() {
+    let project = Project::new()
+        .file("aria.toml", &format!("{}{}", BASE_CONFIG, "\n[profile.staging.deploy]\nsocket = \"/tmp/staging.sock\"\n"));
+
+    project.arc().args(["upload", "missing.aria", "--profile", "staging"])
+        .env_remove("ARC_QUILT_SOCKET")
+        .assert()
+        .failure()
+        .stdout(contains("Quilt daemon: /tmp/staging.sock"));
+}
+
+#[test]
+fn plain_check_warns_about_an_invalid_config_and_continues() {
+    let project = Project::new()
+        .file("aria.toml", "[project\nname = \"broken\"\n")
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Ignoring").and(contains("aria.toml")))
+        .stdout(contains("Tools: 1"));
+    project.arc().args(["check", ".", "--config", "aria.toml"]).assert().failure();
+    project.arc().args(["check", ".", "--frozen"]).assert().failure();
+}
+
+#[test]
+fn config_flag_replaces_the_discovered_file() {
+    let project = Project::new()
+        .file("aria.toml", BASE_CONFIG)
+        .file("configs/all.toml", &BASE_CONFIG.replace("exclude = [\"legacy.ts\"]", "exclude = []"))
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/legacy.ts", &common::tool_source("legacy"));
+
+    project.arc().args(["check", "."]).assert().success().stdout(contains("Tools: 1"));
+    project.arc().args(["check", ".", "--config", "configs/all.toml"]).assert().success().stdout(contains("Tools: 2"));
+    project.arc().args(["build", ".", "-o", "out.aria", "--config", "configs/all.toml"]).assert().success().stdout(contains("Tools: 2"));
+}
+
+#[test]
+fn missing_config_flag_file_is_an_error() {
+    let project = Project::new()
+        .file("aria.toml", BASE_CONFIG)
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["check", ".", "--config", "missing.toml"])
+        .assert()
+        .failure()
+        .stderr(contains("missing.toml"));
+}
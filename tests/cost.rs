@@ -0,0 +1,37 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const PRICED: &str = r#"
+import { tool } from "@aria/sdk";
+@tool({ description: "a", cost: 0.5 }) export function flat(): void {}
+@tool({ description: "b", cost: { tokens: 100, usd: -1 } }) export function split(): void {}
+@tool({ description: "c", cost: -2 }) export function refund(): void {}
+@tool({ description: "d" }) export function free(): void {}
+"#;
+
+#[test]
+fn costs_are_recorded_as_given() {
+    let manifest = Project::new().file("src/tools.ts", PRICED).manifest();
+
+    let costs: serde_json::Map<String, serde_json::Value> = manifest["tools"].as_array().unwrap().iter()
+        .map(|tool| (tool["name"].as_str().unwrap().to_string(), tool.get("cost").cloned().unwrap_or_default()))
+        .collect();
+    assert_eq!(costs["flat"], 0.5);
+    assert_eq!(costs["split"], serde_json::json!({ "tokens": 100, "usd": -1 }));
+    assert_eq!(costs["refund"], -2);
+    assert_eq!(costs["free"], serde_json::Value::Null);
+}
+
+#[test]
+fn negative_costs_are_warnings() {
+    Project::new().file("src/tools.ts", PRICED).arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Tool 'split' has a negative cost (usd: -1)")
+            .and(contains("Tool 'refund' has a negative cost (-2)"))
+            .and(contains("'flat' has a negative cost").not())
+            .and(contains("negative cost: 2")));
+}
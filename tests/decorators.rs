@@ -0,0 +1,279 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+
+fn manifest(source: &str) -> serde_json::Value {
+    Project::new().file("src/items.ts", source).manifest()
+}
+
+#[test]
+fn second_options_argument_overrides_the_first() {
+    let manifest = manifest(r#"
+        import { agent, tool } from "@aria/sdk";
+
+        @tool({ name: "search", description: "finds", timeout: 1000 }, { timeout: 5000 })
+        export function find(query: string): string { return query; }
+
+        @agent({ name: "Finder", description: "a" }, { description: "finds things", tools: ["search"] }, { tools: ["ignored"] })
+        export class Finder {}
+    "#);
+
+    assert_eq!(manifest["tools"][0]["name"], "search");
+    assert_eq!(manifest["tools"][0]["description"], "finds");
+    assert_eq!(manifest["tools"][0]["timeout_ms"], 5000);
+    assert_eq!(manifest["agents"][0]["description"], "finds things");
+    assert_eq!(manifest["agents"][0]["tools"], serde_json::json!(["search"]));
+}
+
+#[test]
+fn spread_properties_are_ignored() {
+    let manifest = manifest(r#"
+        import { tool } from "@aria/sdk";
+
+        const extra = { timeout: 5000 };
+
+        @tool({ description: "finds" }, { ...extra, name: "lookup" })
+        export function find(query: string): string { return query; }
+    "#);
+
+    assert_eq!(manifest["tools"][0]["name"], "lookup");
+    assert_eq!(manifest["tools"][0]["description"], "finds");
+    assert!(manifest["tools"][0].get("timeout_ms").is_none(), "{}", manifest["tools"][0]);
+}
+
+#[test]
+fn default_exported_classes_are_extracted() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/helper.ts", r#"
+            import { agent } from "@aria/sdk";
+            @agent({ description: "helps", tools: ["greet"] })
+            export default class Helper {}
+        "#)
+        .file("src/crew.ts", r#"
+            import { team } from "@aria/sdk";
+            @team({ name: "Crew", description: "works", members: ["Helper"] })
+            export default class {}
+        "#);
+
+    let manifest = project.manifest();
+
+    assert_eq!(manifest["agents"][0]["name"], "Helper");
+    assert_eq!(manifest["teams"][0]["name"], "Crew");
+}
+
+#[test]
+fn anonymous_default_exported_classes_need_a_name() {
+    let project = Project::new().file("src/helper.ts", r#"
+        import { agent } from "@aria/sdk";
+        @agent({ description: "helps" })
+        export default class {}
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Anonymous default-exported @agent class needs a name"));
+}
+
+#[test]
+fn classes_with_two_kind_decorators_are_rejected() {
+    let project = Project::new().file("src/helper.ts", r#"
+        import { agent, team } from "@aria/sdk";
+        @agent({ name: "Helper", description: "helps" })
+        @team({ name: "Crew", description: "works", members: [] })
+        export class Helper {}
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Class 'Helper' has conflicting decorators @agent and @team"));
+}
+
+#[test]
+fn async_generator_and_string_named_methods_are_tools() {
+    let manifest = manifest(r#"
+        import { agent, tool } from "@aria/sdk";
+
+        @agent({ name: "Finder", description: "finds" })
+        export class Finder {
+            @tool({ description: "fetches" })
+            async fetch(url: string): Promise<string> { return url; }
+
+            @tool({ description: "streams" })
+            async *stream(query: string) { yield query; }
+
+            @tool({ description: "searches the web" })
+            "web-search"(query: string): string { return query; }
+        }
+    "#);
+
+    let mut names: Vec<&str> = manifest["tools"].as_array().unwrap().iter().map(|tool| tool["name"].as_str().unwrap()).collect();
+    names.sort();
+    assert_eq!(names, ["fetch", "stream", "web-search"]);
+    assert_eq!(manifest["agents"][0]["tools"], serde_json::json!(["fetch", "stream", "web-search"]));
+}
+
+#[test]
+fn accessors_and_computed_names_are_skipped_with_a_warning() {
+    let project = Project::new().file("src/finder.ts", r#"
+        import { agent, tool } from "@aria/sdk";
+
+        const key = "dynamic";
+
+        @agent({ name: "Finder", description: "finds" })
+        export class Finder {
+            @tool({ description: "reads" })
+            get current(): string { return ""; }
+
+            @tool({ description: "computed" })
+            [key](): void {}
+        }
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("@tool on getter 'current' is ignored: only methods can be tools"))
+        .stdout(contains("@tool on a method with a computed name is ignored"))
+        .stdout(contains("Tools: 0"));
+}
+
+/// A `search` tool whose decorator sets `props`
+fn tool_with(props: &str) -> String {
+    format!(r#"
+        import {{ tool }} from "@aria/sdk";
+
+        @tool({{ description: "finds", {props} }})
+        export function search(query: string): string {{ return query; }}
+    "#)
+}
+
+#[test]
+fn timeout_and_rate_limit_accept_positive_integers() {
+    let manifest = manifest(&tool_with("timeout: 30000, rateLimit: 4294967295"));
+
+    assert_eq!(manifest["tools"][0]["timeout_ms"], 30000);
+    assert_eq!(manifest["tools"][0]["rate_limit"], 4294967295u32);
+}
+
+#[test]
+fn timeout_and_rate_limit_reject_anything_but_positive_integers() {
+    let cases = [
+        ("timeout", "0"),
+        ("timeout", "-5"),
+        ("timeout", "1.5"),
+        ("rateLimit", "0"),
+        ("rateLimit", "-1"),
+        ("rateLimit", "2.5"),
+        ("rateLimit", "4294967296"),
+    ];
+    for (key, value) in cases {
+        let project = Project::new().file("src/search.ts", &tool_with(&format!("{}: {}", key, value)));
+
+        project.arc().args(["check", "."])
+            .assert()
+            .failure()
+            .stderr(contains(format!("Tool 'search' has invalid {} {}: must be a positive integer", key, value)));
+    }
+}
+
+/// A `Finder` agent whose decorator sets `props`
+fn agent_with(props: &str) -> String {
+    format!(r#"
+        import {{ agent }} from "@aria/sdk";
+
+        @agent({{ name: "Finder", description: "finds", {props} }})
+        export class Finder {{}}
+    "#)
+}
+
+#[test]
+fn agent_model_memory_and_streaming_are_extracted() {
+    let manifest = manifest(&agent_with(r#"model: "gpt-4o", memory: true, streaming: false"#));
+
+    assert_eq!(manifest["agents"][0]["model"], "gpt-4o");
+    assert_eq!(manifest["agents"][0]["memory"], true);
+    assert_eq!(manifest["agents"][0]["streaming"], false);
+}
+
+#[test]
+fn agent_options_of_the_wrong_type_are_rejected() {
+    let cases = [
+        (r#"memory: "yes""#, r#"memory "yes": must be a boolean"#),
+        ("streaming: 1", "streaming 1: must be a boolean"),
+        ("model: 4", "model 4: must be a string"),
+    ];
+    for (props, error) in cases {
+        let project = Project::new().file("src/finder.ts", &agent_with(props));
+
+        project.arc().args(["check", "."])
+            .assert()
+            .failure()
+            .stderr(contains(format!("Agent 'Finder' has invalid {}", error)));
+    }
+}
+
+#[test]
+fn template_literals_without_substitutions_are_read_as_strings() {
+    let manifest = manifest(r#"
+        import { tool } from "@aria/sdk";
+
+        @tool({ name: `search`, description: `finds things`, tags: [`web`, "search"] })
+        export function find(query: string): string { return query; }
+    "#);
+
+    assert_eq!(manifest["tools"][0]["name"], "search");
+    assert_eq!(manifest["tools"][0]["description"], "finds things");
+    assert_eq!(manifest["tools"][0]["tags"], serde_json::json!(["web", "search"]));
+}
+
+#[test]
+fn template_literals_with_substitutions_are_skipped_with_a_warning() {
+    let project = Project::new().file("src/search.ts", r#"
+        import { tool } from "@aria/sdk";
+
+        const site = "web";
+
+        @tool({ name: `${site}-search`, description: `finds on ${site}`, tags: [`${site}`, "search"] })
+        export function search(query: string): string { return query; }
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Template literal with substitutions in 'name' cannot be statically extracted"))
+        .stdout(contains("Template literal with substitutions in 'description' cannot be statically extracted"))
+        .stdout(contains("Template literal with substitutions in 'tags' cannot be statically extracted"));
+
+    let tool = &project.manifest()["tools"][0];
+    assert_eq!(tool["name"], "search");
+    assert_eq!(tool["description"], "");
+    assert_eq!(tool["tags"], serde_json::json!(["search"]));
+}
+
+#[test]
+fn tool_methods_outside_agents_are_reported() {
+    let project = Project::new().file("src/misplaced.ts", r#"
+        import { team, tool } from "@aria/sdk";
+
+        export class Helpers {
+            @tool({ description: "formats" })
+            format(text: string): string { return text; }
+        }
+
+        @team({ name: "Crew", description: "works", members: [] })
+        export class Crew {
+            @tool({ description: "plans" })
+            plan(goal: string): string { return goal; }
+        }
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Tool method 'format' is declared in class 'Helpers' which is not decorated with @agent"))
+        .stdout(contains("Tool method 'plan' is declared in class 'Crew' which is not decorated with @agent"));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+fn config(deploy: &str) -> String {
+    format!(r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+
+[runtime]
+bun_version = "1.1"
+
+[deploy]
+{deploy}
+"#)
+}
+
+fn project(deploy: &str) -> Project {
+    Project::new()
+        .file("aria.toml", &config(deploy))
+        .file("src/greet.ts", &common::tool_source("greet"))
+}
+
+#[test]
+fn auto_upload_uploads_after_the_build() {
+    let project = project("socket = \"/nonexistent/deploy.sock\"\nauto_upload = true");
+
+    project.arc().args(["build", ".", "-o", "out.aria"])
+        .env_remove("ARC_QUILT_SOCKET")
+        .assert()
+        .failure()
+        .stdout(contains("Uploading bundle: out.aria").and(contains("Quilt daemon: /nonexistent/deploy.sock")))
+        .stderr(contains("Quilt daemon socket not found: /nonexistent/deploy.sock"));
+
+    assert!(project.join("out.aria").exists());
+}
+
+#[test]
+fn no_upload_skips_auto_upload() {
+    let project = project("socket = \"/nonexistent/deploy.sock\"\nauto_upload = true");
+
+    project.arc().args(["build", ".", "-o", "out.aria", "--no-upload"])
+        .env_remove("ARC_QUILT_SOCKET")
+        .assert()
+        .success()
+        .stdout(contains("Uploading").not());
+}
+
+#[test]
+fn flag_and_environment_take_precedence_over_deploy_socket() {
+    let project = project("socket = \"/nonexistent/deploy.sock\"");
+
+    project.arc().args(["upload", "missing.aria", "--socket", "/nonexistent/flag.sock"])
+        .env("ARC_QUILT_SOCKET", "/nonexistent/env.sock")
+        .assert()
+        .failure()
+        .stdout(contains("Quilt daemon: /nonexistent/flag.sock"));
+    project.arc().args(["upload", "missing.aria"])
+        .env("ARC_QUILT_SOCKET", "/nonexistent/env.sock")
+        .assert()
+        .failure()
+        .stdout(contains("Quilt daemon: /nonexistent/env.sock"));
+    project.arc().args(["upload", "missing.aria"])
+        .env_remove("ARC_QUILT_SOCKET")
+        .assert()
+        .failure()
+        .stdout(contains("Quilt daemon: /nonexistent/deploy.sock"));
+}
+
+#[test]
+fn invalid_deploy_addresses_are_rejected() {
+    for (deploy, error) in [
+        ("socket = \"/run/quilt.sock\"\nendpoint = \"http://localhost:50051\"", "deploy.socket and deploy.endpoint cannot both be set"),
+        ("socket = \"\"", "deploy.socket cannot be empty"),
+        ("endpoint = \"https://localhost:50051\"", "Invalid deploy.endpoint 'https://localhost:50051': must be an http:// URL"),
+    ] {
+        project(deploy).arc().args(["build", ".", "-o", "out.aria"])
+            .assert()
+            .failure()
+            .stderr(contains(error));
+    }
+}
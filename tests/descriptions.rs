@@ -0,0 +1,73 @@
+mod common;
+
+use common::{bundle_manifest, Project};
+use predicates::prelude::*;
+use std::time::Duration;
+
+fn described_tool(description: &str) -> String {
+    format!(
+        "import {{ tool }} from \"@aria/sdk\";\n\n\
+         @tool({{ {description} }})\n\
+         export function search(query: string): string {{\n    return query;\n}}\n"
+    )
+}
+
+fn tool_description(project: &Project) -> serde_json::Value {
+    project.manifest()["tools"][0]["description"].clone()
+}
+
+#[test]
+fn description_files_are_read_relative_to_the_source() {
+    let project = Project::new()
+        .file("src/tools/search.ts", &described_tool("descriptionFile: \"docs/search.md\""))
+        .file("src/tools/docs/search.md", "\nSearches the index.\n\n");
+
+    assert_eq!(tool_description(&project), "Searches the index.");
+}
+
+#[test]
+fn markdown_descriptions_are_not_read_as_files() {
+    let project = Project::new()
+        .file("src/search.ts", &described_tool("description: \"search.md\""))
+        .file("src/search.md", "Searches the index.");
+
+    assert_eq!(tool_description(&project), "search.md");
+}
+
+#[test]
+fn missing_description_files_are_errors() {
+    let project = Project::new().file("src/search.ts", &described_tool("descriptionFile: \"search.md\""));
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot read description file"))
+        .stderr(predicate::str::contains("search.md"));
+}
+
+#[test]
+fn watch_rebuilds_when_a_description_file_changes() {
+    let project = Project::new()
+        .file("src/search.ts", &described_tool("descriptionFile: \"search.md\""))
+        .file("src/search.md", "Before");
+    let bundle = project.join("out.aria");
+
+    let mut watch = std::process::Command::new(assert_cmd::cargo::cargo_bin("arc"))
+        .args(["build", ".", "--watch", "-o", "out.aria"])
+        .current_dir(project.path())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let built = |description: &str| (0..50).any(|_| {
+        std::thread::sleep(Duration::from_millis(200));
+        bundle.exists() && bundle_manifest(&bundle)["tools"][0]["description"] == description
+    });
+
+    let first = built("Before");
+    project.write("src/search.md", "After");
+    let rebuilt = first && built("After");
+    watch.kill().unwrap();
+    watch.wait().unwrap();
+    assert!(first, "watch did not build");
+    assert!(rebuilt, "watch did not rebuild after the description file changed");
+}
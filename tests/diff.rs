@@ -0,0 +1,56 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+fn build(project: &Project, output: &str) {
+    project.arc().args(["build", ".", "-o", output]).assert().success();
+}
+
+#[test]
+fn identical_bundles_succeed() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+    build(&project, "old.aria");
+    build(&project, "new.aria");
+
+    project.arc().args(["diff", "old.aria", "new.aria"])
+        .assert()
+        .success()
+        .stdout(contains("No differences"));
+}
+
+#[test]
+fn code_changes_are_reported_per_item_and_fail() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/wave.ts", &common::tool_source("wave"));
+    build(&project, "old.aria");
+    project.write("src/greet.ts", &common::tool_source("greet").replace("return input;", "return input.trim();"));
+    build(&project, "new.aria");
+
+    project.arc().args(["diff", "old.aria", "new.aria"])
+        .assert()
+        .failure()
+        .stdout(contains("changed tool 'greet'"))
+        .stdout(contains("wave").not())
+        .stderr(contains("Bundles differ: 1 implementation(s) and 1 source file(s) changed"));
+}
+
+#[test]
+fn compare_manifest_fails_the_build_when_items_differ() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+    build(&project, "deployed.aria");
+
+    project.arc().args(["build", ".", "-o", "same.aria", "--compare-manifest", "deployed.aria"])
+        .assert()
+        .success()
+        .stdout(contains("Manifest matches"));
+
+    project.write("src/wave.ts", &common::tool_source("wave"));
+    project.arc().args(["build", ".", "-o", "next.aria", "--compare-manifest", "deployed.aria"])
+        .assert()
+        .failure()
+        .stdout(contains("added tool 'wave'"))
+        .stderr(contains("1 item(s) differ from the --compare-manifest bundle"));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+use serde_json::json;
+
+fn project() -> Project {
+    Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/wave.ts", &common::tool_source("wave"))
+        .file("src/crew.ts", r#"
+import { agent, team, workflow } from "@aria/sdk";
+
+@agent({ name: "Greeter", description: "greets", tools: ["greet", "wave", "greet"] })
+export class Greeter {}
+
+@team({ name: "Crew", description: "works", members: [{ agent: "Greeter", role: "lead" }, { agent: "Greeter", role: "backup" }] })
+export class Crew {}
+
+@workflow({ name: "Twice", description: "greets twice", steps: ["greet", "greet"] })
+export class Twice {}
+"#)
+}
+
+#[test]
+fn duplicate_references_are_dropped_with_a_warning() {
+    project().arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Agent 'Greeter' lists tool 'greet' more than once")
+            .and(contains("Team 'Crew' lists agent 'Greeter' as a member more than once"))
+            .and(contains("Twice").not()));
+}
+
+#[test]
+fn the_first_entry_is_kept() {
+    let manifest = project().manifest();
+
+    assert_eq!(manifest["agents"][0]["tools"], json!(["greet", "wave"]));
+    assert_eq!(manifest["teams"][0]["members"], json!([{ "agent": "Greeter", "role": "lead" }]));
+    assert_eq!(manifest["workflows"][0]["steps"], json!(["greet", "greet"]));
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::{tool_source, Project};
+
+#[test]
+fn emitted_items_are_the_only_stdout() {
+    let project = Project::new().file("src/tools.ts", &tool_source("echo"));
+
+    let output = project.arc().args(["check", ".", "--emit-items"]).assert().success().get_output().stdout.clone();
+    let files: serde_json::Value = serde_json::from_slice(&output).expect("stdout is JSON");
+    let files = files.as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(files[0]["file"].as_str().unwrap().ends_with("tools.ts"));
+    assert_eq!(files[0]["items"][0]["manifest"]["name"], "echo");
+}
+
+#[test]
+fn emitting_items_compiles_once() {
+    let project = Project::new().file("src/tools.ts", &tool_source("echo"));
+
+    let output = project.arc().args(["check", ".", "--emit-items", "-v"]).assert().success().get_output().stderr.clone();
+    let logs = String::from_utf8(output).unwrap();
+    assert_eq!(logs.matches("Compiling with").count(), 1, "{}", logs);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::{tool_source, Project};
+use predicates::prelude::*;
+
+fn broken_project() -> Project {
+    Project::new()
+        .file("src/main.ts", &tool_source("echo"))
+        .file("src/broken.ts", "export function broken( {\n")
+}
+
+#[test]
+fn json_errors_are_the_only_stdout() {
+    for command in ["check", "build"] {
+        let output = broken_project().arc().args([command, ".", "--error-format", "json"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Checking").or(predicate::str::contains("Building")))
+            .get_output()
+            .stdout
+            .clone();
+
+        let diagnostics: serde_json::Value = serde_json::from_slice(&output).expect("stdout is JSON");
+        let diagnostics = diagnostics.as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1, "{}", command);
+        assert_eq!(diagnostics[0]["code"], "ARC0001");
+        assert!(diagnostics[0]["file"].as_str().unwrap().ends_with("broken.ts"));
+    }
+}
+
+#[test]
+fn github_errors_are_the_only_stdout() {
+    let output = broken_project().arc().args(["check", ".", "--error-format", "github"])
+        .assert()
+        .failure()
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(!stdout.is_empty());
+    assert!(stdout.lines().all(|line| line.starts_with("::error ")), "{}", stdout);
+}
+
+#[test]
+fn human_errors_keep_logs_on_stdout() {
+    broken_project().arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Checking Aria project"));
+}
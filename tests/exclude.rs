@@ -0,0 +1,45 @@
+mod common;
+
+use common::Project;
+
+const CONFIG: &str = r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+exclude = ["generated", "src/legacy/*.ts"]
+
+[runtime]
+bun_version = "1.1"
+"#;
+
+fn project() -> Project {
+    let mut project = Project::new().file("aria.toml", CONFIG);
+    for path in ["src/a.ts", "src/deep/keep.ts", "src/generated/g.ts", "src/deep/generated/h.ts", "src/legacy/old.ts"] {
+        project = project.file(path, "export const x = 1;\n");
+    }
+    project
+}
+
+/// Paths `check --list-files` reports for `input`, relative to the project
+fn listed_files(project: &Project, input: &str) -> Vec<String> {
+    let output = project.arc().args(["check", input, "--list-files", "--json"]).assert().success().get_output().stdout.clone();
+    let root = project.path().canonicalize().unwrap();
+    let paths: Vec<String> = serde_json::from_slice(&output).expect("file list is JSON");
+    paths.iter()
+        .map(|path| std::path::Path::new(path).strip_prefix(&root).unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn excluded_directories_and_paths_are_not_discovered() {
+    assert_eq!(listed_files(&project(), "."), ["src/a.ts", "src/deep/keep.ts"]);
+}
+
+#[test]
+fn file_inputs_are_never_excluded() {
+    assert_eq!(listed_files(&project(), "src/legacy/old.ts"), ["src/legacy/old.ts"]);
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use common::{bundle_entries, bundle_manifest, tool_source, Project};
+use predicates::prelude::*;
+
+fn gated_tool(name: &str, feature: &str) -> String {
+    format!(
+        "import {{ tool }} from \"@aria/sdk\";\n\n\
+         @tool({{ description: \"{name} tool\", feature: {feature} }})\n\
+         export function {name}(input: string): string {{\n    return input;\n}}\n"
+    )
+}
+
+fn tool_names(manifest: &serde_json::Value) -> Vec<&str> {
+    manifest["tools"].as_array().unwrap().iter().map(|tool| tool["name"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn disabled_features_drop_their_items_and_sources() {
+    let project = Project::new()
+        .file("src/main.ts", &tool_source("stable"))
+        .file("src/beta.ts", &gated_tool("experimental", "\"beta\""));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+    let bundle = project.join("out.aria");
+    assert_eq!(tool_names(&bundle_manifest(&bundle)), ["stable"]);
+    let entries = bundle_entries(&bundle);
+    assert!(entries.iter().any(|entry| entry.ends_with("main.js")));
+    assert!(!entries.iter().any(|entry| entry.contains("beta")), "{:?}", entries);
+
+    project.arc().args(["build", ".", "--features", "beta", "-o", "out.aria"]).assert().success();
+    let mut names = tool_names(&bundle_manifest(&bundle)).into_iter().map(String::from).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["experimental", "stable"]);
+}
+
+#[test]
+fn excluded_sources_imported_elsewhere_are_kept() {
+    let project = Project::new()
+        .file("src/main.ts", &format!("import {{ experimental }} from \"./beta\";\n{}export const HELPER = experimental;\n", tool_source("stable")))
+        .file("src/beta.ts", &gated_tool("experimental", "\"beta\""));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+    let bundle = project.join("out.aria");
+    assert_eq!(tool_names(&bundle_manifest(&bundle)), ["stable"]);
+    assert!(bundle_entries(&bundle).iter().any(|entry| entry.ends_with("beta.js")));
+}
+
+#[test]
+fn non_string_features_are_errors() {
+    let project = Project::new().file("src/beta.ts", &gated_tool("experimental", "42"));
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Tool 'experimental' has invalid feature: must be a string literal"));
+}
+
+#[test]
+fn check_accepts_features() {
+    let project = Project::new()
+        .file("src/main.ts", &tool_source("stable"))
+        .file("src/beta.ts", &gated_tool("experimental", "\"beta\""));
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 1"))
+        .stdout(predicate::str::contains("Excluded by --features: 1"));
+    project.arc().args(["check", ".", "--features", "beta"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 2"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::Project;
+use serde_json::json;
+
+fn project() -> Project {
+    Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/wave.ts", &common::tool_source("wave"))
+        .file("src/crew.ts", r#"
+import { agent, team, workflow } from "@aria/sdk";
+
+@agent({ name: "Greeter", description: "greets", tools: ["greet", "wave"] })
+export class Greeter {}
+
+@team({ name: "Crew", description: "works", members: ["Greeter"] })
+export class Crew {}
+
+@workflow({ name: "Welcome", description: "welcomes", steps: ["Greeter", "wave"] })
+export class Welcome {}
+"#)
+}
+
+fn graph(project: &Project, args: &[&str]) -> serde_json::Value {
+    project.arc().args(["build", ".", "-o", "out.aria"]).args(args).assert().success();
+    serde_json::from_str(&common::outer_file(&project.join("out.aria"), "metadata/graph.json")).expect("graph is JSON")
+}
+
+#[test]
+fn graph_maps_each_item_to_what_it_references() {
+    assert_eq!(graph(&project(), &[]), json!({
+        "Crew": ["Greeter"],
+        "Greeter": ["greet", "wave"],
+        "Welcome": ["Greeter", "wave"],
+        "greet": [],
+        "wave": [],
+    }));
+}
+
+#[test]
+fn graph_leaves_out_pruned_items() {
+    assert_eq!(graph(&project(), &["--only", "Crew"]), json!({
+        "Crew": ["Greeter"],
+        "Greeter": ["greet", "wave"],
+        "greet": [],
+        "wave": [],
+    }));
+}
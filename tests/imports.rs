@@ -0,0 +1,34 @@
+mod common;
+
+use common::Project;
+
+const GREET: &str = r#"
+import { tool } from "@aria/sdk";
+import { shout } from "../lib/util";
+import { pad } from "../lib/fmt";
+
+@tool({ description: "greets" })
+export function greet(input: string): string { return pad(shout(input)); }
+"#;
+
+#[test]
+fn local_helpers_are_bundled_with_rewritten_imports() {
+    let project = Project::new()
+        .file("src/tools.ts", GREET)
+        .file("lib/util.ts", "export function shout(s: string): string { return s.toUpperCase(); }\n")
+        .file("lib/fmt/index.ts", "export function pad(s: string): string { return ` ${s} `; }\n");
+
+    project.arc().args(["build", "src", "-o", "out.aria"]).assert().success();
+
+    let bundle = project.join("out.aria");
+    let entries = common::bundle_entries(&bundle);
+    for entry in ["implementations/_sources/src/tools.js", "implementations/_sources/lib/util.js", "implementations/_sources/lib/fmt/index.js"] {
+        assert!(entries.contains(&entry.to_string()), "{entry} missing from {entries:?}");
+    }
+
+    let tools = common::bundle_file(&bundle, "implementations/_sources/src/tools.js");
+    assert!(tools.contains(r#"from "../lib/util.js""#), "{tools}");
+    assert!(tools.contains(r#"from "../lib/fmt/index.js""#), "{tools}");
+    assert!(common::bundle_file(&bundle, "implementations/_sources/lib/util.js").contains("toUpperCase"));
+    assert_eq!(common::bundle_file(&bundle, "implementations/tools/greet.js"), "export * from '../_sources/src/tools.js';");
+}
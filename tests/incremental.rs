@@ -0,0 +1,132 @@
+mod common;
+
+use common::{bundle_entries, bundle_file, bundle_manifest, git, tool_source, Project};
+use predicates::prelude::*;
+use predicates::str::contains;
+
+/// A committed project where `top` imports `middle`, which imports `leaf`,
+/// and `other` imports nothing
+fn import_chain() -> Project {
+    let project = Project::new()
+        .file("src/leaf.ts", &format!("{}export const LEAF = 1;\n", tool_source("leaf")))
+        .file("src/middle.ts", &format!("import {{ LEAF }} from \"./leaf\";\n{}export const MIDDLE = LEAF;\n", tool_source("middle")))
+        .file("src/top.ts", &format!("import {{ MIDDLE }} from \"./middle\";\n{}export const TOP = MIDDLE;\n", tool_source("top")))
+        .file("src/other.ts", &tool_source("other"));
+    git(&project, &["init", "-q"]);
+    git(&project, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "--allow-empty", "-m", "empty"]);
+    git(&project, &["add", "-A"]);
+    git(&project, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "init"]);
+    project
+}
+
+/// Mark every cached tool description, so reused entries can be told
+/// apart from recompiled files
+fn mark_cache(project: &Project) {
+    for entry in std::fs::read_dir(project.join(".arc-cache")).expect("cache dir") {
+        let path = entry.unwrap().path();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace(" tool\"", " cached\"")).unwrap();
+    }
+}
+
+fn descriptions(project: &Project) -> serde_json::Map<String, serde_json::Value> {
+    project.arc().args(["build", ".", "--since", "HEAD", "-o", "out.aria"]).assert().success();
+    let manifest = bundle_manifest(&project.join("out.aria"));
+    manifest["tools"].as_array().unwrap().iter()
+        .map(|tool| (tool["name"].as_str().unwrap().to_string(), tool["description"].clone()))
+        .collect()
+}
+
+#[test]
+fn unchanged_files_reuse_the_cache() {
+    let project = import_chain();
+    project.arc().args(["check", ".", "--since", "HEAD"]).assert().success();
+    mark_cache(&project);
+
+    let descriptions = descriptions(&project);
+    for name in ["leaf", "middle", "top", "other"] {
+        assert_eq!(descriptions[name], format!("{} cached", name));
+    }
+}
+
+#[test]
+fn changed_imports_recompile_every_importer() {
+    let project = import_chain();
+    project.arc().args(["check", ".", "--since", "HEAD"]).assert().success();
+    mark_cache(&project);
+
+    // Only the leaf changes; its importers are recompiled, directly or not
+    project.write("src/leaf.ts", &format!("{}export const LEAF = 2;\n", tool_source("leaf")));
+
+    let descriptions = descriptions(&project);
+    assert_eq!(descriptions["leaf"], "leaf tool");
+    assert_eq!(descriptions["middle"], "middle tool");
+    assert_eq!(descriptions["top"], "top tool");
+    assert_eq!(descriptions["other"], "other cached");
+}
+
+#[test]
+fn changed_options_bypass_the_cache() {
+    let project = import_chain();
+    project.arc().args(["check", ".", "--since", "HEAD"]).assert().success();
+    mark_cache(&project);
+
+    project.arc().args(["build", ".", "--since", "HEAD", "--features", "beta", "-o", "out.aria"]).assert().success();
+    let manifest = bundle_manifest(&project.join("out.aria"));
+    let other = manifest["tools"].as_array().unwrap().iter().find(|tool| tool["name"] == "other").unwrap();
+    assert_eq!(other["description"], "other tool");
+}
+
+#[test]
+fn new_projects_ignore_the_cache() {
+    let project = Project::new();
+    project.arc().args(["new", "demo"]).assert().success();
+
+    let gitignore = std::fs::read_to_string(project.join("demo/.gitignore")).unwrap();
+    assert!(gitignore.lines().any(|line| line == ".arc-cache/"));
+}
+
+#[test]
+fn cached_files_reread_their_description_files() {
+    let project = import_chain()
+        .file("src/other.ts", &tool_source("other").replace("description: \"other tool\"", "descriptionFile: \"other.md\""))
+        .file("src/other.md", "Before");
+    git(&project, &["add", "-A"]);
+    git(&project, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "describe"]);
+    project.arc().args(["check", ".", "--since", "HEAD"]).assert().success();
+
+    project.write("src/other.md", "After");
+    assert_eq!(descriptions(&project)["other"], "After");
+}
+
+#[test]
+fn description_only_changes_rebuild_just_the_manifest() {
+    let project = import_chain()
+        .file("src/other.ts", &tool_source("other").replace("description: \"other tool\"", "descriptionFile: \"other.md\""))
+        .file("src/other.md", "Before");
+    git(&project, &["add", "-A"]);
+    git(&project, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "describe"]);
+    let build = || project.arc().args(["build", ".", "--since", "HEAD", "-o", "out.aria", "-v"]).assert().success();
+
+    build().stdout(contains("Manifest-only rebuild").not());
+    let sources: Vec<(String, String)> = bundle_entries(&project.join("out.aria")).into_iter()
+        .filter(|name| name.starts_with("implementations/_sources/") && name.ends_with(".js"))
+        .map(|name| (bundle_file(&project.join("out.aria"), &name), name))
+        .collect();
+
+    project.write("src/other.md", "After");
+    build().stdout(contains("Manifest-only rebuild"));
+
+    let bundle = project.join("out.aria");
+    let other = bundle_manifest(&bundle)["tools"].as_array().unwrap().iter()
+        .find(|tool| tool["name"] == "other").unwrap().clone();
+    assert_eq!(other["description"], "After");
+    assert_eq!(sources.len(), 4);
+    for (content, name) in sources {
+        assert_eq!(bundle_file(&bundle, &name), content);
+    }
+
+    // A changed source is compiled again, so the bundle is rebuilt in full
+    project.write("src/leaf.ts", &format!("{}export const LEAF = 2;\n", tool_source("leaf")));
+    build().stdout(contains("Manifest-only rebuild").not());
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::Project;
+use serde_json::json;
+
+#[test]
+fn inputs_are_written_as_typed_specs() {
+    let manifest = Project::new().file("src/tools.ts", r#"
+        import { tool } from "@aria/sdk";
+        @tool({
+            description: "searches",
+            inputs: {
+                query: "string",
+                limit: { type: "number", default: 10 },
+                strict: { type: "boolean", required: false, description: "Exact matches only" },
+                page: { type: "number", required: true },
+            },
+        })
+        export function search(): void {}
+    "#).manifest();
+
+    assert_eq!(manifest["manifest_version"], 3);
+    assert_eq!(manifest["tools"][0]["inputs"], json!({
+        "query": { "type": "string", "required": true },
+        "limit": { "type": "number", "required": false, "default": 10 },
+        "strict": { "type": "boolean", "required": false, "description": "Exact matches only" },
+        "page": { "type": "number", "required": true },
+    }));
+}
+
+#[test]
+fn manifests_with_type_name_inputs_still_package() {
+    let project = Project::new()
+        .file("manifest.json", &json!({
+            "manifest_version": 2,
+            "name": "app",
+            "version": "1.0.0",
+            "tools": [{ "name": "search", "description": "searches", "inputs": { "query": "string" } }],
+            "agents": [], "teams": [], "pipelines": [],
+        }).to_string())
+        .file("js/search.js", "export function search(query) { return query; }\n");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"]).assert().success();
+
+    let manifest = common::bundle_manifest(&project.join("dist/app.aria"));
+    assert_eq!(manifest["tools"][0]["inputs"], json!({ "query": { "type": "string", "required": true } }));
+}
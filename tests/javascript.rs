@@ -0,0 +1,71 @@
+mod common;
+
+use common::{bundle_entries, bundle_manifest, tool_source, Project};
+use predicates::prelude::*;
+
+const JS_TOOL: &str = "import { tool } from \"@aria/sdk\";\n\n\
+    @tool({ description: \"js tool\" })\n\
+    export function shout(input) {\n    return input.toUpperCase();\n}\n";
+
+fn mixed_project() -> Project {
+    Project::new()
+        .file("src/main.ts", &tool_source("echo"))
+        .file("src/shout.js", JS_TOOL)
+        .file("eslint.config.js", "export default [];\n")
+}
+
+#[test]
+fn javascript_is_only_discovered_when_asked() {
+    let project = mixed_project();
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 1"));
+    project.arc().args(["check", ".", "--include-js"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 2"));
+}
+
+#[test]
+fn javascript_inputs_are_always_compiled() {
+    let project = mixed_project();
+
+    project.arc().args(["check", "src/shout.js"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 1"));
+}
+
+#[test]
+fn emitted_javascript_is_not_rediscovered() {
+    let project = mixed_project();
+
+    for _ in 0..2 {
+        project.arc().args(["build", ".", "--include-js", "--emit-js-dir", "src/generated", "-o", "out.aria"])
+            .assert()
+            .success();
+    }
+
+    assert!(project.join("src/generated").is_dir());
+    let bundle = project.join("out.aria");
+    assert_eq!(bundle_manifest(&bundle)["tools"].as_array().unwrap().len(), 2);
+    let entries = bundle_entries(&bundle);
+    assert!(!entries.iter().any(|entry| entry.contains("generated")), "{:?}", entries);
+    assert!(!entries.iter().any(|entry| entry.contains("eslint.config")), "{:?}", entries);
+}
+
+#[test]
+fn implementations_record_their_language() {
+    let project = mixed_project();
+    project.arc().args(["build", ".", "--include-js", "-o", "out.aria"]).assert().success();
+
+    let manifest = bundle_manifest(&project.join("out.aria"));
+    let language = |name: &str| manifest["implementations"].as_array().unwrap().iter()
+        .find(|record| record["name"] == name)
+        .map(|record| record["source_language"].clone())
+        .unwrap();
+    assert_eq!(language("echo"), "TypeScript");
+    assert_eq!(language("shout"), "JavaScript");
+}
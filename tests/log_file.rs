@@ -0,0 +1,15 @@
+mod common;
+
+use common::Project;
+
+#[test]
+fn verbose_build_details_are_written_to_the_log_file() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria", "-v", "--log-file", "build.log"]).assert().success();
+
+    let log = std::fs::read_to_string(project.join("build.log")).unwrap();
+    for line in ["Detailed build information:", "  - Source files compiled: 1", "Phase timings:", "  - parse: "] {
+        assert!(log.contains(line), "{line:?} missing from log:\n{log}");
+    }
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::Project;
+use serde_json::Value;
+
+fn tool_outputs(source: &str) -> Value {
+    let project = Project::new().file("src/tools.ts", source);
+    let manifest = project.manifest();
+    manifest["tools"].as_array().unwrap().iter()
+        .map(|tool| (tool["name"].as_str().unwrap().to_string(), tool.get("output").cloned().unwrap_or(Value::Null)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+#[test]
+fn records_bare_return_types() {
+    let outputs = tool_outputs(r#"
+        import { tool } from "@aria/sdk";
+        @tool({ description: "a" }) export function count(): number { return 1; }
+        @tool({ description: "b" }) export function names(): string[] { return []; }
+        @tool({ description: "c" }) export function mixed(): (string | number)[] { return []; }
+        @tool({ description: "d" }) export function named(): Api.SearchResult { return null as any; }
+        @tool({ description: "e" }) export function generic(): Map<string, number> { return new Map(); }
+        @tool({ description: "f" }) export function nothing(): void {}
+        @tool({ description: "g" }) export function untyped() { return 1; }
+    "#);
+
+    assert_eq!(outputs["count"], "number");
+    assert_eq!(outputs["names"], "string[]");
+    assert_eq!(outputs["mixed"], "(string | number)[]");
+    assert_eq!(outputs["named"], "Api.SearchResult");
+    assert_eq!(outputs["generic"], "Map<string, number>");
+    assert_eq!(outputs["nothing"], "void");
+    assert_eq!(outputs["untyped"], Value::Null);
+}
+
+#[test]
+fn unwraps_promise_return_types() {
+    let outputs = tool_outputs(r#"
+        import { tool } from "@aria/sdk";
+        @tool({ description: "a" }) export async function search(): Promise<SearchResult[]> { return []; }
+        @tool({ description: "b" }) export async function either(): Promise<string | null> { return null; }
+        @tool({ description: "c" }) export async function done(): Promise<void> {}
+    "#);
+
+    assert_eq!(outputs["search"], "SearchResult[]");
+    assert_eq!(outputs["either"], "string | null");
+    assert_eq!(outputs["done"], "void");
+}
+
+#[test]
+fn records_the_hash_of_each_bundled_source() {
+    let project = Project::new().file("src/tools.ts", &format!("{}\n{}", common::tool_source("greet"), "\
+@tool({ description: \"wave tool\" })
+export function wave(input: string): string {
+    return input;
+}
+"));
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+
+    let bundle = project.join("out.aria");
+    let source_name = common::bundle_entries(&bundle).into_iter()
+        .find(|name| name.starts_with("implementations/_sources/") && name.ends_with(".js"))
+        .expect("bundled source");
+    let source = common::bundle_file(&bundle, &source_name);
+    let records = common::bundle_manifest(&bundle)["implementations"].as_array().unwrap().clone();
+
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert_eq!(record["source_hash"], blake3::hash(source.as_bytes()).to_hex().as_str());
+        assert_eq!(record["source_size"], source.len() as u64);
+    }
+}
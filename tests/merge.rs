@@ -0,0 +1,95 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const CONFIG: &str = r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+dependencies = ["../tools.aria"]
+
+[runtime]
+bun_version = "1.1"
+"#;
+
+/// `tools.aria` defining `greet`, and `app.aria` whose agent uses it as an external
+fn bundles() -> Project {
+    let project = Project::new()
+        .file("tools/src/greet.ts", &common::tool_source("greet"))
+        .file("app/aria.toml", CONFIG)
+        .file("app/src/helper.ts", r#"
+import { agent } from "@aria/sdk";
+
+@agent({ name: "Helper", description: "helps", tools: ["greet"] })
+export class Helper {}
+"#);
+    project.arc().current_dir(project.join("tools")).args(["build", ".", "-o", "../tools.aria"]).assert().success();
+    project.arc().current_dir(project.join("app")).args(["build", ".", "-o", "../app.aria"]).assert().success();
+    project
+}
+
+/// Check that every stub re-exports a source stored in `bundle`
+fn assert_stubs_resolve(bundle: &std::path::Path) {
+    let entries = common::bundle_entries(bundle);
+    let stubs = entries.iter().filter(|name| name.ends_with(".js") && !name.starts_with("implementations/_sources/"));
+    for stub in stubs {
+        let stub_content = common::bundle_file(bundle, stub);
+        let source = stub_content.trim_start_matches("export * from '../").trim_end_matches("';");
+        assert!(entries.contains(&format!("implementations/{source}")), "{stub} imports {source}, not in {entries:?}");
+    }
+}
+
+#[test]
+fn merged_bundles_resolve_their_externals() {
+    let project = bundles();
+    assert_eq!(common::bundle_manifest(&project.join("app.aria"))["externals"][0]["name"], "greet");
+
+    project.arc().args(["merge", "app.aria", "tools.aria", "-o", "merged.aria"]).assert().success();
+
+    let bundle = project.join("merged.aria");
+    let manifest = common::bundle_manifest(&bundle);
+    assert_eq!(manifest["tools"][0]["name"], "greet");
+    assert_eq!(manifest["agents"][0]["name"], "Helper");
+    assert!(manifest.get("externals").is_none(), "{manifest}");
+    assert_stubs_resolve(&bundle);
+}
+
+#[test]
+fn the_first_bundle_names_the_merge() {
+    let project = bundles()
+        .file("manifest.json", &serde_json::json!({
+            "manifest_version": 3,
+            "name": "first",
+            "version": "1.2.3",
+            "tools": [{ "name": "wave", "description": "waves", "inputs": {} }],
+            "agents": [], "teams": [], "pipelines": [],
+        }).to_string())
+        .file("js/wave.js", "export function wave(input) { return input; }\n");
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js", "-o", "first.aria"]).assert().success();
+
+    project.arc().args(["merge", "first.aria", "tools.aria"]).assert().success();
+
+    let manifest = common::bundle_manifest(&project.join("dist/first.aria"));
+    assert_eq!(manifest["name"], "first");
+    assert_eq!(manifest["version"], "1.2.3");
+    assert_eq!(manifest["tools"].as_array().unwrap().len(), 2);
+    assert_stubs_resolve(&project.join("dist/first.aria"));
+}
+
+#[test]
+fn names_defined_twice_are_rejected() {
+    let project = bundles();
+
+    project.arc().args(["merge", "tools.aria", "tools.aria", "-o", "merged.aria"])
+        .assert()
+        .failure()
+        .stderr(contains("Cannot merge bundles with colliding names").and(contains("'greet' is defined in both")));
+
+    assert!(!project.join("merged.aria").exists());
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{bundle_manifest, git, tool_source, Project};
+use predicates::str::contains;
+
+/// A project with one tool in each of the `a` and `b` directories
+fn two_dirs() -> Project {
+    Project::new()
+        .file("a/greet.ts", &tool_source("greet"))
+        .file("b/wave.ts", &tool_source("wave"))
+}
+
+fn commit(project: &Project, dir: &str) {
+    git(project, &["-C", dir, "init", "-q"]);
+    git(project, &["-C", dir, "add", "-A"]);
+    git(project, &["-C", dir, "-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "init"]);
+}
+
+fn tool_names(project: &Project) -> Vec<String> {
+    let mut names: Vec<String> = bundle_manifest(&project.join("out.aria"))["tools"].as_array().unwrap().iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn directory_inputs_are_built_together() {
+    let project = two_dirs();
+    project.arc().args(["check", "a", "b"]).assert().success();
+    project.arc().args(["build", "a", "b", "-o", "out.aria"]).assert().success();
+
+    assert_eq!(tool_names(&project), ["greet", "wave"]);
+}
+
+#[test]
+fn since_accepts_inputs_in_one_repository() {
+    let project = two_dirs();
+    commit(&project, ".");
+    project.arc().args(["check", "a", "b", "--since", "HEAD"]).assert().success();
+    project.arc().args(["build", "a", "b", "--since", "HEAD", "-o", "out.aria"]).assert().success();
+
+    assert_eq!(tool_names(&project), ["greet", "wave"]);
+}
+
+#[test]
+fn since_rejects_inputs_in_different_repositories() {
+    let project = two_dirs();
+    commit(&project, "a");
+    commit(&project, "b");
+
+    for command in ["check", "build"] {
+        project.arc().args([command, "a", "b", "--since", "HEAD"])
+            .assert()
+            .failure()
+            .stderr(contains("--since needs every input in one git repository, but b is outside"));
+    }
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::Project;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+/// Parse `content` as XML, failing on malformed comments, text or tags
+fn assert_well_formed(content: &str) {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().check_comments = true;
+    reader.config_mut().check_end_names = true;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(Event::Text(text)) => {
+                text.unescape().unwrap_or_else(|e| panic!("{e} in:\n{content}"));
+            }
+            Ok(_) => {}
+            Err(e) => panic!("{e} at byte {} in:\n{content}", reader.error_position()),
+        }
+    }
+}
+
+#[test]
+fn generated_llm_xml_is_well_formed() {
+    let project = Project::new();
+
+    for (name, template) in [("my-agent", "basic"), ("my--agent", "basic"), ("research_bot", "advanced")] {
+        project.arc().args(["new", name, "--template", template]).assert().success();
+
+        let llm_xml = std::fs::read_to_string(project.join(name).join("llm.xml")).expect("llm.xml written");
+        assert_well_formed(&llm_xml);
+        assert!(llm_xml.contains(name), "{name} missing from:\n{llm_xml}");
+    }
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+
+fn dangling_agent() -> Project {
+    Project::new().file("src/helper.ts", r#"
+import { agent } from "@aria/sdk";
+
+@agent({ name: "Helper", description: "helps", tools: ["greet"] })
+export class Helper {}
+"#)
+}
+
+#[test]
+fn undefined_references_fail_the_build() {
+    let project = dangling_agent();
+
+    project.arc().args(["build", ".", "-o", "out.aria"])
+        .assert()
+        .failure()
+        .stderr(contains("Agent 'Helper' references undefined tool: 'greet'"));
+
+    assert!(!project.join("out.aria").exists());
+}
+
+#[test]
+fn no_validate_builds_and_warns() {
+    let project = dangling_agent();
+
+    project.arc().args(["build", ".", "-o", "out.aria", "--no-validate"])
+        .assert()
+        .success()
+        .stdout(contains("Agent 'Helper' references undefined tool: 'greet'"));
+
+    assert_eq!(common::bundle_manifest(&project.join("out.aria"))["agents"][0]["tools"], serde_json::json!(["greet"]));
+}
+
+#[test]
+fn check_still_fails() {
+    dangling_agent().arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("references undefined tool"));
+}
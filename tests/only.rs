@@ -0,0 +1,62 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+
+fn project() -> Project {
+    Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/wave.ts", &common::tool_source("wave"))
+        .file("src/lonely.ts", &common::tool_source("lonely"))
+        .file("src/crew.ts", r#"
+import { agent, team } from "@aria/sdk";
+
+@agent({ name: "Greeter", description: "greets", tools: ["greet"] })
+export class Greeter {}
+
+@agent({ name: "Waver", description: "waves", tools: ["wave"] })
+export class Waver {}
+
+@team({ name: "Crew", description: "works", members: ["Greeter"] })
+export class Crew {}
+"#)
+}
+
+fn names(manifest: &serde_json::Value, kind: &str) -> Vec<String> {
+    manifest[kind].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn build_keeps_the_selection_and_what_it_references() {
+    let project = project();
+
+    project.arc().args(["build", ".", "-o", "out.aria", "--only", "Crew", "--only", "lonely"]).assert().success();
+
+    let bundle = project.join("out.aria");
+    let manifest = common::bundle_manifest(&bundle);
+    assert_eq!(names(&manifest, "teams"), ["Crew"]);
+    assert_eq!(names(&manifest, "agents"), ["Greeter"]);
+    assert_eq!(names(&manifest, "tools"), ["greet", "lonely"]);
+
+    let entries = common::bundle_entries(&bundle);
+    assert!(!entries.contains(&"implementations/tools/wave.js".to_string()), "{entries:?}");
+    assert!(entries.iter().any(|name| name.starts_with("implementations/_sources/") && name.ends_with("wave.js")), "{entries:?}");
+}
+
+#[test]
+fn check_reports_only_the_selection() {
+    project().arc().args(["check", ".", "--only", "Waver,lonely"])
+        .assert()
+        .success()
+        .stdout(contains("Tools: 2"))
+        .stdout(contains("Agents: 1"))
+        .stdout(contains("Teams: 0"));
+}
+
+#[test]
+fn unknown_names_are_errors() {
+    project().arc().args(["check", ".", "--only", "Crew,nobody"])
+        .assert()
+        .failure()
+        .stderr(contains("--only names undefined items: nobody"));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::Project;
+
+const TOOLS: &str = r#"
+import { agent, tool } from "@aria/sdk";
+@tool({ description: "z", inputs: { zeta: "string", apple: "number", mango: "boolean" } }) export function zulu(): void {}
+@tool({ description: "a" }) export function alpha(): void {}
+@agent({ name: "Zed", description: "z", tools: ["zulu"] }) export class Zed {}
+@agent({ name: "Abe", description: "a", tools: ["alpha"] }) export class Abe {}
+"#;
+
+fn built_manifest(project: &Project, output: &str) -> String {
+    project.arc().args(["build", ".", "-o", output]).assert().success();
+    common::bundle_file(&project.join(output), "manifest.json")
+}
+
+#[test]
+fn entries_are_sorted_by_name() {
+    let project = Project::new()
+        .file("src/b.ts", TOOLS)
+        .file("src/a.ts", &common::tool_source("mike"));
+
+    let manifest: serde_json::Value = serde_json::from_str(&built_manifest(&project, "out.aria")).unwrap();
+
+    let names = |kind: &str| -> Vec<String> {
+        manifest[kind].as_array().unwrap().iter().map(|item| item["name"].as_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(names("tools"), ["alpha", "mike", "zulu"]);
+    assert_eq!(names("agents"), ["Abe", "Zed"]);
+}
+
+#[test]
+fn input_keys_are_written_in_sorted_order() {
+    let project = Project::new().file("src/tools.ts", TOOLS);
+
+    let manifest = built_manifest(&project, "out.aria");
+
+    let position = |key: &str| manifest.find(&format!("\"{key}\"")).unwrap_or_else(|| panic!("{key} in {manifest}"));
+    assert!(position("apple") < position("mango") && position("mango") < position("zeta"), "{manifest}");
+}
+
+#[test]
+fn repeated_builds_write_identical_manifests() {
+    let project = Project::new()
+        .file("src/b.ts", TOOLS)
+        .file("src/a.ts", &common::tool_source("mike"));
+
+    let first = built_manifest(&project, "first.aria");
+    for attempt in 0..3 {
+        assert_eq!(built_manifest(&project, &format!("again-{attempt}.aria")), first);
+    }
+}
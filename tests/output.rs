@@ -0,0 +1,36 @@
+mod common;
+
+use common::Project;
+use std::io::Read;
+
+#[test]
+fn hash_placeholder_names_the_bundle_after_its_build_hash() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out/app-{hash}.aria"]).assert().success();
+
+    let names: Vec<String> = std::fs::read_dir(project.join("out")).unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".aria"))
+        .collect();
+    assert_eq!(names.len(), 1, "{names:?}");
+    let hash = names[0].strip_prefix("app-").and_then(|n| n.strip_suffix(".aria")).expect("hashed name");
+
+    let mut outer = zip::ZipArchive::new(std::fs::File::open(project.join("out").join(&names[0])).unwrap()).unwrap();
+    let mut metadata = String::new();
+    outer.by_name("metadata/build.json").unwrap().read_to_string(&mut metadata).unwrap();
+    let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+    assert_eq!(metadata["build_hash"], hash);
+}
+
+#[test]
+fn hash_placeholder_is_only_allowed_in_the_file_name() {
+    let project = Project::new()
+        .file("aria.toml", "[project]\nname = \"app\"\nversion = \"1.0.0\"\ndescription = \"d\"\n\n[build]\ntarget = \"typescript\"\noutput = \"{hash}/app.aria\"\n\n[runtime]\nbun_version = \"latest\"\n")
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", "."])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("may only appear in the file name"));
+}
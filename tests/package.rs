@@ -0,0 +1,98 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+fn manifest(name: &str, tool: &str) -> String {
+    serde_json::json!({
+        "manifest_version": 3,
+        "name": name,
+        "version": "1.0.0",
+        "tools": [{ "name": tool, "description": "greets", "inputs": {} }],
+        "agents": [], "teams": [], "pipelines": [],
+    }).to_string()
+}
+
+#[test]
+fn packages_a_manifest_and_compiled_javascript() {
+    let project = Project::new()
+        .file("manifest.json", &manifest("app", "greet"))
+        .file("js/tools/greet.js", "export function greet(input) { return input; }\n");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"]).assert().success();
+
+    let entries = common::bundle_entries(&project.join("dist/app.aria"));
+    assert!(entries.contains(&"implementations/tools/greet.js".to_string()), "{entries:?}");
+}
+
+#[test]
+fn rejects_bundle_names_that_leave_the_output_dir() {
+    let project = Project::new()
+        .file("work/manifest.json", &manifest("../../escaped", "greet"))
+        .file("work/js/greet.js", "export function greet(input) { return input; }\n");
+
+    project.arc().current_dir(project.join("work"))
+        .args(["package", "-m", "manifest.json", "--impl-dir", "js"])
+        .assert()
+        .failure()
+        .stderr(contains("not a safe file name"));
+
+    assert!(!project.join("escaped.aria").exists());
+}
+
+#[test]
+fn rejects_implementation_names_with_path_components() {
+    let project = Project::new()
+        .file("manifest.json", &manifest("app", "../greet"))
+        .file("greet.js", "export function greet(input) { return input; }\n")
+        .file("js/.keep", "");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"])
+        .assert()
+        .failure()
+        .stderr(contains("\"../greet\"").and(contains("not a safe file name")));
+}
+
+#[test]
+fn lists_every_schema_violation_in_a_malformed_manifest() {
+    let project = Project::new()
+        .file("manifest.json", &serde_json::json!({
+            "manifest_version": 3,
+            "name": "app",
+            "version": "1.0.0",
+            "tools": [{ "name": "greet", "inputs": {} }],
+            "agents": "none", "teams": [], "pipelines": [],
+        }).to_string())
+        .file("js/greet.js", "export function greet(input) { return input; }\n");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"])
+        .assert()
+        .failure()
+        .stderr(contains("Manifest does not match schema")
+            .and(contains("/tools/0: \"description\" is a required property"))
+            .and(contains("/agents: \"none\" is not of type \"array\"")));
+
+    assert!(!project.join("dist/app.aria").exists());
+}
+
+#[test]
+fn packages_a_manifest_with_agent_name_team_members() {
+    let project = Project::new()
+        .file("manifest.json", &serde_json::json!({
+            "manifest_version": 1,
+            "name": "app",
+            "version": "1.0.0",
+            "tools": [],
+            "agents": [{ "name": "Helper", "description": "helps", "tools": [] }],
+            "teams": [{ "name": "Crew", "description": "works", "members": ["Helper"] }],
+            "pipelines": [],
+        }).to_string())
+        .file("js/Helper.js", "export class Helper {}\n")
+        .file("js/Crew.js", "export class Crew {}\n");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"]).assert().success();
+
+    let manifest = common::bundle_manifest(&project.join("dist/app.aria"));
+    assert_eq!(manifest["teams"][0]["members"], serde_json::json!([{ "agent": "Helper" }]));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::{tool_source, Project};
+use predicates::prelude::*;
+
+#[test]
+fn reports_every_syntax_error_in_a_file() {
+    let project = Project::new().file("src/broken.ts", "export function a() {\n  with (x) {}\n}\nexport function b() {\n  return 1 +;\n}\n");
+
+    project.arc().arg("check").arg(".")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("2 syntax errors"))
+        .stderr(predicate::str::contains("line 2:"))
+        .stderr(predicate::str::contains("line 5: Expression expected"));
+}
+
+#[test]
+fn reports_a_single_syntax_error_by_line() {
+    let project = Project::new().file("src/broken.ts", "export function a() {\n  return 1 +;\n}\n");
+
+    project.arc().arg("check").arg(".")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse module: line 2: Expression expected"));
+}
+
+#[test]
+fn recovered_syntax_errors_are_warnings() {
+    let source = format!("\"use strict\";\n{}\nexport function b() {{\n  delete x;\n}}\n", tool_source("echo"));
+    let project = Project::new().file("src/tools.ts", &source);
+
+    project.arc().arg("check").arg(".")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tools: 1"))
+        .stdout(predicate::str::contains("'delete' cannot be called on an identifier in strict mode"));
+}
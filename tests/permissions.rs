@@ -0,0 +1,81 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+fn config(permissions: &str) -> String {
+    format!(r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+file_permissions = {permissions}
+
+[runtime]
+bun_version = "1.1"
+"#)
+}
+
+/// The permission bits of every entry in `archive`, by name
+fn modes<R: Read + std::io::Seek>(archive: &mut zip::ZipArchive<R>) -> Vec<(String, u32)> {
+    (0..archive.len())
+        .map(|i| {
+            let entry = archive.by_index(i).unwrap();
+            (entry.name().to_string(), entry.unix_mode().expect("entry has a unix mode") & 0o777)
+        })
+        .collect()
+}
+
+/// Modes of the outer `.aria` entries followed by those of `bundle.zip`
+fn bundle_modes(bundle: &Path) -> Vec<(String, u32)> {
+    let mut outer = zip::ZipArchive::new(std::fs::File::open(bundle).unwrap()).unwrap();
+    let mut entries = modes(&mut outer);
+    let mut inner_data = Vec::new();
+    outer.by_name("bundle.zip").unwrap().read_to_end(&mut inner_data).unwrap();
+    entries.extend(modes(&mut zip::ZipArchive::new(Cursor::new(inner_data)).unwrap()));
+    entries
+}
+
+fn assert_modes(bundle: &Path, file_mode: u32) {
+    for (name, mode) in bundle_modes(bundle) {
+        let expected = if name.ends_with('/') { 0o755 } else { file_mode };
+        assert_eq!(mode, expected, "{name} is {mode:o}");
+    }
+}
+
+#[test]
+fn files_are_not_executable_by_default() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+
+    assert_modes(&project.join("out.aria"), 0o644);
+}
+
+#[test]
+fn file_permissions_are_configurable() {
+    let project = Project::new()
+        .file("aria.toml", &config("0o640"))
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+
+    assert_modes(&project.join("out.aria"), 0o640);
+}
+
+#[test]
+fn permissions_above_0o777_are_rejected() {
+    let project = Project::new()
+        .file("aria.toml", &config("0o4755"))
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria"])
+        .assert()
+        .failure()
+        .stderr(contains("Invalid build.file_permissions 0o4755: must be at most 0o777"));
+}
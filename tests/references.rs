@@ -0,0 +1,118 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+
+const HELPER_AGENT: &str = r#"
+import { agent, tool } from "@aria/sdk";
+
+@agent({ name: "Helper", description: "helps", tools: ["greet"] })
+export class Helper {
+    @tool({ description: "looks things up" })
+    lookup(query: string): string { return query; }
+}
+"#;
+
+#[test]
+fn agents_reference_standalone_tools_and_their_own_methods() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/helper.ts", HELPER_AGENT);
+
+    let manifest = project.manifest();
+
+    assert_eq!(manifest["agents"][0]["tools"], serde_json::json!(["greet", "lookup"]));
+}
+
+#[test]
+fn undefined_tools_are_reported() {
+    let project = Project::new().file("src/helper.ts", HELPER_AGENT);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Agent 'Helper' references undefined tool: 'greet'"));
+}
+
+#[test]
+fn tools_defined_twice_are_ambiguous() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/other/greet.ts", &common::tool_source("greet"))
+        .file("src/helper.ts", HELPER_AGENT);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Agent 'Helper' references ambiguous tool 'greet', candidates:"))
+        .stderr(contains("src/greet.ts"))
+        .stderr(contains("src/other/greet.ts"));
+}
+
+/// An agent named `name` with a `search` tool method, listing `tools`
+fn searching_agent(name: &str, tools: &str) -> String {
+    format!(r#"
+import {{ agent, tool }} from "@aria/sdk";
+
+@agent({{ name: "{name}", description: "searches", tools: [{tools}] }})
+export class {name} {{
+    @tool({{ description: "searches" }})
+    search(query: string): string {{ return query; }}
+}}
+"#)
+}
+
+const CALLER_AGENT: &str = r#"
+import { agent } from "@aria/sdk";
+
+@agent({ name: "Caller", description: "calls", tools: ["search"] })
+export class Caller {}
+"#;
+
+#[test]
+fn references_prefer_standalone_tools_over_methods() {
+    let project = Project::new()
+        .file("src/search.ts", &common::tool_source("search"))
+        .file("src/finder.ts", &searching_agent("Finder", ""))
+        .file("src/caller.ts", CALLER_AGENT);
+
+    let manifest = project.manifest();
+
+    let tools: Vec<&str> = manifest["tools"].as_array().unwrap().iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert!(tools.contains(&"search") && tools.contains(&"Finder.search"), "{:?}", tools);
+    let agent_tools = |name: &str| manifest["agents"].as_array().unwrap().iter()
+        .find(|a| a["name"] == name).unwrap()["tools"].clone();
+    assert_eq!(agent_tools("Caller"), serde_json::json!(["search"]));
+    assert_eq!(agent_tools("Finder"), serde_json::json!(["Finder.search"]));
+}
+
+#[test]
+fn references_prefer_the_agents_own_methods_over_other_agents() {
+    let project = Project::new()
+        .file("src/finder.ts", &searching_agent("Finder", r#""search""#))
+        .file("src/seeker.ts", &searching_agent("Seeker", r#""Finder.search""#));
+
+    let manifest = project.manifest();
+
+    let agent_tools = |name: &str| manifest["agents"].as_array().unwrap().iter()
+        .find(|a| a["name"] == name).unwrap()["tools"].clone();
+    assert_eq!(agent_tools("Finder"), serde_json::json!(["Finder.search"]));
+    assert_eq!(agent_tools("Seeker"), serde_json::json!(["Finder.search", "Seeker.search"]));
+}
+
+#[test]
+fn references_matching_several_methods_are_ambiguous() {
+    let project = Project::new()
+        .file("src/finder.ts", &searching_agent("Finder", ""))
+        .file("src/seeker.ts", &searching_agent("Seeker", ""))
+        .file("src/caller.ts", CALLER_AGENT);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Agent 'Caller' references ambiguous tool 'search', candidates:"))
+        .stderr(contains("'Finder.search' in "))
+        .stderr(contains("'Seeker.search' in "));
+}
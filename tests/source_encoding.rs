@@ -0,0 +1,69 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+use std::io::Read;
+
+const GZIP_CONFIG: &str = r#"
+[project]
+name = "app"
+version = "1.0.0"
+description = "An app"
+
+[build]
+target = "typescript"
+source_encoding = "gzip"
+
+[runtime]
+bun_version = "1.1"
+"#;
+
+fn gunzip(data: &[u8]) -> String {
+    let mut decoded = String::new();
+    flate2::read::GzDecoder::new(data).read_to_string(&mut decoded).expect("entry is gzipped");
+    decoded
+}
+
+#[test]
+fn gzip_stores_each_source_gzipped() {
+    let project = Project::new()
+        .file("aria.toml", GZIP_CONFIG)
+        .file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+
+    let bundle = project.join("out.aria");
+    let entries = common::bundle_entries(&bundle);
+    assert!(!entries.iter().any(|name| name.ends_with("greet.js") && name.contains("_sources")), "{entries:?}");
+    let source = gunzip(&common::bundle_bytes(&bundle, "implementations/_sources/greet.js.gz"));
+    assert!(source.contains("export function greet(input)"), "{source}");
+
+    let manifest = common::bundle_manifest(&bundle);
+    assert_eq!(manifest["source_encoding"], "gzip");
+    assert_eq!(manifest["implementations"][0]["source_hash"], blake3::hash(source.as_bytes()).to_hex().as_str());
+    assert_eq!(manifest["implementations"][0]["source_size"], source.len() as u64);
+}
+
+#[test]
+fn identity_leaves_the_manifest_unchanged() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+
+    project.arc().args(["build", ".", "-o", "out.aria"]).assert().success();
+
+    let bundle = project.join("out.aria");
+    assert!(common::bundle_entries(&bundle).contains(&"implementations/_sources/greet.js".to_string()));
+    assert!(common::bundle_manifest(&bundle).get("source_encoding").is_none());
+}
+
+#[test]
+fn gzipped_sources_are_decoded_when_loaded() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+    project.arc().args(["build", ".", "-o", "plain.aria"]).assert().success();
+    project.write("aria.toml", GZIP_CONFIG);
+    project.arc().args(["build", ".", "-o", "gzip.aria"]).assert().success();
+
+    project.arc().args(["diff", "plain.aria", "gzip.aria"])
+        .assert()
+        .success()
+        .stdout(contains("No differences"));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+#[test]
+fn check_reads_source_from_stdin_and_logs_to_stderr() {
+    let project = Project::new();
+
+    project.arc().args(["check", ".", "--stdin"])
+        .write_stdin(common::tool_source("greet"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(contains("Checking Aria source from: <stdin>"))
+        .stderr(contains("Tools: 1"));
+}
+
+#[test]
+fn build_reads_source_from_stdin_and_logs_to_stderr() {
+    let project = Project::new();
+
+    project.arc().args(["build", ".", "--stdin", "-o", "out.aria"])
+        .write_stdin(common::tool_source("greet"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let manifest = common::bundle_manifest(&project.join("out.aria"));
+    assert_eq!(manifest["tools"][0]["name"], "greet");
+}
+
+#[test]
+fn verbose_stdin_builds_keep_stdout_empty() {
+    let project = Project::new();
+
+    project.arc().args(["build", ".", "--stdin", "-o", "out.aria", "-v"])
+        .write_stdin(common::tool_source("greet"))
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(contains("Detailed build information:"))
+        .stderr(contains("Phase timings:"));
+}
+
+#[test]
+fn stdin_errors_name_the_stdin_source() {
+    let project = Project::new();
+
+    project.arc().args(["check", ".", "--stdin"])
+        .write_stdin("export const = ;\n")
+        .assert()
+        .failure()
+        .stdout(predicate::str::is_empty())
+        .stderr(contains("<stdin>"));
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const TAGGED: &str = r#"
+import { agent, tool } from "@aria/sdk";
+
+@tool({ description: "searches", tags: ["web", "search"] })
+export function search(query: string): string { return query; }
+
+@tool({ description: "adds" })
+export function add(a: number, b: number): number { return a + b; }
+
+@agent({ name: "Researcher", description: "researches", tools: ["search"], tags: ["web"] })
+export class Researcher {}
+"#;
+
+#[test]
+fn tags_are_recorded_in_the_manifest() {
+    let project = Project::new().file("src/items.ts", TAGGED);
+
+    let manifest = project.manifest();
+
+    let tools = manifest["tools"].as_array().unwrap();
+    let search = tools.iter().find(|tool| tool["name"] == "search").unwrap();
+    let add = tools.iter().find(|tool| tool["name"] == "add").unwrap();
+    assert_eq!(search["tags"], serde_json::json!(["web", "search"]));
+    assert!(add.get("tags").is_none(), "{add}");
+    assert_eq!(manifest["agents"][0]["tags"], serde_json::json!(["web"]));
+}
+
+#[test]
+fn check_lists_the_items_with_a_tag() {
+    let project = Project::new().file("src/items.ts", TAGGED);
+
+    project.arc().args(["check", ".", "--filter-tag", "web"])
+        .assert()
+        .success()
+        .stdout(contains("Tagged 'web':")
+            .and(contains("tool search"))
+            .and(contains("agent Researcher"))
+            .and(contains("tool add").not()));
+}
+
+#[test]
+fn non_string_tags_are_ignored_with_a_warning() {
+    let project = Project::new().file("src/tools.ts", r#"
+        import { tool } from "@aria/sdk";
+        @tool({ description: "searches", tags: ["web", 42] })
+        export function search(query: string): string { return query; }
+    "#);
+
+    project.arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Ignoring non-string tag on 'search'"));
+
+    assert_eq!(project.manifest()["tools"][0]["tags"], serde_json::json!(["web"]));
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{git, Project};
+use predicates::prelude::*;
+use predicates::str::contains;
+
+const SOURCE: &str = r#"
+import { tool } from "@aria/sdk";
+import { readFile, writeFile } from "fs";
+import type { Options } from "./types";
+import * as path from "path";
+import helper from "lodash";
+
+@tool({ description: "joins" })
+export function run(input: string, opts: Options): string { return path.join(input, readFile.name); }
+
+const names = { writeFile: 1, helper: 2 };
+"#;
+
+#[test]
+fn unused_bindings_are_warned_about() {
+    Project::new().file("src/tools.ts", SOURCE).arc().args(["check", ".", "--lint-unused-imports"])
+        .assert()
+        .success()
+        .stdout(contains("Unused import 'writeFile' from 'fs'")
+            .and(contains("Unused import 'helper' from 'lodash'"))
+            .and(contains("unused import: 2"))
+            .and(contains("'readFile'").not())
+            .and(contains("'Options'").not())
+            .and(contains("'path'").not()));
+}
+
+#[test]
+fn the_lint_is_off_by_default() {
+    Project::new().file("src/tools.ts", SOURCE).arc().args(["check", "."])
+        .assert()
+        .success()
+        .stdout(contains("Unused import").not());
+}
+
+#[test]
+fn cached_files_follow_the_flag() {
+    let project = Project::new().file("src/tools.ts", SOURCE);
+    git(&project, &["init", "-q"]);
+    git(&project, &["add", "-A"]);
+    git(&project, &["-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-q", "-m", "init"]);
+
+    project.arc().args(["check", ".", "--since", "HEAD"]).assert().success();
+    assert!(std::fs::read_dir(project.join(".arc-cache")).expect("cache dir").next().is_some());
+    project.arc().args(["check", ".", "--since", "HEAD", "--lint-unused-imports"])
+        .assert()
+        .success()
+        .stdout(contains("Unused import 'writeFile'"));
+    project.arc().args(["check", ".", "--since", "HEAD"])
+        .assert()
+        .success()
+        .stdout(contains("Unused import").not());
+}
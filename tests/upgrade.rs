@@ -0,0 +1,27 @@
+mod common;
+
+use common::Project;
+use predicates::str::contains;
+
+const PARTIAL_CONFIG: &str = "[project]\nname = \"app\"\nversion = \"1.0.0\"\n";
+
+#[test]
+fn dry_run_prints_the_changes_without_writing() {
+    let project = Project::new().file("aria.toml", PARTIAL_CONFIG);
+
+    project.arc().args(["upgrade", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(contains("+description = \"An Aria agentic application\""))
+        .stdout(contains("+bun_version = \"latest\""));
+
+    assert_eq!(std::fs::read_to_string(project.join("aria.toml")).unwrap(), PARTIAL_CONFIG);
+}
+
+#[test]
+fn upgraded_files_are_up_to_date() {
+    let project = Project::new().file("aria.toml", PARTIAL_CONFIG);
+
+    project.arc().arg("upgrade").assert().success().stdout(contains("Upgraded"));
+    project.arc().arg("upgrade").assert().success().stdout(contains("already matches the current schema"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{tool_source, Project};
+use predicates::prelude::*;
+use std::io::Read;
+
+/// The build hash recorded in the `.aria` file at `bundle`
+fn build_hash(bundle: &std::path::Path) -> String {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(bundle).unwrap()).unwrap();
+    let mut content = String::new();
+    archive.by_name("metadata/build.json").unwrap().read_to_string(&mut content).unwrap();
+    let metadata: serde_json::Value = serde_json::from_str(&content).unwrap();
+    metadata["build_hash"].as_str().unwrap().to_string()
+}
+
+fn receipt(build_hash: &str, socket: &str) -> String {
+    serde_json::json!({
+        "bundle_id": "bundle-1",
+        "bundle_path": "app.aria",
+        "hash_algorithm": "blake3",
+        "build_hash": build_hash,
+        "uploaded_at": "2026-01-01T00:00:00Z",
+        "socket_path": socket,
+        "bytes_uploaded": 1,
+        "upload_time_seconds": 0.1,
+    }).to_string()
+}
+
+#[test]
+fn receipts_are_named_after_the_bundle() {
+    let project = Project::new().file("src/main.ts", &tool_source("echo"));
+    project.arc().args(["build", ".", "-o", "app.aria"]).assert().success();
+    let socket = project.join("quilt.sock").display().to_string();
+    let hash = build_hash(&project.join("app.aria"));
+
+    // A receipt shared by every bundle in the directory is not read
+    project.write(".arc-receipt.json", &receipt(&hash, &socket));
+    project.arc().args(["upload", "app.aria", "--skip-existing", "-s", &socket]).assert().failure();
+
+    project.write("app.aria.receipt.json", &receipt(&hash, &socket));
+    project.arc().args(["upload", "app.aria", "--skip-existing", "-s", &socket])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Bundle already uploaded as bundle-1"))
+        .stdout(predicate::str::contains("app.aria.receipt.json"));
+}
+
+#[test]
+fn skip_existing_skips_bundles_already_uploaded_to_the_daemon() {
+    let project = Project::new().file("src/main.ts", &tool_source("echo"));
+    project.arc().args(["build", ".", "-o", "app.aria"]).assert().success();
+    // Nothing listens here, so only a skipped upload can succeed
+    let socket = project.join("quilt.sock").display().to_string();
+    let receipt = receipt(&build_hash(&project.join("app.aria")), &socket);
+    project.write("app.aria.receipt.json", &receipt);
+
+    project.arc().args(["upload", "app.aria", "--skip-existing", "-s", &socket])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped"));
+    assert_eq!(std::fs::read_to_string(project.join("app.aria.receipt.json")).unwrap(), receipt);
+
+    // Without the flag, or for another daemon, the upload is attempted
+    project.arc().args(["upload", "app.aria", "-s", &socket]).assert().failure();
+    let other = project.join("other.sock").display().to_string();
+    project.arc().args(["upload", "app.aria", "--skip-existing", "-s", &other])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Skipped").not());
+}
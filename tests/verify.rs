@@ -0,0 +1,110 @@
+mod common;
+
+use common::Project;
+use ed25519_dalek::{Signer, SigningKey};
+use predicates::str::contains;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// Rewrite the outer archive of the bundle at `path`, passing each entry's
+/// contents through `edit` and appending `extra` entries
+fn rewrite_bundle(path: &Path, edit: impl Fn(&str, Vec<u8>) -> Vec<u8>, extra: &[(&str, Vec<u8>)]) {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).unwrap();
+        let name = entry.name().to_string();
+        entries.push((name.clone(), edit(&name, data)));
+    }
+
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    for (name, data) in entries.iter().map(|(n, d)| (n.as_str(), d)).chain(extra.iter().map(|(n, d)| (*n, d))) {
+        writer.start_file(name, zip::write::SimpleFileOptions::default()).unwrap();
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap();
+}
+
+fn build_hash(path: &Path) -> String {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).unwrap()).unwrap();
+    let mut metadata = String::new();
+    archive.by_name("metadata/build.json").unwrap().read_to_string(&mut metadata).unwrap();
+    serde_json::from_str::<serde_json::Value>(&metadata).unwrap()["build_hash"].as_str().unwrap().to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn public_key_hex(seed: u8) -> String {
+    hex(&SigningKey::from_bytes(&[seed; 32]).verifying_key().to_bytes())
+}
+
+/// Build a bundle signed with `key` and write the matching public key as hex
+fn signed_project(key: &SigningKey) -> Project {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+    project.arc().args(["build", ".", "-o", "app.aria"]).assert().success();
+
+    let bundle = project.join("app.aria");
+    let signature = key.sign(build_hash(&bundle).as_bytes());
+    let entry = serde_json::json!({
+        "algorithm": "ed25519",
+        "signer": "release@example.com",
+        "signature": hex(&signature.to_bytes()),
+    });
+    rewrite_bundle(&bundle, |_, data| data, &[("metadata/signature", entry.to_string().into_bytes())]);
+
+    project.write("key.pub", &hex(&key.verifying_key().to_bytes()));
+    project
+}
+
+#[test]
+fn verifies_a_signed_bundle() {
+    let project = signed_project(&SigningKey::from_bytes(&[7; 32]));
+
+    project.arc().args(["verify", "app.aria", "--public-key", "key.pub"])
+        .assert()
+        .success()
+        .stdout(contains("app.aria is signed by release@example.com"));
+}
+
+#[test]
+fn rejects_a_signature_from_another_key() {
+    let project = signed_project(&SigningKey::from_bytes(&[7; 32]));
+    project.write("other.pub", &public_key_hex(8));
+
+    project.arc().args(["verify", "app.aria", "--public-key", "other.pub"])
+        .assert()
+        .failure()
+        .stderr(contains("does not match this bundle and key"));
+}
+
+#[test]
+fn rejects_tampered_contents() {
+    let project = signed_project(&SigningKey::from_bytes(&[7; 32]));
+    rewrite_bundle(&project.join("app.aria"), |name, mut data| {
+        if name == "bundle.zip" {
+            data.extend_from_slice(b"tampered");
+        }
+        data
+    }, &[]);
+
+    project.arc().args(["verify", "app.aria", "--public-key", "key.pub"])
+        .assert()
+        .failure()
+        .stderr(contains("do not match its build hash"));
+}
+
+#[test]
+fn rejects_an_unsigned_bundle() {
+    let project = Project::new().file("src/greet.ts", &common::tool_source("greet"));
+    project.arc().args(["build", ".", "-o", "app.aria"]).assert().success();
+    project.write("key.pub", &public_key_hex(7));
+
+    project.arc().args(["verify", "app.aria", "--public-key", "key.pub"])
+        .assert()
+        .failure()
+        .stderr(contains("the bundle is unsigned"));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::Project;
+use predicates::prelude::*;
+use predicates::str::contains;
+
+fn workflow(steps: &str) -> String {
+    format!(r#"
+import {{ workflow }} from "@aria/sdk";
+
+@workflow({{ name: "Onboard", description: "onboards", steps: {steps} }})
+export class Onboard {{}}
+"#)
+}
+
+#[test]
+fn workflows_are_bundled_with_their_steps() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/wave.ts", &common::tool_source("wave"))
+        .file("src/onboard.ts", &workflow(r#"["wave", "greet"]"#));
+
+    project.arc().args(["build", ".", "-o", "out.aria"])
+        .assert()
+        .success()
+        .stdout(contains("Workflows: 1"));
+
+    let bundle = project.join("out.aria");
+    let manifest = common::bundle_manifest(&bundle);
+    assert_eq!(manifest["workflows"][0]["name"], "Onboard");
+    assert_eq!(manifest["workflows"][0]["steps"], serde_json::json!(["wave", "greet"]));
+    assert!(common::bundle_entries(&bundle).contains(&"implementations/workflows/Onboard.js".to_string()));
+}
+
+#[test]
+fn undefined_steps_are_errors() {
+    let project = Project::new()
+        .file("src/greet.ts", &common::tool_source("greet"))
+        .file("src/onboard.ts", &workflow(r#"["greet", "missing"]"#));
+
+    project.arc().args(["check", "."])
+        .assert()
+        .failure()
+        .stderr(contains("Workflow 'Onboard' step references undefined name: 'missing'")
+            .and(contains("'greet'").not()));
+}
+
+#[test]
+fn manifests_without_workflows_still_package() {
+    let project = Project::new()
+        .file("manifest.json", &serde_json::json!({
+            "manifest_version": 3,
+            "name": "app",
+            "version": "1.0.0",
+            "tools": [{ "name": "greet", "description": "greets", "inputs": {} }],
+            "agents": [], "teams": [], "pipelines": [],
+        }).to_string())
+        .file("js/greet.js", "export function greet(input) { return input; }\n");
+
+    project.arc().args(["package", "-m", "manifest.json", "--impl-dir", "js"]).assert().success();
+
+    assert_eq!(common::bundle_manifest(&project.join("dist/app.aria"))["workflows"], serde_json::json!([]));
+}